@@ -0,0 +1,97 @@
+use bb_vpki::blockchain::{BlockchainTransaction, TransactionType};
+use bb_vpki::network::{DynLedgerGateway, LedgerGateway};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::Arc;
+use std::time::Instant;
+
+// Kept out of the benchmark binary: the counting allocator would otherwise
+// sit under every other benchmark's allocations.
+
+thread_local! {
+    static THREAD_ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// System allocator that counts allocations per thread, so the gateway
+/// dispatch benchmark can attribute allocations to the submits it awaits.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = THREAD_ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn thread_allocations() -> u64 {
+    THREAD_ALLOCATIONS.with(Cell::get)
+}
+
+/// Accepts every submit instantly, isolating the cost of dispatch itself.
+struct NullGateway;
+
+impl LedgerGateway for NullGateway {
+    async fn connect(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn submit_transaction(&self, _tx: &BlockchainTransaction) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Allocations and time per submit, calling the gateway directly through
+/// `LedgerGateway` versus through the boxing `DynLedgerGateway` adapter.
+/// Submits are awaited inline so every allocation lands on this thread.
+async fn benchmark_gateway_dispatch(submits: u32) -> ((f64, f64), (f64, f64)) {
+    let gateway = NullGateway;
+    let dyn_gateway: Arc<dyn DynLedgerGateway> = Arc::new(NullGateway);
+    let tx = BlockchainTransaction::new(
+        "DISPATCH-BENCH".to_string(),
+        TransactionType::CertificateIssuance,
+        vec![],
+    );
+
+    let (allocs_before, start) = (thread_allocations(), Instant::now());
+    for _ in 0..submits {
+        let _ = LedgerGateway::submit_transaction(&gateway, &tx).await;
+    }
+    let native = (
+        (thread_allocations() - allocs_before) as f64 / submits as f64,
+        start.elapsed().as_nanos() as f64 / submits as f64,
+    );
+
+    let (allocs_before, start) = (thread_allocations(), Instant::now());
+    for _ in 0..submits {
+        let _ = dyn_gateway.submit_transaction(&tx).await;
+    }
+    let boxed = (
+        (thread_allocations() - allocs_before) as f64 / submits as f64,
+        start.elapsed().as_nanos() as f64 / submits as f64,
+    );
+
+    (native, boxed)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    println!("Gateway Submit Dispatch\n");
+
+    let ((native_allocs, native_ns), (boxed_allocs, boxed_ns)) =
+        benchmark_gateway_dispatch(100_000).await;
+    println!(
+        "  → native async: {:.2} allocs/submit, {:.1} ns/submit",
+        native_allocs, native_ns
+    );
+    println!(
+        "  → dyn adapter:  {:.2} allocs/submit, {:.1} ns/submit",
+        boxed_allocs, boxed_ns
+    );
+}
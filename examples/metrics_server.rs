@@ -0,0 +1,39 @@
+use bb_vpki::metrics::PerformanceMetrics;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serves the latest benchmark results on `/metrics` for Prometheus to
+/// scrape. `metrics.json` is re-read on every request, so a benchmark
+/// running alongside shows up as soon as it saves.
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:9898").await?;
+    println!("Serving metrics on http://127.0.0.1:9898/metrics");
+
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let Ok(n) = stream.read(&mut buf).await else {
+                return;
+            };
+            let request = String::from_utf8_lossy(&buf[..n]);
+
+            let response = if request.starts_with("GET /metrics ") {
+                let metrics = std::fs::read_to_string("metrics.json")
+                    .ok()
+                    .and_then(|json| PerformanceMetrics::from_json_str(&json).ok())
+                    .unwrap_or_default();
+                let body = metrics.to_prometheus();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string()
+            };
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
@@ -1,15 +1,87 @@
+use super::genesis::GenesisConfig;
+use super::merkle::merkle_root;
 use super::transaction::BlockchainTransaction;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block {
     pub index: u64,
+    /// When the block was sealed, i.e. proof-of-work finished. Only known
+    /// once the proof-of-work hash is found, so it is folded into `hash`
+    /// afterwards; see `BlockHeader::is_valid`.
     pub timestamp: DateTime<Utc>,
+    /// When proof-of-work began; covered by the proof-of-work hash.
+    #[serde(default)]
+    pub mining_started_at: DateTime<Utc>,
     pub transactions: Vec<BlockchainTransaction>,
     pub previous_hash: String,
     pub hash: String,
     pub nonce: u64,
+    /// Proof-of-work difficulty this block was mined at.
+    #[serde(default)]
+    pub difficulty: u32,
+    /// Root of the Merkle tree over `transactions`; the block hash commits to
+    /// the transactions through it.
+    #[serde(default)]
+    pub merkle_root: String,
+}
+
+/// The hashed part of a block, without its transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub index: u64,
+    #[serde(default)]
+    pub timestamp: DateTime<Utc>,
+    pub mining_started_at: DateTime<Utc>,
+    pub previous_hash: String,
+    pub merkle_root: String,
+    pub nonce: u64,
+    pub difficulty: u32,
+    pub hash: String,
+}
+
+impl BlockHeader {
+    /// Everything hashed except the trailing nonce.
+    pub(crate) fn hash_prefix(&self) -> String {
+        format!(
+            "{}{}{}{}",
+            self.index, self.mining_started_at, self.merkle_root, self.previous_hash
+        )
+    }
+
+    /// Hash the consensus engine searched for: everything but the seal time.
+    pub fn pow_hash(&self) -> String {
+        hash_with_nonce(&self.hash_prefix(), self.nonce)
+    }
+
+    /// The proof-of-work hash meets the difficulty and `hash` commits to it
+    /// and the seal time.
+    pub fn is_valid(&self) -> bool {
+        let pow_hash = self.pow_hash();
+        meets_difficulty(&pow_hash, self.difficulty)
+            && seal_hash(&pow_hash, &self.timestamp) == self.hash
+    }
+}
+
+/// Block hash over the proof-of-work hash and the time the block was sealed.
+pub(crate) fn seal_hash(pow_hash: &str, sealed_at: &DateTime<Utc>) -> String {
+    format!(
+        "{:x}",
+        Sha256::digest(format!("{}{}", pow_hash, sealed_at).as_bytes())
+    )
+}
+
+pub(crate) fn hash_with_nonce(prefix: &str, nonce: u64) -> String {
+    format!(
+        "{:x}",
+        Sha256::digest(format!("{}{}", prefix, nonce).as_bytes())
+    )
+}
+
+pub(crate) fn meets_difficulty(hash: &str, difficulty: u32) -> bool {
+    hash.starts_with(&"0".repeat(difficulty as usize))
 }
 
 impl Block {
@@ -17,25 +89,67 @@ impl Block {
         index: u64,
         transactions: Vec<BlockchainTransaction>,
         previous_hash: String,
+        difficulty: u32,
+        mining_started_at: DateTime<Utc>,
     ) -> Self {
         Self {
             index,
-            timestamp: Utc::now(),
+            merkle_root: merkle_root(&transactions),
+            timestamp: mining_started_at,
+            mining_started_at,
             transactions,
             previous_hash,
             hash: String::new(),
             nonce: 0,
+            difficulty,
         }
     }
 
+    /// Stamps the seal time once the consensus engine has filled in the
+    /// nonce and proof-of-work hash, and replaces `hash` with one that also
+    /// commits to the seal time.
+    pub fn stamp_seal_time(&mut self, sealed_at: DateTime<Utc>) {
+        self.timestamp = sealed_at;
+        self.hash = seal_hash(&self.hash, &sealed_at);
+    }
+
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            index: self.index,
+            timestamp: self.timestamp,
+            mining_started_at: self.mining_started_at,
+            previous_hash: self.previous_hash.clone(),
+            merkle_root: self.merkle_root.clone(),
+            nonce: self.nonce,
+            difficulty: self.difficulty,
+            hash: self.hash.clone(),
+        }
+    }
+
+    /// Whether `merkle_root` still matches the block's transactions.
+    pub fn verify_merkle_root(&self) -> bool {
+        self.merkle_root == merkle_root(&self.transactions)
+    }
+
     pub fn genesis() -> Self {
+        let now = Utc::now();
         Self {
             index: 0,
-            timestamp: Utc::now(),
+            timestamp: now,
+            mining_started_at: now,
             transactions: vec![],
             previous_hash: "0".to_string(),
             hash: "genesis_hash".to_string(),
             nonce: 0,
+            difficulty: 0,
+            merkle_root: merkle_root(&[]),
         }
     }
+
+    /// Deterministic genesis block for a named network, so nodes can check
+    /// they were handed a chain from the network they expect.
+    /// See `GenesisConfig` to also bake in trusted CA keys.
+    pub fn genesis_for_network(network_id: &str) -> Self {
+        GenesisConfig::new(network_id).build_block()
+    }
 }
@@ -1,46 +1,592 @@
-use super::block::Block;
-use super::transaction::BlockchainTransaction;
+use super::block::{Block, meets_difficulty, seal_hash};
+use super::codec;
+use super::consensus::{ConsensusEngine, ProofOfWork};
+use super::index::StatusIndex;
+use super::merkle::{InclusionProof, merkle_root};
+use super::miner::{MineTrigger, MinerHandle};
+use super::transaction::{BlockchainTransaction, TransactionPriority, TransactionType};
+use crate::pki::CertificateStatus;
+use crate::task::TaskRegistry;
+use crate::task::registry::cancellation;
+use crate::time::{Clock, SystemClock};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::Mutex as StdMutex;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use thiserror::Error;
+use tokio::sync::{Mutex, Notify, RwLock, broadcast, watch};
+
+/// Chooses the proof-of-work difficulty for a block from its contents.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DifficultyPolicy {
+    /// Every block is mined at the chain's base difficulty.
+    Uniform,
+    /// Pending revocations are mined in a block of their own at
+    /// `revocation_difficulty` so safety-critical revocations commit sooner;
+    /// every block carrying anything else keeps the base difficulty.
+    FastRevocation { revocation_difficulty: u32 },
+}
+
+/// Which transactions `Blockchain::add_transaction` lets into the pending pool.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TransactionPolicy {
+    /// Every transaction is accepted, signed or not.
+    #[default]
+    AcceptAll,
+    /// Only transactions signed by one of `trusted_keys`, e.g. the CAs' keys.
+    RequireSignature { trusted_keys: Vec<Vec<u8>> },
+}
+
+/// Why `Blockchain::try_add_transaction` refused a transaction.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TransactionRejection {
+    #[error("transaction {0} is not signed")]
+    Unsigned(String),
+    #[error("transaction {0} is not signed by a trusted key")]
+    InvalidSignature(String),
+}
+
+/// Blocks mined between difficulty retargets unless set with
+/// `Blockchain::with_retarget_interval`.
+pub const DEFAULT_RETARGET_INTERVAL: usize = 10;
+
+/// Block events buffered per subscriber; slower subscribers skip ahead and
+/// see `RecvError::Lagged` with the number missed.
+pub const BLOCK_EVENT_CAPACITY: usize = 256;
+
+/// Lowest difficulty retargeting will lower the chain to.
+const MIN_RETARGET_DIFFICULTY: u32 = 1;
+
+/// Cumulative counts from `mine_pending_transactions`, for judging whether
+/// blocks are underfilled or saturated.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MiningStats {
+    /// Calls that found pending transactions to mine.
+    pub mine_calls: u64,
+    pub blocks_mined: u64,
+    pub transactions_mined: u64,
+    /// Most transactions a block may carry; `None` while blocks are unbounded.
+    pub block_capacity: Option<usize>,
+}
+
+impl MiningStats {
+    pub fn blocks_per_mine(&self) -> f64 {
+        if self.mine_calls == 0 {
+            return 0.0;
+        }
+        self.blocks_mined as f64 / self.mine_calls as f64
+    }
+
+    pub fn avg_txs_per_block(&self) -> f64 {
+        if self.blocks_mined == 0 {
+            return 0.0;
+        }
+        self.transactions_mined as f64 / self.blocks_mined as f64
+    }
+
+    /// Average share of the block capacity used; undefined without a capacity.
+    pub fn avg_block_fill_ratio(&self) -> Option<f64> {
+        self.block_capacity
+            .map(|capacity| self.avg_txs_per_block() / capacity as f64)
+    }
+}
+
+/// A block found by `Blockchain::get_block_by_index` or `get_block_by_hash`.
+#[derive(Debug, Clone)]
+pub enum BlockLookup {
+    Found(Block),
+    /// The block existed but was pruned; only its hash was kept.
+    Pruned {
+        index: u64,
+        hash: String,
+    },
+}
+
+/// The block produced by `Blockchain::mine_pending_transactions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MineResult {
+    pub block_index: u64,
+    pub tx_count: usize,
+    pub nonce: u64,
+    /// Sealing time, as recorded for consensus latency.
+    pub elapsed_ms: u128,
+}
+
+/// Sent to `Blockchain::subscribe` receivers for every mined block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEvent {
+    pub index: u64,
+    pub hash: String,
+    pub tx_count: usize,
+    /// When the block was sealed.
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// What `Blockchain::compact` removed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactionReport {
+    pub blocks_removed: usize,
+    pub transactions_before: usize,
+    pub transactions_after: usize,
+    /// Ids of the superseded transactions dropped, one entry per transaction.
+    pub compacted_tx_ids: Vec<String>,
+    pub size_before_bytes: usize,
+    pub size_after_bytes: usize,
+    /// Hash of the old tip, which the compaction block extends.
+    pub checkpoint_hash: String,
+}
+
+/// Why `Blockchain::rollback_to` refused to truncate the chain.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RollbackError {
+    #[error("cannot roll back to block {index}: the tip is block {tip}")]
+    BeyondTip { index: u64, tip: u64 },
+    #[error("cannot roll back to block {index}: blocks before {first_retained} were pruned")]
+    BelowRetained { index: u64, first_retained: u64 },
+}
+
+/// First integrity violation `Blockchain::validate_chain` found.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ChainError {
+    #[error("block {index}: stored hash does not match its recomputed header hash")]
+    HashMismatch { index: u64 },
+    #[error("block {index}: previous_hash does not match the preceding block")]
+    BrokenLink { index: u64 },
+    #[error("block {index}: Merkle root does not match its transactions")]
+    MerkleRootMismatch { index: u64 },
+    #[error("block {index}: mined at difficulty {actual}, below the required {required}")]
+    InsufficientDifficulty {
+        index: u64,
+        actual: u32,
+        required: u32,
+    },
+    #[error("block {index}: hash does not meet its difficulty")]
+    InvalidProofOfWork { index: u64 },
+    #[error("block {index}: expected index {expected}")]
+    IndexGap { index: u64, expected: u64 },
+}
 
 pub struct Blockchain {
     pub chain: Arc<RwLock<Vec<Block>>>,
     pending_transactions: Arc<RwLock<Vec<BlockchainTransaction>>>,
-    difficulty: u32,
+    /// Base difficulty; moved by retargeting when a target block time is set.
+    difficulty: AtomicU32,
+    difficulty_policy: DifficultyPolicy,
+    transaction_policy: TransactionPolicy,
+    rejected_transactions: Arc<AtomicU64>,
+    target_block_time: Option<std::time::Duration>,
+    retarget_interval: usize,
+    /// Proof-of-work times of the blocks mined since the last retarget.
+    // std mutex: never held across an await
+    retarget_window: StdMutex<Vec<std::time::Duration>>,
     pruned_blocks: Arc<RwLock<HashMap<u64, String>>>,
     archived_certs: Arc<RwLock<HashMap<String, String>>>,
+    /// Sealing time of each mined block, i.e. one consensus round, recorded
+    /// once per transaction.
     consensus_latencies_ms: Arc<RwLock<Vec<u128>>>,
+    /// Time each transaction spent in the pending pool before mining began.
+    queue_wait_ms: Arc<RwLock<Vec<u128>>>,
+    mining_stats: Arc<RwLock<MiningStats>>,
+    /// Default deadline for pending transactions, counted from enqueueing.
+    transaction_ttl: Option<chrono::Duration>,
+    /// Ids of transactions dropped from the pool past their deadline.
+    expired_transactions: Arc<RwLock<Vec<String>>>,
+    pending_notify: Arc<Notify>,
+    block_events: broadcast::Sender<BlockEvent>,
+    clock: Arc<dyn Clock>,
+    consensus: Arc<dyn ConsensusEngine>,
+    max_chain_len: Option<usize>,
+    /// Most transactions moved into one block; the rest wait for the next.
+    max_txs_per_block: Option<usize>,
+    /// Always locked after `chain` when both are needed.
+    status_index: Arc<RwLock<StatusIndex>>,
+    /// Held by anything that builds on or replaces the tip, from reading it
+    /// until the chain is updated, so two miners never extend the same tip.
+    /// Always locked before `chain`.
+    mining: Arc<Mutex<()>>,
+    tasks: Option<Arc<TaskRegistry>>,
 }
 
 impl Blockchain {
     pub fn new(difficulty: u32) -> Self {
-        let genesis = Block::genesis();
+        Self::with_genesis(difficulty, Block::genesis())
+    }
 
+    /// A chain whose blocks are sealed by `engine`, starting at the engine's
+    /// difficulty.
+    pub fn new_with_consensus(engine: Arc<dyn ConsensusEngine>) -> Self {
+        let mut chain = Self::new(engine.difficulty());
+        chain.consensus = engine;
+        chain
+    }
+
+    pub fn with_genesis(difficulty: u32, genesis: Block) -> Self {
         Self {
             chain: Arc::new(RwLock::new(vec![genesis])),
             pending_transactions: Arc::new(RwLock::new(vec![])),
-            difficulty,
+            difficulty: AtomicU32::new(difficulty),
+            difficulty_policy: DifficultyPolicy::Uniform,
+            transaction_policy: TransactionPolicy::AcceptAll,
+            rejected_transactions: Arc::new(AtomicU64::new(0)),
+            target_block_time: None,
+            retarget_interval: DEFAULT_RETARGET_INTERVAL,
+            retarget_window: StdMutex::new(Vec::new()),
             pruned_blocks: Arc::new(RwLock::new(HashMap::new())),
             archived_certs: Arc::new(RwLock::new(HashMap::new())),
             consensus_latencies_ms: Arc::new(RwLock::new(Vec::new())),
+            queue_wait_ms: Arc::new(RwLock::new(Vec::new())),
+            mining_stats: Arc::new(RwLock::new(MiningStats::default())),
+            transaction_ttl: None,
+            expired_transactions: Arc::new(RwLock::new(Vec::new())),
+            pending_notify: Arc::new(Notify::new()),
+            block_events: broadcast::channel(BLOCK_EVENT_CAPACITY).0,
+            clock: Arc::new(SystemClock),
+            consensus: Arc::new(ProofOfWork::new(difficulty)),
+            max_chain_len: None,
+            max_txs_per_block: None,
+            status_index: Arc::new(RwLock::new(StatusIndex::default())),
+            mining: Arc::new(Mutex::new(())),
+            tasks: None,
+        }
+    }
+
+    /// A chain that retargets its difficulty toward one block per `target`:
+    /// every `retarget_interval` blocks the average proof-of-work time is
+    /// compared with `target` and the difficulty moves by one step toward
+    /// it, never below 1.
+    pub fn with_target_block_time(difficulty: u32, target: std::time::Duration) -> Self {
+        let mut chain = Self::new(difficulty);
+        chain.target_block_time = Some(target);
+        chain
+    }
+
+    /// Blocks mined between retargets; see `with_target_block_time`.
+    pub fn with_retarget_interval(mut self, blocks: usize) -> Self {
+        self.retarget_interval = blocks.max(1);
+        self
+    }
+
+    /// Base difficulty new blocks are mined at, before the difficulty policy.
+    pub fn current_difficulty(&self) -> u32 {
+        self.difficulty.load(Ordering::Relaxed)
+    }
+
+    /// Records one block's proof-of-work time and, once a full interval has
+    /// been mined, moves the difficulty one step toward the target.
+    fn record_block_time(&self, elapsed: std::time::Duration) {
+        let Some(target) = self.target_block_time else {
+            return;
+        };
+        let mut window = self.retarget_window.lock().unwrap();
+        window.push(elapsed);
+        if window.len() < self.retarget_interval {
+            return;
+        }
+        let average = window.iter().sum::<std::time::Duration>() / window.len() as u32;
+        window.clear();
+        drop(window);
+
+        let current = self.current_difficulty();
+        let next = match average.cmp(&target) {
+            CmpOrdering::Less => current.saturating_add(1),
+            CmpOrdering::Greater => current.saturating_sub(1).max(MIN_RETARGET_DIFFICULTY),
+            CmpOrdering::Equal => current,
+        };
+        if next != current {
+            log::info!(
+                "retarget: average block time {:?} vs target {:?}, difficulty {} -> {}",
+                average,
+                target,
+                current,
+                next
+            );
+            self.difficulty.store(next, Ordering::Relaxed);
+        }
+    }
+
+    /// Replaces the wall clock used to timestamp mined blocks.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Prunes automatically after mining whenever the chain holds more than
+    /// `max_len` blocks, keeping genesis and the most recent blocks. Pruned
+    /// block hashes are kept as with `prune_old_blocks`.
+    pub fn with_max_chain_len(mut self, max_len: usize) -> Self {
+        self.max_chain_len = Some(max_len.max(2));
+        self
+    }
+
+    pub fn max_chain_len(&self) -> Option<usize> {
+        self.max_chain_len
+    }
+
+    /// Caps each block at `max_txs` transactions, highest priority first;
+    /// the overflow waits for the next block.
+    pub fn with_max_txs_per_block(mut self, max_txs: usize) -> Self {
+        self.max_txs_per_block = Some(max_txs.max(1));
+        self
+    }
+
+    /// Gives every transaction added without its own deadline one `ttl` after
+    /// it enters the pending pool, so a stalled miner cannot commit it late.
+    pub fn with_transaction_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.transaction_ttl = chrono::Duration::from_std(ttl).ok();
+        self
+    }
+
+    /// Registers background miners with `tasks`, which can stop them.
+    pub fn with_task_registry(mut self, tasks: Arc<TaskRegistry>) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+
+    pub fn with_difficulty_policy(mut self, policy: DifficultyPolicy) -> Self {
+        self.difficulty_policy = policy;
+        self
+    }
+
+    pub fn with_transaction_policy(mut self, policy: TransactionPolicy) -> Self {
+        self.transaction_policy = policy;
+        self
+    }
+
+    fn difficulty_for(&self, transactions: &[BlockchainTransaction]) -> u32 {
+        self.difficulty_from(self.current_difficulty(), transactions)
+    }
+
+    fn difficulty_from(&self, base: u32, transactions: &[BlockchainTransaction]) -> u32 {
+        match self.difficulty_policy {
+            DifficultyPolicy::Uniform => base,
+            DifficultyPolicy::FastRevocation {
+                revocation_difficulty,
+            } => {
+                let only_revocations = !transactions.is_empty()
+                    && transactions
+                        .iter()
+                        .all(|tx| matches!(tx.tx_type, TransactionType::CertificateRevocation));
+                if only_revocations {
+                    revocation_difficulty.min(base)
+                } else {
+                    base
+                }
+            }
         }
     }
 
+    /// Queues `tx` for mining. Transactions the transaction policy refuses
+    /// are dropped with a warning; use `try_add_transaction` to see why.
     pub async fn add_transaction(&self, tx: BlockchainTransaction) {
+        if let Err(rejection) = self.try_add_transaction(tx).await {
+            log::warn!("dropped transaction: {}", rejection);
+        }
+    }
+
+    /// Queues `tx` for mining ahead of any lower-priority transactions,
+    /// overriding the priority its type gives it.
+    pub async fn add_transaction_with_priority(
+        &self,
+        mut tx: BlockchainTransaction,
+        priority: TransactionPriority,
+    ) {
+        tx.priority = priority;
+        self.add_transaction(tx).await;
+    }
+
+    /// Queues `tx` for mining if the transaction policy accepts it.
+    pub async fn try_add_transaction(
+        &self,
+        mut tx: BlockchainTransaction,
+    ) -> Result<(), TransactionRejection> {
+        if let Err(rejection) = self.check_transaction(&tx) {
+            self.rejected_transactions.fetch_add(1, Ordering::Relaxed);
+            return Err(rejection);
+        }
+        let now = self.clock.now();
+        tx.enqueued_at = Some(now);
+        if tx.deadline.is_none() {
+            tx.deadline = self.transaction_ttl.map(|ttl| now + ttl);
+        }
         self.pending_transactions.write().await.push(tx);
+        self.pending_notify.notify_one();
+        Ok(())
+    }
+
+    fn check_transaction(&self, tx: &BlockchainTransaction) -> Result<(), TransactionRejection> {
+        match &self.transaction_policy {
+            TransactionPolicy::AcceptAll => Ok(()),
+            TransactionPolicy::RequireSignature { .. } if tx.signature.is_empty() => {
+                Err(TransactionRejection::Unsigned(tx.tx_id.clone()))
+            }
+            TransactionPolicy::RequireSignature { trusted_keys } => {
+                if trusted_keys.iter().any(|key| tx.verify(key)) {
+                    Ok(())
+                } else {
+                    Err(TransactionRejection::InvalidSignature(tx.tx_id.clone()))
+                }
+            }
+        }
+    }
+
+    /// Transactions refused by the transaction policy so far.
+    pub fn get_rejected_transaction_count(&self) -> u64 {
+        self.rejected_transactions.load(Ordering::Relaxed)
+    }
+
+    pub async fn genesis_hash(&self) -> String {
+        self.chain
+            .read()
+            .await
+            .first()
+            .map(|b| b.hash.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_pending_count(&self) -> usize {
+        self.pending_transactions.read().await.len()
+    }
+
+    /// Whether a transaction with `tx_id` has been mined into a block.
+    pub async fn is_confirmed(&self, tx_id: &str) -> bool {
+        self.chain
+            .read()
+            .await
+            .iter()
+            .any(|block| block.transactions.iter().any(|tx| tx.tx_id == tx_id))
+    }
+
+    /// Receives an event for every block mined from now on. A receiver that
+    /// falls more than `BLOCK_EVENT_CAPACITY` events behind loses the oldest
+    /// and gets `RecvError::Lagged`; mining never waits for subscribers.
+    pub fn subscribe(&self) -> broadcast::Receiver<BlockEvent> {
+        self.block_events.subscribe()
+    }
+
+    /// Starts a background task that mines automatically according to `trigger`.
+    /// Transactions arriving while a block is being mined are coalesced into
+    /// the next block rather than producing one block each.
+    pub fn spawn_miner(self: Arc<Self>, trigger: MineTrigger) -> MinerHandle {
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+        let pool_size = trigger.pool_size();
+        let period = trigger.interval();
+        let cancelled = cancellation(self.tasks.as_ref());
+        let tasks = self.tasks.clone();
+
+        let task = tokio::spawn(async move {
+            tokio::pin!(cancelled);
+            let mut ticker = period.map(tokio::time::interval);
+            // the first tick of a tokio interval completes immediately
+            if let Some(t) = ticker.as_mut() {
+                t.tick().await;
+            }
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => break,
+                    _ = &mut cancelled => break,
+                    _ = self.pending_notify.notified(), if pool_size.is_some() => {
+                        if self.get_pending_count().await >= pool_size.unwrap_or(1) {
+                            self.mine_all_pending().await;
+                            if let Some(t) = ticker.as_mut() {
+                                t.reset();
+                            }
+                        }
+                    }
+                    _ = async { ticker.as_mut().unwrap().tick().await }, if ticker.is_some() => {
+                        self.mine_pending_transactions().await;
+                    }
+                }
+            }
+
+            self.mine_all_pending().await;
+        });
+        if let Some(tasks) = tasks {
+            tasks.register("miner", &task);
+        }
+
+        MinerHandle { shutdown_tx, task }
+    }
+
+    /// Drops pending transactions whose deadline has passed, recording them
+    /// as expired. Returns how many were dropped. Mining runs this first.
+    pub async fn expire_stale_transactions(&self) -> usize {
+        let now = self.clock.now();
+        let mut txs = self.pending_transactions.write().await;
+        let (stale, fresh): (Vec<_>, Vec<_>) = txs
+            .drain(..)
+            .partition(|tx| tx.deadline.is_some_and(|deadline| deadline < now));
+        *txs = fresh;
+        drop(txs);
+
+        let count = stale.len();
+        if count > 0 {
+            self.expired_transactions
+                .write()
+                .await
+                .extend(stale.into_iter().map(|tx| tx.tx_id));
+        }
+        count
+    }
+
+    pub async fn expired_transaction_count(&self) -> usize {
+        self.expired_transactions.read().await.len()
+    }
+
+    /// Ids of expired transactions, oldest first.
+    pub async fn expired_transactions(&self) -> Vec<String> {
+        self.expired_transactions.read().await.clone()
+    }
+
+    /// Mines one block from the pending transactions, highest priority first
+    /// and otherwise in the order queued, up to the per-block cap.
+    /// Returns `None` without mining when nothing is pending.
+    pub async fn mine_pending_transactions(&self) -> Option<MineResult> {
+        let mined = self.mine_block().await?;
+        self.mining_stats.write().await.mine_calls += 1;
+        Some(mined)
+    }
+
+    /// Mines blocks until no transactions are pending; returns how many.
+    pub async fn mine_all_pending(&self) -> usize {
+        let mut blocks = 0;
+        while self.mine_block().await.is_some() {
+            blocks += 1;
+        }
+        if blocks > 0 {
+            self.mining_stats.write().await.mine_calls += 1;
+        }
+        blocks
     }
 
-    pub async fn mine_pending_transactions(&self) {
-        let pending = {
+    /// Mines a single block; `None` if nothing was pending.
+    async fn mine_block(&self) -> Option<MineResult> {
+        let _mining = self.mining.lock().await;
+        self.expire_stale_transactions().await;
+        let pending: Vec<BlockchainTransaction> = {
             let mut txs = self.pending_transactions.write().await;
             if txs.is_empty() {
-                return;
+                return None;
+            }
+            sort_for_mining(&mut txs);
+            let revocations = match self.difficulty_policy {
+                DifficultyPolicy::FastRevocation { .. } => take_fast_revocations(&mut txs),
+                DifficultyPolicy::Uniform => Vec::new(),
+            };
+            let mut batch = if revocations.is_empty() {
+                std::mem::take(&mut *txs)
+            } else {
+                revocations
+            };
+            if let Some(max) = self.max_txs_per_block
+                && batch.len() > max
+            {
+                let overflow = batch.split_off(max);
+                txs.splice(0..0, overflow);
             }
-            let pending = txs.clone();
-            txs.clear();
-            pending
+            batch
         };
 
         let chain = self.chain.read().await;
@@ -49,40 +595,164 @@ impl Blockchain {
         let previous_hash = previous_block.hash.clone();
         drop(chain);
 
-        let mut block = Block::new(index, pending, previous_hash);
-
-        loop {
-            let hash = self.calculate_hash(&block);
-            if self.is_valid_hash(&hash) {
-                block.hash = hash;
-                break;
-            }
-            block.nonce += 1;
-        }
+        let difficulty = self.difficulty_for(&pending);
+        let block = Block::new(index, pending, previous_hash, difficulty, self.clock.now());
+        let sealing = std::time::Instant::now();
+        let block = self.seal(block).await;
+        self.record_block_time(sealing.elapsed());
 
-        // calculate consensus latencies: difference between block timestamp and each tx timestamp
-        let mut latencies = Vec::new();
-        for tx in &block.transactions {
+        // consensus is the engine's sealing itself; time spent waiting in the
+        // pending pool beforehand is tracked separately as queue wait
+        let consensus_ms = block
+            .timestamp
+            .signed_duration_since(block.mining_started_at)
+            .num_milliseconds()
+            .max(0) as u128;
+        let queue_waits = block.transactions.iter().filter_map(|tx| {
+            let enqueued_at = tx.enqueued_at.unwrap_or(tx.timestamp);
             let diff = block
-                .timestamp
-                .signed_duration_since(tx.timestamp)
+                .mining_started_at
+                .signed_duration_since(enqueued_at)
                 .num_milliseconds();
-            if diff >= 0 {
-                latencies.push(diff as u128);
-            }
-        }
+            (diff >= 0).then_some(diff as u128)
+        });
 
-        if !latencies.is_empty() {
+        if !block.transactions.is_empty() {
             let mut stored = self.consensus_latencies_ms.write().await;
-            stored.extend(latencies);
-            // keep vector bounded to last 1000 entries to avoid unbounded growth
-            if stored.len() > 1000 {
-                let start = stored.len() - 1000;
-                *stored = stored[start..].to_vec();
-            }
+            push_bounded(
+                &mut stored,
+                std::iter::repeat_n(consensus_ms, block.transactions.len()),
+            );
+            drop(stored);
+            push_bounded(&mut *self.queue_wait_ms.write().await, queue_waits);
+        }
+
+        let mined = MineResult {
+            block_index: block.index,
+            tx_count: block.transactions.len(),
+            nonce: block.nonce,
+            elapsed_ms: consensus_ms,
+        };
+        let mut stats = self.mining_stats.write().await;
+        stats.blocks_mined += 1;
+        stats.transactions_mined += block.transactions.len() as u64;
+        drop(stats);
+
+        let event = BlockEvent {
+            index: block.index,
+            hash: block.hash.clone(),
+            tx_count: block.transactions.len(),
+            timestamp: block.timestamp,
+        };
+        let mut chain = self.chain.write().await;
+        self.status_index.write().await.apply_block(&block);
+        chain.push(block);
+        let over_limit = self.max_chain_len.is_some_and(|max| chain.len() > max);
+        drop(chain);
+
+        if over_limit && let Some(max) = self.max_chain_len {
+            // prune_old_blocks keeps genesis plus `keep_last_n` blocks
+            self.prune_old_blocks(max - 1).await;
+        }
+        // an error only means nobody is subscribed
+        let _ = self.block_events.send(event);
+        Some(mined)
+    }
+
+    /// Latest on-chain status of `cert_id`, from the incrementally maintained index.
+    pub async fn certificate_status(&self, cert_id: &str) -> Option<CertificateStatus> {
+        self.status_index.read().await.get(cert_id)
+    }
+
+    /// Merkle inclusion proof for the most recent on-chain transaction with `tx_id`.
+    pub async fn inclusion_proof(&self, tx_id: &str) -> Option<InclusionProof> {
+        let chain = self.chain.read().await;
+        chain.iter().rev().find_map(|block| {
+            let leaf_index = block
+                .transactions
+                .iter()
+                .rposition(|tx| tx.tx_id == tx_id)?;
+            Some(InclusionProof::new(
+                &block.transactions,
+                leaf_index,
+                block.header(),
+            ))
+        })
+    }
+
+    /// Merkle inclusion proof for the transaction `tx_id` in the block at
+    /// `block_index`: its sibling-hash path up to the block's Merkle root.
+    /// `None` if the block was pruned or does not carry the transaction.
+    pub async fn generate_merkle_proof(
+        &self,
+        block_index: u64,
+        tx_id: &str,
+    ) -> Option<InclusionProof> {
+        let chain = self.chain.read().await;
+        let block = chain.iter().find(|block| block.index == block_index)?;
+        let leaf_index = block.transactions.iter().position(|tx| tx.tx_id == tx_id)?;
+        Some(InclusionProof::new(
+            &block.transactions,
+            leaf_index,
+            block.header(),
+        ))
+    }
+
+    /// `None` if no block with `index` was ever on this chain.
+    pub async fn get_block_by_index(&self, index: u64) -> Option<BlockLookup> {
+        let chain = self.chain.read().await;
+        if let Some(block) = chain.iter().find(|block| block.index == index) {
+            return Some(BlockLookup::Found(block.clone()));
+        }
+        drop(chain);
+        self.pruned_blocks
+            .read()
+            .await
+            .get(&index)
+            .map(|hash| BlockLookup::Pruned {
+                index,
+                hash: hash.clone(),
+            })
+    }
+
+    /// `None` if no block with `hash` was ever on this chain.
+    pub async fn get_block_by_hash(&self, hash: &str) -> Option<BlockLookup> {
+        let chain = self.chain.read().await;
+        if let Some(block) = chain.iter().find(|block| block.hash == hash) {
+            return Some(BlockLookup::Found(block.clone()));
         }
+        drop(chain);
+        self.pruned_blocks
+            .read()
+            .await
+            .iter()
+            .find(|(_, pruned_hash)| pruned_hash.as_str() == hash)
+            .map(|(index, hash)| BlockLookup::Pruned {
+                index: *index,
+                hash: hash.clone(),
+            })
+    }
 
-        self.chain.write().await.push(block);
+    /// Most recent mined transaction for `cert_id`, e.g. its latest
+    /// revocation. Transactions in pruned blocks are no longer available.
+    pub async fn find_certificate_transaction(
+        &self,
+        cert_id: &str,
+    ) -> Option<BlockchainTransaction> {
+        self.chain
+            .read()
+            .await
+            .iter()
+            .rev()
+            .flat_map(|block| block.transactions.iter().rev())
+            .find(|tx| tx.tx_id == cert_id)
+            .cloned()
+    }
+
+    /// Rebuilds the status index from scratch from the current chain.
+    pub async fn build_status_index(&self) {
+        let chain = self.chain.read().await;
+        *self.status_index.write().await = StatusIndex::build(chain.iter());
     }
 
     pub async fn get_average_consensus_latency_ms(&self) -> f64 {
@@ -94,47 +764,177 @@ impl Blockchain {
         (sum as f64) / (stored.len() as f64)
     }
 
+    /// Recorded consensus latencies, once per mined transaction, oldest first.
+    pub async fn get_consensus_latencies_ms(&self) -> Vec<u128> {
+        self.consensus_latencies_ms.read().await.clone()
+    }
+
     pub async fn get_consensus_percentiles_ms(&self) -> (f64, f64, f64) {
-        let stored = self.consensus_latencies_ms.read().await;
+        percentiles(&self.consensus_latencies_ms.read().await)
+    }
+
+    pub async fn get_average_queue_wait_ms(&self) -> f64 {
+        let stored = self.queue_wait_ms.read().await;
         if stored.is_empty() {
-            return (0.0, 0.0, 0.0);
-        }
-        // work on a sorted copy
-        let mut vals: Vec<u128> = stored.clone();
-        vals.sort();
-        let n = vals.len();
-        let p = |quant: f64| -> usize {
-            let idx = (quant * n as f64).ceil() as isize - 1;
-            if idx < 0 {
-                0usize
-            } else if (idx as usize) >= n {
-                n - 1
-            } else {
-                idx as usize
-            }
+            return 0.0;
+        }
+        let sum: u128 = stored.iter().sum();
+        (sum as f64) / (stored.len() as f64)
+    }
+
+    pub async fn get_queue_wait_percentiles_ms(&self) -> (f64, f64, f64) {
+        percentiles(&self.queue_wait_ms.read().await)
+    }
+
+    pub async fn get_mining_stats(&self) -> MiningStats {
+        MiningStats {
+            block_capacity: self.max_txs_per_block,
+            ..*self.mining_stats.read().await
+        }
+    }
+
+    /// Has the consensus engine seal the block and stamps it with the time
+    /// it was sealed.
+    async fn seal(&self, mut block: Block) -> Block {
+        let proof = self.consensus.seal_block(&mut block).await;
+        log::debug!("sealed block {}: {:?}", block.index, proof);
+        block.stamp_seal_time(self.clock.now());
+        block
+    }
+
+    /// Fork choice. Adopts `candidate` in place of the local chain when it is
+    /// valid, shares our genesis, and carries more proof-of-work since the
+    /// fork point, or the same work with a numerically smaller tip hash. The
+    /// tie-break depends only on the two chains, so every node shown the same
+    /// pair of forks picks the same one. Returns whether the candidate was
+    /// adopted.
+    pub async fn consider_chain(&self, candidate: Vec<Block>) -> bool {
+        if !self.is_valid_chain(&candidate) {
+            return false;
+        }
+
+        let _mining = self.mining.lock().await;
+        let mut chain = self.chain.write().await;
+        if chain.first().map(|b| &b.hash) != candidate.first().map(|b| &b.hash) {
+            return false;
+        }
+        let (Some(ours), Some(theirs)) = (chain.last(), candidate.last()) else {
+            return false;
         };
 
-        let p50 = vals[p(0.50)] as f64;
-        let p95 = vals[p(0.95)] as f64;
-        let p99 = vals[p(0.99)] as f64;
+        let fork = fork_point(&chain, &candidate);
+        let our_work = chain_work(chain.iter().filter(|b| b.index > fork));
+        let their_work = chain_work(candidate.iter().filter(|b| b.index > fork));
+        let adopt = match their_work.cmp(&our_work) {
+            CmpOrdering::Greater => true,
+            CmpOrdering::Equal => cmp_hash_values(&theirs.hash, &ours.hash) == CmpOrdering::Less,
+            CmpOrdering::Less => false,
+        };
+        if adopt {
+            let mut index = self.status_index.write().await;
+            Self::reorg_index(&mut index, &chain, &candidate);
+            *chain = candidate;
+        }
+        adopt
+    }
+
+    /// Moves the index from `old` to `new`: rolls back the blocks orphaned
+    /// since the fork point, newest first, then applies the new branch. Falls
+    /// back to a full rebuild when the fork point lies in a pruned range.
+    fn reorg_index(index: &mut StatusIndex, old: &[Block], new: &[Block]) {
+        let fork = fork_point(old, new);
+        let orphaned: Vec<&Block> = old.iter().filter(|b| b.index > fork).collect();
 
-        (p50, p95, p99)
+        let contiguous = orphaned
+            .iter()
+            .enumerate()
+            .all(|(i, b)| b.index == fork + 1 + i as u64);
+        if !contiguous || !orphaned.iter().rev().all(|b| index.rollback_block(b)) {
+            *index = StatusIndex::build(new.iter());
+            return;
+        }
+        for block in &new[fork as usize + 1..] {
+            index.apply_block(block);
+        }
     }
 
-    fn calculate_hash(&self, block: &Block) -> String {
-        let data = format!(
-            "{}{}{}{}{}",
-            block.index,
-            block.timestamp,
-            serde_json::to_string(&block.transactions).unwrap(),
-            block.previous_hash,
-            block.nonce
-        );
-        format!("{:x}", Sha256::digest(data.as_bytes()))
+    /// Re-checks every block: recomputed header hash, link to the previous
+    /// block, Merkle root, and proof-of-work at no less than the difficulty
+    /// this chain would require for the block's transactions. Links into a
+    /// pruned range are checked against the recorded hashes of pruned blocks.
+    /// Genesis is only checked for its Merkle root; its hash is not mined.
+    pub async fn validate_chain(&self) -> Result<(), ChainError> {
+        let chain = self.chain.read().await;
+        let pruned = self.pruned_blocks.read().await;
+
+        for (position, block) in chain.iter().enumerate() {
+            let index = block.index;
+            if !block.verify_merkle_root() {
+                return Err(ChainError::MerkleRootMismatch { index });
+            }
+            let Some(prev) = position.checked_sub(1).map(|p| &chain[p]) else {
+                continue;
+            };
+
+            let previous_hash = if index == prev.index + 1 {
+                &prev.hash
+            } else if let Some(hash) = index.checked_sub(1).and_then(|i| pruned.get(&i)) {
+                hash
+            } else {
+                return Err(ChainError::IndexGap {
+                    index,
+                    expected: prev.index + 1,
+                });
+            };
+            if &block.previous_hash != previous_hash {
+                return Err(ChainError::BrokenLink { index });
+            }
+
+            let pow_hash = block.header().pow_hash();
+            if seal_hash(&pow_hash, &block.timestamp) != block.hash {
+                return Err(ChainError::HashMismatch { index });
+            }
+            let required = self.required_difficulty(&block.transactions);
+            if block.difficulty < required {
+                return Err(ChainError::InsufficientDifficulty {
+                    index,
+                    actual: block.difficulty,
+                    required,
+                });
+            }
+            if !meets_difficulty(&pow_hash, block.difficulty) {
+                return Err(ChainError::InvalidProofOfWork { index });
+            }
+        }
+        Ok(())
     }
 
-    fn is_valid_hash(&self, hash: &str) -> bool {
-        hash.starts_with(&"0".repeat(self.difficulty as usize))
+    /// Least difficulty `validate_chain` and fork choice accept for a block
+    /// carrying `transactions`. With retargeting, blocks mined before the
+    /// difficulty last rose are legitimately below it, so only the retarget
+    /// floor is required.
+    fn required_difficulty(&self, transactions: &[BlockchainTransaction]) -> u32 {
+        let base = if self.target_block_time.is_some() {
+            MIN_RETARGET_DIFFICULTY
+        } else {
+            self.current_difficulty()
+        };
+        self.difficulty_from(base, transactions)
+    }
+
+    /// Checks links, recomputed hashes, and proof-of-work at no less than the
+    /// required difficulty for every block after genesis. Pruned chains are
+    /// rejected since their links cannot be checked.
+    fn is_valid_chain(&self, blocks: &[Block]) -> bool {
+        !blocks.is_empty()
+            && blocks.windows(2).all(|pair| {
+                let (prev, block) = (&pair[0], &pair[1]);
+                block.index == prev.index + 1
+                    && block.previous_hash == prev.hash
+                    && block.merkle_root == merkle_root(&block.transactions)
+                    && block.difficulty >= self.required_difficulty(&block.transactions)
+                    && block.header().is_valid()
+            })
     }
 
     pub async fn prune_old_blocks(&self, keep_last_n: usize) -> usize {
@@ -148,9 +948,11 @@ impl Blockchain {
         let to_prune = chain_len - keep_last_n;
         let mut pruned = self.pruned_blocks.write().await;
 
+        let mut index = self.status_index.write().await;
         for i in 1..to_prune {
             if let Some(block) = chain.get(i) {
                 pruned.insert(block.index, block.hash.clone());
+                index.forget_undo(&block.hash);
             }
         }
 
@@ -158,13 +960,172 @@ impl Blockchain {
         to_prune - 1
     }
 
+    /// Truncates the chain so block `index` is the tip, e.g. after a bad block
+    /// is found. Returns the removed blocks, oldest first. Their transactions
+    /// go back to the front of the pending pool, minus any past their
+    /// deadline, to be mined again. Pruned history cannot be restored, so
+    /// `index` may not lie below the first retained block after genesis.
+    pub async fn rollback_to(&self, index: u64) -> Result<Vec<Block>, RollbackError> {
+        let _mining = self.mining.lock().await;
+        let mut chain = self.chain.write().await;
+        let tip = chain.last().unwrap().index;
+        if index > tip {
+            return Err(RollbackError::BeyondTip { index, tip });
+        }
+        if let Some(first_retained) = chain.get(1).map(|b| b.index).filter(|&i| i > 1)
+            && index < first_retained
+        {
+            return Err(RollbackError::BelowRetained {
+                index,
+                first_retained,
+            });
+        }
+
+        let keep = chain.iter().take_while(|b| b.index <= index).count();
+        let removed: Vec<Block> = chain.drain(keep..).collect();
+        if removed.is_empty() {
+            return Ok(removed);
+        }
+
+        let mut status_index = self.status_index.write().await;
+        if !removed.iter().rev().all(|b| status_index.rollback_block(b)) {
+            *status_index = StatusIndex::build(chain.iter());
+        }
+        drop(status_index);
+        drop(chain);
+
+        let now = self.clock.now();
+        let requeued: Vec<BlockchainTransaction> = removed
+            .iter()
+            .flat_map(|b| b.transactions.iter())
+            .filter(|tx| tx.deadline.is_none_or(|deadline| deadline >= now))
+            .cloned()
+            .collect();
+        if !requeued.is_empty() {
+            self.pending_transactions
+                .write()
+                .await
+                .splice(0..0, requeued);
+            self.pending_notify.notify_one();
+        }
+
+        Ok(removed)
+    }
+
+    /// Rewrites everything after genesis as a single block holding only the
+    /// latest transaction per certificate, the newest issuance or renewal
+    /// carrying each certificate (so edge nodes can still verify it), and
+    /// every non-certificate transaction such as key rotations. The new block
+    /// links to the old tip, which serves as the checkpoint of the compacted
+    /// history; the removed blocks' hashes are kept as with
+    /// `prune_old_blocks`. Current-status queries answer as before. Holds the
+    /// chain lock while mining the block.
+    pub async fn compact(&self) -> CompactionReport {
+        let _mining = self.mining.lock().await;
+        let mut chain = self.chain.write().await;
+        let size_before_bytes = codec::encode(&*chain).unwrap_or_default().len();
+        let tip = chain.last().unwrap();
+        let checkpoint_hash = tip.hash.clone();
+        let next_index = tip.index + 1;
+
+        let history: Vec<&BlockchainTransaction> = chain
+            .iter()
+            .skip(1)
+            .flat_map(|b| b.transactions.iter())
+            .collect();
+        let mut latest: HashMap<&str, usize> = HashMap::new();
+        let mut latest_certificate: HashMap<&str, usize> = HashMap::new();
+        for (position, tx) in history.iter().enumerate() {
+            if tx.tx_type.resulting_status().is_some() {
+                latest.insert(&tx.tx_id, position);
+            }
+            if matches!(
+                tx.tx_type,
+                TransactionType::CertificateIssuance | TransactionType::CertificateRenewal
+            ) {
+                latest_certificate.insert(&tx.tx_id, position);
+            }
+        }
+        let (retained, compacted): (Vec<_>, Vec<_>) =
+            history.iter().enumerate().partition(|(position, tx)| {
+                tx.tx_type.resulting_status().is_none()
+                    || latest.get(tx.tx_id.as_str()) == Some(position)
+                    || latest_certificate.get(tx.tx_id.as_str()) == Some(position)
+            });
+
+        let mut report = CompactionReport {
+            blocks_removed: 0,
+            transactions_before: history.len(),
+            transactions_after: retained.len(),
+            compacted_tx_ids: compacted.iter().map(|(_, tx)| tx.tx_id.clone()).collect(),
+            size_before_bytes,
+            size_after_bytes: size_before_bytes,
+            checkpoint_hash,
+        };
+        if compacted.is_empty() {
+            return report;
+        }
+
+        let transactions = retained.into_iter().map(|(_, tx)| (*tx).clone()).collect();
+        let block = Block::new(
+            next_index,
+            transactions,
+            report.checkpoint_hash.clone(),
+            self.current_difficulty(),
+            self.clock.now(),
+        );
+        let block = self.seal(block).await;
+
+        let mut pruned = self.pruned_blocks.write().await;
+        for removed in chain.drain(1..) {
+            pruned.insert(removed.index, removed.hash);
+            report.blocks_removed += 1;
+        }
+        drop(pruned);
+        chain.push(block);
+        *self.status_index.write().await = StatusIndex::build(chain.iter());
+
+        report.size_after_bytes = codec::encode(&*chain).unwrap_or_default().len();
+        report
+    }
+
     pub async fn archive_deprecated_certificate(&self, cert_id: String, cert_hash: String) {
         self.archived_certs.write().await.insert(cert_id, cert_hash);
     }
 
+    /// Hash stored for an archived deprecated certificate.
+    pub async fn get_archived_certificate(&self, cert_id: &str) -> Option<String> {
+        self.archived_certs.read().await.get(cert_id).cloned()
+    }
+
+    /// Every archived `(cert_id, cert_hash)`, sorted by certificate id.
+    pub async fn list_archived_certificates(&self) -> Vec<(String, String)> {
+        let mut archived: Vec<(String, String)> = self
+            .archived_certs
+            .read()
+            .await
+            .iter()
+            .map(|(id, hash)| (id.clone(), hash.clone()))
+            .collect();
+        archived.sort();
+        archived
+    }
+
+    /// Takes a certificate back out of the archive, e.g. for an audit,
+    /// returning the hash it was archived with.
+    pub async fn restore_archived_certificate(&self, cert_id: &str) -> Option<String> {
+        self.archived_certs.write().await.remove(cert_id)
+    }
+
     pub async fn get_blockchain_size(&self) -> usize {
+        self.export_chain().await.len()
+    }
+
+    /// The chain in the canonical encoding from `codec`, byte-identical on
+    /// every platform.
+    pub async fn export_chain(&self) -> Vec<u8> {
         let chain = self.chain.read().await;
-        bincode::serialize(&*chain).unwrap_or_default().len()
+        codec::encode(&*chain).unwrap_or_default()
     }
 
     pub async fn get_transaction_throughput(&self, duration_secs: u64) -> f64 {
@@ -179,4 +1140,173 @@ impl Blockchain {
     pub async fn get_chain_length(&self) -> usize {
         self.chain.read().await.len()
     }
+
+    /// Commitment over every block hash, formatted as `<tip index>:<sha256>`.
+    /// Identical chains give identical digests; the tip index tells a shorter
+    /// chain apart from a forked one of the same length.
+    pub async fn integrity_digest(&self) -> String {
+        let chain = self.chain.read().await;
+        let mut hasher = Sha256::new();
+        for block in chain.iter() {
+            hasher.update(block.index.to_be_bytes());
+            hasher.update(block.hash.as_bytes());
+        }
+        let tip_index = chain.last().map(|b| b.index).unwrap_or(0);
+        format!("{}:{:x}", tip_index, hasher.finalize())
+    }
+
+    /// Reconstructs the renewal lineage containing `cert_id`, oldest first,
+    /// by following the supersession links recorded in renewal transactions.
+    /// Returns just `[cert_id]` when the certificate was never renewed.
+    pub async fn renewal_chain(&self, cert_id: &str) -> Vec<String> {
+        let mut superseded_by: HashMap<String, String> = HashMap::new();
+        let mut supersedes: HashMap<String, String> = HashMap::new();
+        {
+            let chain = self.chain.read().await;
+            for tx in chain.iter().flat_map(|b| b.transactions.iter()) {
+                if let Some(payload) = tx.renewal_payload() {
+                    superseded_by.insert(
+                        payload.superseded_cert_id.clone(),
+                        payload.certificate.id.clone(),
+                    );
+                    supersedes.insert(payload.certificate.id, payload.superseded_cert_id);
+                }
+            }
+        }
+
+        // walk back to the original certificate, guarding against cycles
+        let mut root = cert_id.to_string();
+        let mut seen = HashSet::from([root.clone()]);
+        while let Some(prev) = supersedes.get(&root) {
+            if !seen.insert(prev.clone()) {
+                break;
+            }
+            root = prev.clone();
+        }
+
+        let mut lineage = vec![root.clone()];
+        let mut seen = HashSet::from([root.clone()]);
+        let mut current = root;
+        while let Some(next) = superseded_by.get(&current) {
+            if !seen.insert(next.clone()) {
+                break;
+            }
+            lineage.push(next.clone());
+            current = next.clone();
+        }
+        lineage
+    }
+}
+
+/// Test-only inspection API, enabled by the `test-harness` feature.
+#[cfg(feature = "test-harness")]
+impl Blockchain {
+    pub async fn pending_snapshot(&self) -> Vec<BlockchainTransaction> {
+        self.pending_transactions.read().await.clone()
+    }
+
+    pub async fn consensus_latencies_snapshot(&self) -> Vec<u128> {
+        self.consensus_latencies_ms.read().await.clone()
+    }
+
+    pub async fn archived_snapshot(&self) -> HashMap<String, String> {
+        self.archived_certs.read().await.clone()
+    }
+}
+
+/// Orders two hex hashes as unsigned big integers.
+fn cmp_hash_values(a: &str, b: &str) -> CmpOrdering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.to_ascii_lowercase().cmp(&b.to_ascii_lowercase()))
+}
+
+/// Index of the newest block `old` shares with `new`; genesis if nothing else.
+fn fork_point(old: &[Block], new: &[Block]) -> u64 {
+    // `new` is unpruned, so new[i].index == i
+    old.iter()
+        .rev()
+        .find(|b| new.get(b.index as usize).is_some_and(|n| n.hash == b.hash))
+        .map_or(0, |b| b.index)
+}
+
+/// Expected hashes tried to mine `blocks`: 16^difficulty per block, since
+/// difficulty counts leading hex zeros.
+fn chain_work<'a>(blocks: impl Iterator<Item = &'a Block>) -> u128 {
+    blocks.fold(0u128, |work, block| {
+        let block_work = 1u128.checked_shl(4 * block.difficulty).unwrap_or(u128::MAX);
+        work.saturating_add(block_work)
+    })
+}
+
+/// Removes the revocations from `txs` to be mined in a block of their own. A
+/// revocation whose certificate has another transaction pending is left in
+/// place, so it is never mined ahead of that certificate's issuance.
+fn take_fast_revocations(txs: &mut Vec<BlockchainTransaction>) -> Vec<BlockchainTransaction> {
+    let mut per_id: HashMap<&str, usize> = HashMap::new();
+    for tx in txs.iter() {
+        *per_id.entry(tx.tx_id.as_str()).or_default() += 1;
+    }
+    let fast: Vec<bool> = txs
+        .iter()
+        .map(|tx| {
+            matches!(tx.tx_type, TransactionType::CertificateRevocation)
+                && per_id[tx.tx_id.as_str()] == 1
+        })
+        .collect();
+    let (taken, kept): (Vec<_>, Vec<_>) = std::mem::take(txs)
+        .into_iter()
+        .zip(fast)
+        .partition(|(_, fast)| *fast);
+    *txs = kept.into_iter().map(|(tx, _)| tx).collect();
+    taken.into_iter().map(|(tx, _)| tx).collect()
+}
+
+/// Orders the pending pool for mining: highest priority first, otherwise in
+/// the order queued. A transaction is raised to the priority of any later one
+/// with the same id, so e.g. an issuance is never mined after its revocation.
+fn sort_for_mining(txs: &mut Vec<BlockchainTransaction>) {
+    let mut latest: HashMap<String, TransactionPriority> = HashMap::new();
+    let mut effective: Vec<TransactionPriority> = txs
+        .iter()
+        .rev()
+        .map(|tx| {
+            let priority = latest.entry(tx.tx_id.clone()).or_insert(tx.priority);
+            *priority = (*priority).max(tx.priority);
+            *priority
+        })
+        .collect();
+    effective.reverse();
+
+    let mut ordered: Vec<_> = effective.into_iter().zip(txs.drain(..)).collect();
+    // stable, so equal priorities keep their pool order
+    ordered.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+    txs.extend(ordered.into_iter().map(|(_, tx)| tx));
+}
+
+// keep at most the last 1000 samples to avoid unbounded growth
+fn push_bounded(stored: &mut Vec<u128>, values: impl IntoIterator<Item = u128>) {
+    stored.extend(values);
+    if stored.len() > 1000 {
+        let start = stored.len() - 1000;
+        stored.drain(..start);
+    }
+}
+
+/// Nearest-rank p50, p95 and p99 of `samples`.
+fn percentiles(samples: &[u128]) -> (f64, f64, f64) {
+    if samples.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    let mut vals = samples.to_vec();
+    vals.sort();
+    let n = vals.len();
+    let p = |quant: f64| ((quant * n as f64).ceil() as usize).clamp(1, n) - 1;
+    (
+        vals[p(0.50)] as f64,
+        vals[p(0.95)] as f64,
+        vals[p(0.99)] as f64,
+    )
 }
@@ -0,0 +1,26 @@
+//! Canonical binary encoding for chain data that leaves the process.
+//!
+//! `bincode::serialize` follows whatever the crate's defaults are, so sizes and
+//! any persisted or synced bytes go through these fixed options instead:
+//! little-endian, fixed-width integers, and no trailing bytes. The layout is the
+//! same as bincode 1.x's defaults, so payloads written before still decode, and
+//! a chain encodes to the same bytes on every platform.
+
+use bincode::Options;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_little_endian()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+}
+
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, bincode::Error> {
+    options().serialize(value)
+}
+
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, bincode::Error> {
+    options().deserialize(bytes)
+}
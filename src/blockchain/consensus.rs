@@ -0,0 +1,231 @@
+use super::block::{Block, hash_with_nonce, meets_difficulty};
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Mutex as StdMutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Seals blocks for a `Blockchain`: fills in the nonce and proof-of-work
+/// hash once the engine's agreement rule is met. The chain then stamps the
+/// seal time with `Block::stamp_seal_time`.
+#[async_trait]
+pub trait ConsensusEngine: Send + Sync {
+    async fn seal_block(&self, block: &mut Block) -> ConsensusProof;
+
+    /// Proof-of-work difficulty a chain built on this engine starts at;
+    /// 0 for engines that agree without work.
+    fn difficulty(&self) -> u32;
+}
+
+/// Evidence of how a block was agreed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsensusProof {
+    Work {
+        nonce: u64,
+        difficulty: u32,
+    },
+    /// A BFT round committed by `votes` of `validators`.
+    Quorum {
+        round: u64,
+        votes: usize,
+        validators: usize,
+        latency: Duration,
+    },
+}
+
+/// Hash-prefix proof-of-work, searched on tokio's blocking pool.
+#[derive(Debug, Clone, Copy)]
+pub struct ProofOfWork {
+    difficulty: u32,
+    workers: usize,
+}
+
+impl ProofOfWork {
+    pub fn new(difficulty: u32) -> Self {
+        Self {
+            difficulty,
+            workers: 1,
+        }
+    }
+
+    /// Number of threads that search nonces for each block.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// CPU-bound nonce search. Worker `i` tries nonces `i, i + workers, ...`;
+    /// the first to find a valid hash stops the others. Blocking — call from
+    /// `spawn_blocking`.
+    fn search_nonce(prefix: &str, difficulty: u32, workers: usize) -> (u64, String) {
+        if workers <= 1 {
+            let mut nonce = 0;
+            loop {
+                let hash = hash_with_nonce(prefix, nonce);
+                if meets_difficulty(&hash, difficulty) {
+                    return (nonce, hash);
+                }
+                nonce += 1;
+            }
+        }
+
+        let found = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers as u64)
+                .map(|start| {
+                    let found = &found;
+                    scope.spawn(move || {
+                        let mut nonce = start;
+                        while !found.load(Ordering::Relaxed) {
+                            let hash = hash_with_nonce(prefix, nonce);
+                            if meets_difficulty(&hash, difficulty) {
+                                found.store(true, Ordering::Relaxed);
+                                return Some((nonce, hash));
+                            }
+                            nonce += workers as u64;
+                        }
+                        None
+                    })
+                })
+                .collect();
+            // several workers may hit at once; keep the lowest nonce
+            handles
+                .into_iter()
+                .filter_map(|h| h.join().unwrap())
+                .min_by_key(|(nonce, _)| *nonce)
+                .unwrap()
+        })
+    }
+}
+
+#[async_trait]
+impl ConsensusEngine for ProofOfWork {
+    /// Mines at the block's own difficulty, which the chain may have moved
+    /// away from the engine's starting difficulty.
+    async fn seal_block(&self, block: &mut Block) -> ConsensusProof {
+        let prefix = block.header().hash_prefix();
+        let difficulty = block.difficulty;
+        let workers = self.workers;
+        let (nonce, hash) =
+            tokio::task::spawn_blocking(move || Self::search_nonce(&prefix, difficulty, workers))
+                .await
+                .expect("nonce search panicked");
+        block.nonce = nonce;
+        block.hash = hash;
+        ConsensusProof::Work { nonce, difficulty }
+    }
+
+    fn difficulty(&self) -> u32 {
+        self.difficulty
+    }
+}
+
+/// Simulated PBFT: a leader proposes the block, then the prepare and commit
+/// phases each finish once `quorum` validators' votes have arrived. Vote
+/// delays are drawn from the message latency range; the round takes as long
+/// as the slowest vote each phase waits for. Blocks carry no proof-of-work;
+/// the round number is stored as the nonce.
+pub struct PbftSimulated {
+    validators: usize,
+    quorum: usize,
+    min_latency: Duration,
+    max_latency: Duration,
+    round: AtomicU64,
+    // std mutex: never held across an await
+    rng: StdMutex<StdRng>,
+}
+
+impl PbftSimulated {
+    /// `validators` nodes tolerating `f = (validators - 1) / 3` faults, with
+    /// the usual `2f + 1` quorum.
+    pub fn new(validators: usize) -> Self {
+        let validators = validators.max(1);
+        Self {
+            validators,
+            quorum: 2 * ((validators - 1) / 3) + 1,
+            min_latency: Duration::from_millis(1),
+            max_latency: Duration::from_millis(5),
+            round: AtomicU64::new(0),
+            rng: StdMutex::new(StdRng::seed_from_u64(0)),
+        }
+    }
+
+    /// Votes needed per phase, at most the validator count.
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = quorum.clamp(1, self.validators);
+        self
+    }
+
+    /// One-way delay of each protocol message, drawn uniformly from `min..=max`.
+    pub fn with_message_latency(mut self, min: Duration, max: Duration) -> Self {
+        self.min_latency = min;
+        self.max_latency = max.max(min);
+        self
+    }
+
+    /// Reseeds the vote-delay RNG so a run can be replayed.
+    pub fn with_seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn validators(&self) -> usize {
+        self.validators
+    }
+
+    pub fn quorum(&self) -> usize {
+        self.quorum
+    }
+
+    /// Rounds completed so far.
+    pub fn rounds(&self) -> u64 {
+        self.round.load(Ordering::Relaxed)
+    }
+
+    fn message_latency(&self, rng: &mut StdRng) -> Duration {
+        if self.min_latency == self.max_latency {
+            return self.min_latency;
+        }
+        rng.gen_range(self.min_latency..=self.max_latency)
+    }
+
+    /// Time until the `quorum`-th fastest of the validators' votes arrives.
+    fn phase_latency(&self, rng: &mut StdRng) -> Duration {
+        let mut votes: Vec<Duration> = (0..self.validators)
+            .map(|_| self.message_latency(rng))
+            .collect();
+        votes.sort();
+        votes[self.quorum - 1]
+    }
+
+    fn round_latency(&self) -> Duration {
+        let mut rng = self.rng.lock().unwrap();
+        let pre_prepare = self.message_latency(&mut rng);
+        let prepare = self.phase_latency(&mut rng);
+        let commit = self.phase_latency(&mut rng);
+        pre_prepare + prepare + commit
+    }
+}
+
+#[async_trait]
+impl ConsensusEngine for PbftSimulated {
+    async fn seal_block(&self, block: &mut Block) -> ConsensusProof {
+        let latency = self.round_latency();
+        tokio::time::sleep(latency).await;
+
+        let round = self.round.fetch_add(1, Ordering::Relaxed) + 1;
+        block.nonce = round;
+        block.hash = hash_with_nonce(&block.header().hash_prefix(), round);
+        ConsensusProof::Quorum {
+            round,
+            votes: self.quorum,
+            validators: self.validators,
+            latency,
+        }
+    }
+
+    fn difficulty(&self) -> u32 {
+        0
+    }
+}
@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Content-addressed storage for transaction payloads kept off-chain.
+/// Payloads are keyed by the hex SHA-256 of their bytes.
+#[async_trait]
+pub trait ContentStore: Send + Sync {
+    /// Stores `bytes` and returns their content hash.
+    async fn put(&self, bytes: Vec<u8>) -> String;
+    async fn get(&self, content_hash: &str) -> Option<Vec<u8>>;
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[derive(Default)]
+pub struct InMemoryContentStore {
+    blobs: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl InMemoryContentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn len(&self) -> usize {
+        self.blobs.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.blobs.read().await.is_empty()
+    }
+}
+
+#[async_trait]
+impl ContentStore for InMemoryContentStore {
+    async fn put(&self, bytes: Vec<u8>) -> String {
+        let hash = content_hash(&bytes);
+        self.blobs.write().await.insert(hash.clone(), bytes);
+        hash
+    }
+
+    async fn get(&self, content_hash: &str) -> Option<Vec<u8>> {
+        self.blobs.read().await.get(content_hash).cloned()
+    }
+}
@@ -0,0 +1,96 @@
+use super::block::Block;
+use super::merkle::merkle_root;
+use super::transaction::{BlockchainTransaction, TransactionType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Payload of a `TrustAnchor` transaction in the genesis block: the keys a
+/// CA is trusted under from the start of the network.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustAnchorRecord {
+    pub ca_id: String,
+    pub public_keys: Vec<Vec<u8>>,
+}
+
+/// Everything that goes into a network's genesis block. Two nodes built from
+/// the same config start from the same block, hash included.
+#[derive(Debug, Clone, Default)]
+pub struct GenesisConfig {
+    pub network_id: String,
+    /// Initial CA trust store; ordered so the genesis block is deterministic.
+    pub ca_keyring: BTreeMap<String, Vec<Vec<u8>>>,
+}
+
+impl GenesisConfig {
+    pub fn new(network_id: &str) -> Self {
+        Self {
+            network_id: network_id.to_string(),
+            ca_keyring: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `public_key` to the keys `ca_id` is trusted under at genesis.
+    pub fn with_trusted_ca(mut self, ca_id: &str, public_key: Vec<u8>) -> Self {
+        let keys = self.ca_keyring.entry(ca_id.to_string()).or_default();
+        if !keys.contains(&public_key) {
+            keys.push(public_key);
+        }
+        self
+    }
+
+    /// The genesis block: one `TrustAnchor` transaction per CA, committed to
+    /// by the block hash through the Merkle root.
+    pub fn build_block(&self) -> Block {
+        let transactions: Vec<BlockchainTransaction> = self
+            .ca_keyring
+            .iter()
+            .map(|(ca_id, public_keys)| {
+                let mut tx = BlockchainTransaction::trust_anchor(&TrustAnchorRecord {
+                    ca_id: ca_id.clone(),
+                    public_keys: public_keys.clone(),
+                });
+                tx.timestamp = DateTime::<Utc>::UNIX_EPOCH;
+                tx
+            })
+            .collect();
+        let merkle_root = merkle_root(&transactions);
+
+        // an empty keyring hashes as before, so existing network ids keep their genesis
+        let mut preimage = format!("BBVPKI-GENESIS-{}", self.network_id);
+        if !transactions.is_empty() {
+            preimage.push_str(&merkle_root);
+        }
+        let hash = format!("{:x}", Sha256::digest(preimage.as_bytes()));
+
+        Block {
+            index: 0,
+            timestamp: DateTime::<Utc>::UNIX_EPOCH,
+            mining_started_at: DateTime::<Utc>::UNIX_EPOCH,
+            transactions,
+            previous_hash: "0".to_string(),
+            hash,
+            nonce: 0,
+            difficulty: 0,
+            merkle_root,
+        }
+    }
+}
+
+impl BlockchainTransaction {
+    pub fn trust_anchor(record: &TrustAnchorRecord) -> Self {
+        Self::new(
+            format!("TRUST-ANCHOR-{}", record.ca_id),
+            TransactionType::TrustAnchor,
+            serde_json::to_vec(record).unwrap_or_default(),
+        )
+    }
+
+    pub fn trust_anchor_record(&self) -> Option<TrustAnchorRecord> {
+        match self.tx_type {
+            TransactionType::TrustAnchor => self.encoding.decode(&self.data).ok(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use super::block::Block;
+use crate::pki::CertificateStatus;
+use std::collections::HashMap;
+
+/// Latest on-chain status of every certificate, kept in step with the chain.
+/// Each applied block leaves an undo record so a reorg can roll it back
+/// without rescanning the chain.
+#[derive(Default)]
+pub(crate) struct StatusIndex {
+    statuses: HashMap<String, CertificateStatus>,
+    /// Per block hash: the status each touched cert had before the block.
+    undo: HashMap<String, Vec<(String, Option<CertificateStatus>)>>,
+}
+
+impl StatusIndex {
+    /// Full rebuild from `blocks`, oldest first.
+    pub(crate) fn build<'a>(blocks: impl IntoIterator<Item = &'a Block>) -> Self {
+        let mut index = Self::default();
+        for block in blocks {
+            index.apply_block(block);
+        }
+        index
+    }
+
+    pub(crate) fn get(&self, cert_id: &str) -> Option<CertificateStatus> {
+        self.statuses.get(cert_id).cloned()
+    }
+
+    pub(crate) fn apply_block(&mut self, block: &Block) {
+        let mut undo = Vec::new();
+        for tx in &block.transactions {
+            let Some(status) = tx.tx_type.resulting_status() else {
+                continue;
+            };
+            let previous = self.statuses.insert(tx.tx_id.clone(), status);
+            undo.push((tx.tx_id.clone(), previous));
+        }
+        self.undo.insert(block.hash.clone(), undo);
+    }
+
+    /// Reverts `block`, which must be the most recently applied block still
+    /// in effect. Returns false if its undo record is gone.
+    pub(crate) fn rollback_block(&mut self, block: &Block) -> bool {
+        let Some(undo) = self.undo.remove(&block.hash) else {
+            return false;
+        };
+        for (cert_id, previous) in undo.into_iter().rev() {
+            match previous {
+                Some(status) => self.statuses.insert(cert_id, status),
+                None => self.statuses.remove(&cert_id),
+            };
+        }
+        true
+    }
+
+    /// Drops undo records for blocks that can no longer be reorged away.
+    pub(crate) fn forget_undo(&mut self, block_hash: &str) {
+        self.undo.remove(block_hash);
+    }
+}
@@ -0,0 +1,132 @@
+use super::block::BlockHeader;
+use super::transaction::BlockchainTransaction;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Commitment to a transaction's payload. Leaves hash this rather than the
+/// payload itself, so a proof can withhold the payload and still verify.
+pub fn data_commitment(tx: &BlockchainTransaction) -> String {
+    sha256_hex(&tx.data)
+}
+
+pub fn leaf_hash(tx: &BlockchainTransaction) -> String {
+    leaf_hash_with_commitment(tx, &data_commitment(tx))
+}
+
+fn leaf_hash_with_commitment(tx: &BlockchainTransaction, commitment: &str) -> String {
+    let fields = (
+        &tx.tx_id,
+        &tx.tx_type,
+        tx.timestamp,
+        commitment,
+        &tx.signature,
+        tx.encoding,
+        &tx.content_hash,
+        tx.enqueued_at,
+        tx.deadline,
+    );
+    sha256_hex(&serde_json::to_vec(&fields).unwrap_or_default())
+}
+
+fn node_hash(left: &str, right: &str) -> String {
+    sha256_hex(format!("{}{}", left, right).as_bytes())
+}
+
+/// Merkle root over the transactions' leaf hashes. An odd node at any level
+/// is paired with itself; the root of no transactions is the hash of nothing.
+pub fn merkle_root(transactions: &[BlockchainTransaction]) -> String {
+    let mut level: Vec<String> = transactions.iter().map(leaf_hash).collect();
+    if level.is_empty() {
+        return sha256_hex(&[]);
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+    }
+    level.remove(0)
+}
+
+/// Sibling hashes from leaf `leaf_index` up to the root.
+fn merkle_path(transactions: &[BlockchainTransaction], mut leaf_index: usize) -> Vec<String> {
+    let mut level: Vec<String> = transactions.iter().map(leaf_hash).collect();
+    let mut path = Vec::new();
+    while level.len() > 1 {
+        let sibling = leaf_index ^ 1;
+        path.push(level.get(sibling).unwrap_or(&level[leaf_index]).clone());
+        level = level
+            .chunks(2)
+            .map(|pair| node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+        leaf_index /= 2;
+    }
+    path
+}
+
+/// Evidence that `transaction` is in the block described by `header`. It
+/// proves inclusion only; it cannot show that no later transaction for the
+/// same certificate exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// With `redacted`, `data` is emptied and only `data_commitment` remains.
+    pub transaction: BlockchainTransaction,
+    pub data_commitment: String,
+    pub redacted: bool,
+    pub leaf_index: usize,
+    pub siblings: Vec<String>,
+    /// The tree head: commits to the Merkle root and carries the block's
+    /// proof-of-work. Compare `header.hash` with a trusted chain tip or
+    /// checkpoint to anchor the proof.
+    pub header: BlockHeader,
+}
+
+impl InclusionProof {
+    pub(crate) fn new(
+        transactions: &[BlockchainTransaction],
+        leaf_index: usize,
+        header: BlockHeader,
+    ) -> Self {
+        Self {
+            transaction: transactions[leaf_index].clone(),
+            data_commitment: data_commitment(&transactions[leaf_index]),
+            redacted: false,
+            leaf_index,
+            siblings: merkle_path(transactions, leaf_index),
+            header,
+        }
+    }
+
+    /// Drops the payload (for a certificate, everything including the
+    /// vehicle id) and keeps only its commitment. The proof still shows the
+    /// transaction type, and so the status, is on chain.
+    pub fn redact(mut self) -> Self {
+        self.transaction.data.clear();
+        self.redacted = true;
+        self
+    }
+
+    /// Recomputes the root from the transaction and its path, then checks the
+    /// header commits to that root and that its hash and proof-of-work hold.
+    /// Needs nothing from the party that produced the proof.
+    pub fn verify(&self) -> bool {
+        if !self.redacted && data_commitment(&self.transaction) != self.data_commitment {
+            return false;
+        }
+        let mut hash = leaf_hash_with_commitment(&self.transaction, &self.data_commitment);
+        let mut index = self.leaf_index;
+        for sibling in &self.siblings {
+            hash = if index.is_multiple_of(2) {
+                node_hash(&hash, sibling)
+            } else {
+                node_hash(sibling, &hash)
+            };
+            index /= 2;
+        }
+        hash == self.header.merkle_root && self.header.is_valid()
+    }
+}
@@ -0,0 +1,48 @@
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+/// Condition that makes a background miner produce a block.
+#[derive(Debug, Clone, Copy)]
+pub enum MineTrigger {
+    /// Mine once the pending pool holds at least this many transactions.
+    PoolSize(usize),
+    /// Mine whatever is pending on a fixed interval.
+    Interval(Duration),
+    /// Mine on whichever of the pool size or interval fires first.
+    Either(usize, Duration),
+}
+
+impl MineTrigger {
+    pub(crate) fn pool_size(&self) -> Option<usize> {
+        match self {
+            MineTrigger::PoolSize(n) | MineTrigger::Either(n, _) => Some((*n).max(1)),
+            MineTrigger::Interval(_) => None,
+        }
+    }
+
+    pub(crate) fn interval(&self) -> Option<Duration> {
+        match self {
+            MineTrigger::Interval(d) | MineTrigger::Either(_, d) => Some(*d),
+            MineTrigger::PoolSize(_) => None,
+        }
+    }
+}
+
+/// Handle to a miner started with `Blockchain::spawn_miner`.
+pub struct MinerHandle {
+    pub(crate) shutdown_tx: watch::Sender<bool>,
+    pub(crate) task: JoinHandle<()>,
+}
+
+impl MinerHandle {
+    /// Stops the miner after it flushes any transactions still pending.
+    pub async fn shutdown(self) {
+        let _ = self.shutdown_tx.send(true);
+        let _ = self.task.await;
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
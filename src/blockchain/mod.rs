@@ -1,6 +1,26 @@
 pub mod block;
 pub mod chain;
+pub mod codec;
+pub mod consensus;
+pub mod content;
+pub mod genesis;
+mod index;
+pub mod merkle;
+pub mod miner;
 pub mod transaction;
 
-pub use chain::Blockchain;
-pub use transaction::{BlockchainTransaction, TransactionType};
+pub use block::BlockHeader;
+pub use chain::{
+    BLOCK_EVENT_CAPACITY, BlockEvent, BlockLookup, Blockchain, ChainError, CompactionReport,
+    DEFAULT_RETARGET_INTERVAL, DifficultyPolicy, MineResult, MiningStats, RollbackError,
+    TransactionPolicy, TransactionRejection,
+};
+pub use consensus::{ConsensusEngine, ConsensusProof, PbftSimulated, ProofOfWork};
+pub use content::{ContentStore, InMemoryContentStore};
+pub use genesis::{GenesisConfig, TrustAnchorRecord};
+pub use merkle::InclusionProof;
+pub use miner::{MineTrigger, MinerHandle};
+pub use transaction::{
+    BlockchainTransaction, DataEncoding, DecodeError, KeyRotationRecord, RenewalPayload,
+    TransactionPriority, TransactionType,
+};
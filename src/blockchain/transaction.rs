@@ -1,5 +1,12 @@
+use super::codec;
+use super::content::{ContentStore, content_hash};
+use crate::crypto::{KeyAlgorithm, verify_ed25519};
+use crate::pki::{Certificate, CertificateStatus};
 use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signer, SigningKey};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockchainTransaction {
@@ -8,6 +15,81 @@ pub struct BlockchainTransaction {
     pub timestamp: DateTime<Utc>,
     pub data: Vec<u8>,
     pub signature: Vec<u8>,
+    /// How `data` is encoded, so decoders don't have to assume JSON.
+    #[serde(default)]
+    pub encoding: DataEncoding,
+    /// When set, `data` is empty and the payload lives in a `ContentStore`
+    /// under this hash.
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// When the transaction entered a chain's pending pool.
+    #[serde(default)]
+    pub enqueued_at: Option<DateTime<Utc>>,
+    /// Dropped from the pending pool instead of mined once this passes.
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
+    /// Pending-pool ordering; higher priorities are mined first.
+    #[serde(default)]
+    pub priority: TransactionPriority,
+}
+
+/// Mining order of pending transactions: by priority, then queue order.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum TransactionPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Serialization format of a transaction's `data` payload.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataEncoding {
+    #[default]
+    Json,
+    Bincode,
+    MsgPack,
+}
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("transaction {0} does not carry a certificate")]
+    NoCertificate(String),
+    #[error("failed to decode {encoding:?} payload: {message}")]
+    Malformed {
+        encoding: DataEncoding,
+        message: String,
+    },
+    #[error("payload {0} is stored off-chain; decode it with a content store")]
+    OffChain(String),
+    #[error("payload {0} not found in content store")]
+    ContentMissing(String),
+    #[error("payload {0} does not match its content hash")]
+    ContentHashMismatch(String),
+}
+
+impl DataEncoding {
+    pub fn encode<T: Serialize>(&self, value: &T) -> Vec<u8> {
+        match self {
+            DataEncoding::Json => serde_json::to_vec(value).unwrap_or_default(),
+            DataEncoding::Bincode => codec::encode(value).unwrap_or_default(),
+            DataEncoding::MsgPack => rmp_serde::to_vec(value).unwrap_or_default(),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, DecodeError> {
+        let result = match self {
+            DataEncoding::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            DataEncoding::Bincode => codec::decode(bytes).map_err(|e| e.to_string()),
+            DataEncoding::MsgPack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+        };
+        result.map_err(|message| DecodeError::Malformed {
+            encoding: *self,
+            message,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,16 +98,210 @@ pub enum TransactionType {
     CertificateRevocation,
     CertificateRenewal,
     DeprecationArchive,
+    KeyRotation,
+    CertificateSuspension,
+    CertificateReinstatement,
+    /// Genesis-only: the initial keys of a trusted CA.
+    TrustAnchor,
+}
+
+impl TransactionType {
+    /// Priority transactions of this type get unless set otherwise.
+    /// Revocations and suspensions stop a certificate being trusted, so they
+    /// go ahead of everything else.
+    pub fn default_priority(&self) -> TransactionPriority {
+        match self {
+            Self::CertificateRevocation | Self::CertificateSuspension => TransactionPriority::High,
+            _ => TransactionPriority::Normal,
+        }
+    }
+
+    /// Status a certificate has after a transaction of this type, if the
+    /// type concerns a certificate at all.
+    pub fn resulting_status(&self) -> Option<CertificateStatus> {
+        match self {
+            Self::CertificateIssuance
+            | Self::CertificateRenewal
+            | Self::CertificateReinstatement => Some(CertificateStatus::Active),
+            Self::CertificateRevocation => Some(CertificateStatus::Revoked),
+            Self::DeprecationArchive => Some(CertificateStatus::Deprecated),
+            Self::CertificateSuspension => Some(CertificateStatus::Suspended),
+            Self::KeyRotation | Self::TrustAnchor => None,
+        }
+    }
+}
+
+/// Payload of a `CertificateRenewal` transaction, linking the new certificate
+/// to the one it supersedes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenewalPayload {
+    pub superseded_cert_id: String,
+    pub certificate: Certificate,
+}
+
+/// Payload of a `KeyRotation` transaction. `signature` is made by the old
+/// key over `new_public_key`, proving the rotation was authorized by the
+/// key relying parties already trust.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRotationRecord {
+    pub ca_id: String,
+    pub old_public_key: Vec<u8>,
+    pub new_public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+    /// Algorithm of both keys; rotation never changes it.
+    #[serde(default)]
+    pub algorithm: KeyAlgorithm,
+}
+
+impl KeyRotationRecord {
+    pub fn verify(&self) -> bool {
+        self.algorithm
+            .verify(&self.old_public_key, &self.new_public_key, &self.signature)
+    }
 }
 
 impl BlockchainTransaction {
     pub fn new(tx_id: String, tx_type: TransactionType, data: Vec<u8>) -> Self {
         Self {
             tx_id,
+            priority: tx_type.default_priority(),
             tx_type,
             timestamp: Utc::now(),
             data,
             signature: vec![],
+            encoding: DataEncoding::Json,
+            content_hash: None,
+            enqueued_at: None,
+            deadline: None,
+        }
+    }
+
+    /// What `sign` covers: the id, type and payload. Off-chain payloads are
+    /// covered through their content hash.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&(
+            "BBVPKI-TRANSACTION",
+            &self.tx_id,
+            &self.tx_type,
+            &self.data,
+            &self.content_hash,
+        ))
+        .unwrap_or_default()
+    }
+
+    /// Signs the transaction as its submitter; see `TransactionPolicy`.
+    pub fn sign(&mut self, key: &SigningKey) {
+        self.signature = key.sign(&self.signing_bytes()).to_bytes().to_vec();
+    }
+
+    pub fn verify(&self, public_key: &[u8]) -> bool {
+        verify_ed25519(public_key, &self.signing_bytes(), &self.signature)
+    }
+
+    /// Sets a deadline after which the transaction is no longer worth mining.
+    pub fn with_deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Builds a transaction whose payload is `cert` in the given encoding.
+    pub fn for_certificate(
+        tx_type: TransactionType,
+        cert: &Certificate,
+        encoding: DataEncoding,
+    ) -> Self {
+        let mut tx = Self::new(cert.id.clone(), tx_type, encoding.encode(cert));
+        tx.encoding = encoding;
+        tx
+    }
+
+    /// Like `for_certificate`, but only the payload's content hash goes
+    /// on-chain; the encoded certificate is written to `store`.
+    pub async fn for_certificate_off_chain(
+        tx_type: TransactionType,
+        cert: &Certificate,
+        encoding: DataEncoding,
+        store: &dyn ContentStore,
+    ) -> Self {
+        let mut tx = Self::new(cert.id.clone(), tx_type, vec![]);
+        tx.encoding = encoding;
+        tx.content_hash = Some(store.put(encoding.encode(cert)).await);
+        tx
+    }
+
+    /// Decodes the certificate carried inline by an issuance or renewal transaction.
+    pub fn decode_certificate(&self) -> Result<Certificate, DecodeError> {
+        if let Some(hash) = &self.content_hash {
+            return Err(DecodeError::OffChain(hash.clone()));
+        }
+        self.decode_certificate_bytes(&self.data)
+    }
+
+    /// Decodes the certificate, fetching it from `store` when the payload is
+    /// off-chain. Fetched bytes are checked against the on-chain hash.
+    pub async fn decode_certificate_from(
+        &self,
+        store: &dyn ContentStore,
+    ) -> Result<Certificate, DecodeError> {
+        let Some(hash) = &self.content_hash else {
+            return self.decode_certificate_bytes(&self.data);
+        };
+        let bytes = store
+            .get(hash)
+            .await
+            .ok_or_else(|| DecodeError::ContentMissing(hash.clone()))?;
+        if content_hash(&bytes) != *hash {
+            return Err(DecodeError::ContentHashMismatch(hash.clone()));
+        }
+        self.decode_certificate_bytes(&bytes)
+    }
+
+    fn decode_certificate_bytes(&self, bytes: &[u8]) -> Result<Certificate, DecodeError> {
+        match self.tx_type {
+            TransactionType::CertificateIssuance => self.encoding.decode(bytes),
+            TransactionType::CertificateRenewal => self
+                .encoding
+                .decode::<RenewalPayload>(bytes)
+                .map(|payload| payload.certificate),
+            _ => Err(DecodeError::NoCertificate(self.tx_id.clone())),
+        }
+    }
+
+    pub fn renewal(certificate: &Certificate, superseded_cert_id: &str) -> Self {
+        let payload = RenewalPayload {
+            superseded_cert_id: superseded_cert_id.to_string(),
+            certificate: certificate.clone(),
+        };
+        Self::new(
+            certificate.id.clone(),
+            TransactionType::CertificateRenewal,
+            serde_json::to_vec(&payload).unwrap_or_default(),
+        )
+    }
+
+    pub fn renewal_payload(&self) -> Option<RenewalPayload> {
+        match self.tx_type {
+            TransactionType::CertificateRenewal => self.encoding.decode(&self.data).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn key_rotation(record: &KeyRotationRecord) -> Self {
+        Self::new(
+            format!(
+                "KEYROT-{}-{}",
+                record.ca_id,
+                hex::encode(&record.new_public_key)
+            ),
+            TransactionType::KeyRotation,
+            serde_json::to_vec(record).unwrap_or_default(),
+        )
+    }
+
+    pub fn key_rotation_record(&self) -> Option<KeyRotationRecord> {
+        match self.tx_type {
+            TransactionType::KeyRotation => self.encoding.decode(&self.data).ok(),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,33 @@
+use ed25519_dalek::SigningKey;
+use rand::rngs::OsRng;
+use rand_core::CryptoRngCore;
+use std::sync::Mutex as StdMutex;
+
+/// Randomness the HSM and TPM draw every key, seed and nonce from. Swap in a
+/// hardware RNG, an audited source, or a seeded `StdRng` for reproducible runs.
+pub type EntropySource = Box<dyn CryptoRngCore + Send>;
+
+/// An `EntropySource` shared behind a lock; never held across an await.
+pub(crate) struct Entropy(StdMutex<EntropySource>);
+
+impl Entropy {
+    pub(crate) fn new(source: EntropySource) -> Self {
+        Self(StdMutex::new(source))
+    }
+
+    pub(crate) fn os() -> Self {
+        Self::new(Box::new(OsRng))
+    }
+
+    pub(crate) fn signing_key(&self) -> SigningKey {
+        SigningKey::generate(&mut **self.0.lock().unwrap())
+    }
+
+    pub(crate) fn ecdsa_signing_key(&self) -> k256::ecdsa::SigningKey {
+        k256::ecdsa::SigningKey::random(&mut *self.0.lock().unwrap())
+    }
+
+    pub(crate) fn fill_bytes(&self, dest: &mut [u8]) {
+        self.0.lock().unwrap().fill_bytes(dest);
+    }
+}
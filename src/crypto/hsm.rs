@@ -1,27 +1,94 @@
+use super::entropy::{Entropy, EntropySource};
+use super::signature::KeyAlgorithm;
 use ed25519_dalek::{Signer, SigningKey};
-use rand::rngs::OsRng;
+use k256::ecdsa;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// A CA private key of any supported algorithm.
+enum CaKey {
+    Ed25519(SigningKey),
+    EcdsaSecp256k1(ecdsa::SigningKey),
+}
+
+impl CaKey {
+    fn algorithm(&self) -> KeyAlgorithm {
+        match self {
+            CaKey::Ed25519(_) => KeyAlgorithm::Ed25519,
+            CaKey::EcdsaSecp256k1(_) => KeyAlgorithm::EcdsaSecp256k1,
+        }
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        match self {
+            CaKey::Ed25519(key) => key.verifying_key().to_bytes().to_vec(),
+            CaKey::EcdsaSecp256k1(key) => key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            CaKey::Ed25519(key) => key.sign(message).to_bytes().to_vec(),
+            CaKey::EcdsaSecp256k1(key) => {
+                let signature: ecdsa::Signature = key.sign(message);
+                signature.to_bytes().to_vec()
+            }
+        }
+    }
+}
+
 /// Hardware Security Module for CA operations
 pub struct HardwareSecurityModule {
-    ca_keys: Arc<RwLock<HashMap<String, SigningKey>>>,
+    ca_keys: Arc<RwLock<HashMap<String, CaKey>>>,
     operations_log: Arc<RwLock<Vec<String>>>,
+    entropy: Entropy,
+}
+
+impl Default for HardwareSecurityModule {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HardwareSecurityModule {
     pub fn new() -> Self {
+        Self::with_entropy(Entropy::os())
+    }
+
+    /// An HSM that generates every CA key from `rng` instead of the OS.
+    pub fn with_rng(rng: EntropySource) -> Self {
+        Self::with_entropy(Entropy::new(rng))
+    }
+
+    fn with_entropy(entropy: Entropy) -> Self {
         Self {
             ca_keys: Arc::new(RwLock::new(HashMap::new())),
             operations_log: Arc::new(RwLock::new(Vec::new())),
+            entropy,
         }
     }
 
+    /// Generates an ed25519 key for `ca_id`; see
+    /// `generate_ca_keypair_with_algorithm`.
     pub async fn generate_ca_keypair(&self, ca_id: &str) -> Vec<u8> {
-        let mut csprng = OsRng;
-        let signing_key = SigningKey::generate(&mut csprng);
-        let public_key = signing_key.verifying_key().to_bytes().to_vec();
+        self.generate_ca_keypair_with_algorithm(ca_id, KeyAlgorithm::Ed25519)
+            .await
+    }
+
+    /// Generates and stores a key of `algorithm` for `ca_id`, replacing any
+    /// key it held, and returns the public key.
+    pub async fn generate_ca_keypair_with_algorithm(
+        &self,
+        ca_id: &str,
+        algorithm: KeyAlgorithm,
+    ) -> Vec<u8> {
+        let signing_key = self.generate_key(algorithm);
+        let public_key = signing_key.public_key();
 
         self.ca_keys
             .write()
@@ -33,14 +100,77 @@ impl HardwareSecurityModule {
         public_key
     }
 
+    fn generate_key(&self, algorithm: KeyAlgorithm) -> CaKey {
+        match algorithm {
+            KeyAlgorithm::Ed25519 => CaKey::Ed25519(self.entropy.signing_key()),
+            KeyAlgorithm::EcdsaSecp256k1 => CaKey::EcdsaSecp256k1(self.entropy.ecdsa_signing_key()),
+        }
+    }
+
+    /// Replaces a CA's key with a fresh one of the same algorithm. Returns the old public key, the
+    /// new public key, and the old key's signature over the new public key,
+    /// which lets relying parties accept the new key on the old key's authority.
+    pub async fn rotate_ca_keypair(
+        &self,
+        ca_id: &str,
+    ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), String> {
+        let mut keys = self.ca_keys.write().await;
+        let old_key = keys.get(ca_id).ok_or("CA key not found in HSM")?;
+
+        let new_key = self.generate_key(old_key.algorithm());
+        let old_public_key = old_key.public_key();
+        let new_public_key = new_key.public_key();
+        let continuity_signature = old_key.sign(&new_public_key);
+
+        keys.insert(ca_id.to_string(), new_key);
+        drop(keys);
+        self.log_operation(&format!("CA keypair rotated: {}", ca_id))
+            .await;
+
+        Ok((old_public_key, new_public_key, continuity_signature))
+    }
+
+    /// Zeroizes a CA's key, e.g. when decommissioning the CA. Signing as
+    /// `ca_id` fails afterwards. False if there was no such key.
+    pub async fn destroy_ca_keypair(&self, ca_id: &str) -> bool {
+        let removed = self.ca_keys.write().await.remove(ca_id).is_some();
+        if removed {
+            self.log_operation(&format!("CA keypair destroyed: {}", ca_id))
+                .await;
+        }
+        removed
+    }
+
+    /// The current verifying key for `ca_id`, if the HSM holds one.
+    pub async fn get_public_key(&self, ca_id: &str) -> Option<Vec<u8>> {
+        self.ca_keys.read().await.get(ca_id).map(CaKey::public_key)
+    }
+
+    /// Algorithm of the key the HSM holds for `ca_id`.
+    pub async fn key_algorithm(&self, ca_id: &str) -> Option<KeyAlgorithm> {
+        self.ca_keys.read().await.get(ca_id).map(CaKey::algorithm)
+    }
+
     pub async fn sign_certificate(&self, ca_id: &str, cert_data: &[u8]) -> Result<Vec<u8>, String> {
+        self.sign_with_algorithm(ca_id, cert_data)
+            .await
+            .map(|(_, signature)| signature)
+    }
+
+    /// Like `sign_certificate`, also returning the algorithm of the key that
+    /// signed, which verifiers need to check the signature.
+    pub async fn sign_with_algorithm(
+        &self,
+        ca_id: &str,
+        data: &[u8],
+    ) -> Result<(KeyAlgorithm, Vec<u8>), String> {
         let keys = self.ca_keys.read().await;
         if let Some(keypair) = keys.get(ca_id) {
-            let signature = keypair.sign(cert_data).to_bytes().to_vec();
+            let signed = (keypair.algorithm(), keypair.sign(data));
             drop(keys);
             self.log_operation(&format!("Certificate signed by CA: {}", ca_id))
                 .await;
-            return Ok(signature);
+            return Ok(signed);
         }
         Err("CA key not found in HSM".to_string())
     }
@@ -1,5 +1,12 @@
+pub mod entropy;
 pub mod hsm;
+pub mod signature;
 pub mod tpm;
 
+pub use entropy::EntropySource;
 pub use hsm::HardwareSecurityModule;
-pub use tpm::TrustedPlatformModule;
+pub use signature::{
+    CONTEXT_ATTESTATION, CONTEXT_CSR, CONTEXT_V2I_HANDSHAKE, CONTEXT_V2V, KeyAlgorithm,
+    is_weak_ed25519_key, verify_ecdsa_secp256k1, verify_ed25519, with_context,
+};
+pub use tpm::{Attestation, TpmError, TrustedPlatformModule, verify_attestation};
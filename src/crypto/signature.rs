@@ -0,0 +1,75 @@
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use k256::ecdsa;
+use serde::{Deserialize, Serialize};
+
+/// Signature scheme of a key held by the HSM.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyAlgorithm {
+    #[default]
+    Ed25519,
+    /// ECDSA over secp256k1 with SHA-256; public keys are 33-byte compressed
+    /// SEC1 points and signatures 64-byte `r || s`.
+    EcdsaSecp256k1,
+}
+
+impl KeyAlgorithm {
+    /// Verifies `signature` under this scheme. Malformed keys or signatures
+    /// simply fail verification.
+    pub fn verify(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            KeyAlgorithm::Ed25519 => verify_ed25519(public_key, message, signature),
+            KeyAlgorithm::EcdsaSecp256k1 => verify_ecdsa_secp256k1(public_key, message, signature),
+        }
+    }
+}
+
+/// Signing contexts for `with_context`, one per protocol use of a key.
+pub const CONTEXT_V2V: &str = "bbvpki/v2v-message";
+pub const CONTEXT_V2I_HANDSHAKE: &str = "bbvpki/v2i-handshake";
+pub const CONTEXT_CSR: &str = "bbvpki/certificate-request";
+pub const CONTEXT_ATTESTATION: &str = "bbvpki/attestation";
+
+/// Domain-separates `message` for signing: the context's length as a
+/// big-endian u32, the context, then the message. A signature made under
+/// one context never verifies under another, whatever the message bytes.
+pub fn with_context(context: &str, message: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(4 + context.len() + message.len());
+    bytes.extend((context.len() as u32).to_be_bytes());
+    bytes.extend(context.as_bytes());
+    bytes.extend(message);
+    bytes
+}
+
+/// Verifies an ed25519 signature given raw public key and signature bytes.
+/// Malformed keys or signatures simply fail verification.
+pub fn verify_ed25519(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    if let Ok(pk_array) = <&[u8; 32]>::try_from(public_key)
+        && let Ok(pk) = VerifyingKey::from_bytes(pk_array)
+        && let Ok(sig_array) = <&[u8; 64]>::try_from(signature)
+    {
+        let sig = Signature::from_bytes(sig_array);
+        return pk.verify(message, &sig).is_ok();
+    }
+    false
+}
+
+/// Verifies a secp256k1 ECDSA signature given SEC1 public key bytes and a
+/// 64-byte `r || s` signature.
+pub fn verify_ecdsa_secp256k1(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    if let Ok(pk) = ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+        && let Ok(sig) = ecdsa::Signature::from_slice(signature)
+    {
+        return pk.verify(message, &sig).is_ok();
+    }
+    false
+}
+
+/// True if `public_key` can never verify a signature: not 32 bytes, not a
+/// point on the curve, or a small-order point such as the identity (which
+/// all-zero bytes decode to).
+pub fn is_weak_ed25519_key(public_key: &[u8]) -> bool {
+    match <&[u8; 32]>::try_from(public_key).map(VerifyingKey::from_bytes) {
+        Ok(Ok(pk)) => pk.is_weak(),
+        _ => true,
+    }
+}
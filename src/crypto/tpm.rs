@@ -1,28 +1,127 @@
+use super::entropy::{Entropy, EntropySource};
+use super::signature::{CONTEXT_ATTESTATION, verify_ed25519, with_context};
+use crate::time::{Clock, SystemClock};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chrono::{DateTime, Utc};
 use ed25519_dalek::{Signer, SigningKey};
-use rand::rngs::OsRng;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use thiserror::Error;
 use tokio::sync::RwLock;
 
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TpmError {
+    #[error("Key not found in TPM")]
+    KeyNotFound,
+    #[error("Signing rate limit of {max_per_sec}/s exceeded for {key_id}")]
+    RateLimited { key_id: String, max_per_sec: u32 },
+}
+
+/// A TPM's statement that it holds the private half of `public_key`, signed
+/// with its attestation key over the key and a fresh nonce.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attestation {
+    pub public_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl Attestation {
+    fn statement(public_key: &[u8], nonce: &[u8]) -> Vec<u8> {
+        let mut statement = public_key.to_vec();
+        statement.extend(nonce);
+        with_context(CONTEXT_ATTESTATION, &statement)
+    }
+}
+
+/// Checks `attestation` was signed by the TPM whose attestation key is
+/// `attestation_pubkey`, e.g. before a CA certifies the attested key.
+pub fn verify_attestation(attestation: &Attestation, attestation_pubkey: &[u8]) -> bool {
+    let statement = Attestation::statement(&attestation.public_key, &attestation.nonce);
+    verify_ed25519(attestation_pubkey, &statement, &attestation.signature)
+}
+
+/// Sliding one-second window of signatures made with a rate-limited key.
+struct SigningWindow {
+    max_per_sec: u32,
+    recent: VecDeque<DateTime<Utc>>,
+}
+
 /// Trusted Platform Module - Secure key storage and signing
 pub struct TrustedPlatformModule {
     private_keys: Arc<RwLock<HashMap<String, Vec<u8>>>>,
-    #[allow(dead_code)]
+    /// Endorses keys generated inside this TPM; see `attest`.
     attestation_key: SigningKey,
+    /// Never leaves the TPM; wraps private keys before they are exported.
+    storage_root_key: [u8; 32],
+    signing_windows: Arc<RwLock<HashMap<String, SigningWindow>>>,
+    entropy: Entropy,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for TrustedPlatformModule {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TrustedPlatformModule {
     pub fn new() -> Self {
-        let mut csprng = OsRng;
+        Self::with_entropy(Entropy::os())
+    }
+
+    /// A TPM whose storage root key, attestation key, generated keys and
+    /// sealing nonces all come from `rng` instead of the OS.
+    pub fn with_rng(rng: EntropySource) -> Self {
+        Self::with_entropy(Entropy::new(rng))
+    }
+
+    fn with_entropy(entropy: Entropy) -> Self {
+        let mut storage_root_key = [0u8; 32];
+        entropy.fill_bytes(&mut storage_root_key);
+        Self::with_storage_root_key(storage_root_key, entropy)
+    }
+
+    fn with_storage_root_key(storage_root_key: [u8; 32], entropy: Entropy) -> Self {
         Self {
             private_keys: Arc::new(RwLock::new(HashMap::new())),
-            attestation_key: SigningKey::generate(&mut csprng),
+            attestation_key: entropy.signing_key(),
+            storage_root_key,
+            signing_windows: Arc::new(RwLock::new(HashMap::new())),
+            entropy,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Replaces the wall clock that times signing rate-limit windows.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Opens a TPM whose non-volatile storage (the storage root key) lives at
+    /// `nv_path`, creating it on first use. The file stands in for the chip's
+    /// own NV memory so a simulated vehicle keeps its TPM across restarts.
+    pub fn open(nv_path: &str) -> Result<Self, String> {
+        if let Ok(bytes) = std::fs::read(nv_path) {
+            let srk = <[u8; 32]>::try_from(bytes.as_slice())
+                .map_err(|_| "Corrupt TPM NV storage".to_string())?;
+            return Ok(Self::with_storage_root_key(srk, Entropy::os()));
         }
+        let tpm = Self::new();
+        tpm.persist(nv_path)?;
+        Ok(tpm)
+    }
+
+    /// Writes the TPM's non-volatile storage to `nv_path`.
+    pub fn persist(&self, nv_path: &str) -> Result<(), String> {
+        std::fs::write(nv_path, self.storage_root_key)
+            .map_err(|e| format!("Failed to write TPM NV storage: {}", e))
     }
 
     pub async fn generate_key_pair(&self, key_id: &str) -> Vec<u8> {
-        let mut csprng = OsRng;
-        let signing_key = SigningKey::generate(&mut csprng);
+        let signing_key = self.entropy.signing_key();
         let public_key = signing_key.verifying_key().to_bytes().to_vec();
 
         self.private_keys
@@ -33,24 +132,155 @@ impl TrustedPlatformModule {
         public_key
     }
 
-    pub async fn sign_with_tpm(&self, key_id: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    /// Like `generate_key_pair`, but signing with the key is limited to
+    /// `max_per_sec` signatures in any one-second window.
+    pub async fn generate_key_pair_with_rate_limit(
+        &self,
+        key_id: &str,
+        max_per_sec: u32,
+    ) -> Vec<u8> {
+        let public_key = self.generate_key_pair(key_id).await;
+        self.signing_windows.write().await.insert(
+            key_id.to_string(),
+            SigningWindow {
+                max_per_sec,
+                recent: VecDeque::new(),
+            },
+        );
+        public_key
+    }
+
+    async fn check_rate_limit(&self, key_id: &str) -> Result<(), TpmError> {
+        let mut windows = self.signing_windows.write().await;
+        let Some(window) = windows.get_mut(key_id) else {
+            return Ok(());
+        };
+
+        let now = self.clock.now();
+        while window
+            .recent
+            .front()
+            .is_some_and(|t| now - *t >= chrono::Duration::seconds(1))
+        {
+            window.recent.pop_front();
+        }
+        if window.recent.len() >= window.max_per_sec as usize {
+            return Err(TpmError::RateLimited {
+                key_id: key_id.to_string(),
+                max_per_sec: window.max_per_sec,
+            });
+        }
+        window.recent.push_back(now);
+        Ok(())
+    }
+
+    pub async fn sign_with_tpm(&self, key_id: &str, data: &[u8]) -> Result<Vec<u8>, TpmError> {
+        if !self.private_keys.read().await.contains_key(key_id) {
+            return Err(TpmError::KeyNotFound);
+        }
+        self.check_rate_limit(key_id).await?;
+
         let keys = self.private_keys.read().await;
-        if let Some(secret_bytes) = keys.get(key_id) {
-            if secret_bytes.len() == 32 {
-                if let Ok(secret_array) = <&[u8; 32]>::try_from(secret_bytes.as_slice()) {
-                    let signing_key = SigningKey::from_bytes(secret_array);
-                    return Ok(signing_key.sign(data).to_bytes().to_vec());
-                }
-            }
+        if let Some(secret_bytes) = keys.get(key_id)
+            && let Ok(secret_array) = <&[u8; 32]>::try_from(secret_bytes.as_slice())
+        {
+            let signing_key = SigningKey::from_bytes(secret_array);
+            return Ok(signing_key.sign(data).to_bytes().to_vec());
+        }
+        Err(TpmError::KeyNotFound)
+    }
+
+    /// Exports a private key encrypted under the storage root key. The key id is
+    /// bound as associated data so a sealed blob cannot be loaded under another id.
+    pub async fn seal_key(&self, key_id: &str) -> Result<Vec<u8>, String> {
+        let keys = self.private_keys.read().await;
+        let secret_bytes = keys.get(key_id).ok_or("Key not found in TPM")?;
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.storage_root_key));
+        let mut nonce = [0u8; 12];
+        self.entropy.fill_bytes(&mut nonce);
+        let nonce = Nonce::from(nonce);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: secret_bytes,
+                    aad: key_id.as_bytes(),
+                },
+            )
+            .map_err(|_| "Failed to seal key".to_string())?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    /// Imports a blob produced by `seal_key` and returns its public key.
+    pub async fn unseal_key(&self, key_id: &str, sealed: &[u8]) -> Result<Vec<u8>, String> {
+        if sealed.len() < 12 {
+            return Err("Sealed key blob too short".to_string());
         }
-        Err("Key not found in TPM".to_string())
+        let (nonce, ciphertext) = sealed.split_at(12);
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.storage_root_key));
+        let secret_bytes = cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: key_id.as_bytes(),
+                },
+            )
+            .map_err(|_| "Failed to unseal key".to_string())?;
+        let secret_array = <[u8; 32]>::try_from(secret_bytes.as_slice())
+            .map_err(|_| "Unsealed key has wrong length".to_string())?;
+        let public_key = SigningKey::from_bytes(&secret_array)
+            .verifying_key()
+            .to_bytes()
+            .to_vec();
+
+        self.private_keys
+            .write()
+            .await
+            .insert(key_id.to_string(), secret_bytes);
+
+        Ok(public_key)
     }
 
     pub async fn secure_erase(&self, key_id: &str) -> bool {
+        self.signing_windows.write().await.remove(key_id);
         self.private_keys.write().await.remove(key_id).is_some()
     }
 
-    // pub async fn get_attestation_key(&self) -> Vec<u8> {
-    //     self.attestation_key.verifying_key().to_bytes().to_vec()
-    // }
+    /// Public half of the attestation key, which verifiers must already trust.
+    pub fn get_attestation_key(&self) -> Vec<u8> {
+        self.attestation_key.verifying_key().to_bytes().to_vec()
+    }
+
+    /// Attests that `key_id`'s private key lives in this TPM: signs its
+    /// public key and a fresh random nonce with the attestation key.
+    pub async fn attest(&self, key_id: &str) -> Result<Attestation, String> {
+        let keys = self.private_keys.read().await;
+        let secret_bytes = keys.get(key_id).ok_or("Key not found in TPM")?;
+        let secret_array = <&[u8; 32]>::try_from(secret_bytes.as_slice())
+            .map_err(|_| "Stored key has wrong length".to_string())?;
+        let public_key = SigningKey::from_bytes(secret_array)
+            .verifying_key()
+            .to_bytes()
+            .to_vec();
+        drop(keys);
+
+        let mut nonce = vec![0u8; 32];
+        self.entropy.fill_bytes(&mut nonce);
+        let signature = self
+            .attestation_key
+            .sign(&Attestation::statement(&public_key, &nonce))
+            .to_bytes()
+            .to_vec();
+        Ok(Attestation {
+            public_key,
+            nonce,
+            signature,
+        })
+    }
 }
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use tokio::time::Duration;
+
+/// Settings for an edge node that resizes its cache toward a target hit rate.
+#[derive(Debug, Clone)]
+pub struct AdaptiveCacheConfig {
+    /// Hit rate (percent) the node tries to stay at or above.
+    pub target_hit_rate: f64,
+    /// Once the hit rate exceeds the target by this many points the cache is
+    /// shrunk to release memory.
+    pub shrink_margin: f64,
+    pub min_size: usize,
+    pub max_size: usize,
+    /// Entries added or removed per adjustment.
+    pub step: usize,
+    pub interval: Duration,
+}
+
+impl Default for AdaptiveCacheConfig {
+    fn default() -> Self {
+        Self {
+            target_hit_rate: 85.0,
+            shrink_margin: 10.0,
+            min_size: 100,
+            max_size: 10_000,
+            step: 100,
+            interval: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheResizeEvent {
+    pub timestamp: DateTime<Utc>,
+    pub old_size: usize,
+    pub new_size: usize,
+    /// Hit rate observed over the interval that triggered the resize.
+    pub hit_rate: f64,
+}
@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum EdgeError {
+    #[error("Certificate not found")]
+    NotFound,
+    /// Returned instead of queueing when too many authentications are in flight.
+    #[error("Edge node overloaded ({in_flight} authentications in flight)")]
+    Overloaded { in_flight: usize },
+    /// The node's chain does not start from the expected network genesis.
+    #[error("Wrong network: expected genesis {expected}, found {actual}")]
+    WrongNetwork { expected: String, actual: String },
+    /// The on-chain certificate was issued by a different CA than its id claims.
+    #[error("Certificate {cert_id} claims issuer {issuer_ca} outside its id namespace")]
+    IssuerMismatch { cert_id: String, issuer_ca: String },
+    /// The on-chain certificate is not signed by any key its issuer is trusted under.
+    #[error("Certificate {0} does not carry a valid signature from its issuer")]
+    InvalidSignature(String),
+    /// The node has no trust anchor for the certificate's issuer, so its
+    /// signature cannot be checked.
+    #[error("No trusted key for issuer {0}")]
+    UntrustedIssuer(String),
+    /// The CRL is not signed by a trusted key of the CA it names.
+    #[error("CRL from {0} is not signed by a trusted key")]
+    UntrustedCrl(String),
+    /// The CRL's `next_update` has passed, so newer revocations may be missing.
+    #[error("CRL from {issuer} expired at {next_update}")]
+    StaleCrl { issuer: String, next_update: String },
+}
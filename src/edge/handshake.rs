@@ -0,0 +1,84 @@
+use super::error::EdgeError;
+use super::node::EdgeNode;
+use crate::pki::Certificate;
+use crate::vehicle::OnBoardUnit;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use thiserror::Error;
+
+/// First message of the V2I handshake: the RSU's certificate and a fresh
+/// nonce the vehicle must sign.
+#[derive(Debug, Clone)]
+pub struct HandshakeChallenge {
+    pub certificate: Certificate,
+    pub nonce: Vec<u8>,
+}
+
+/// The vehicle's reply: its certificate, its signature over the RSU's nonce,
+/// and a nonce of its own for the RSU to sign back.
+#[derive(Debug, Clone)]
+pub struct HandshakeResponse {
+    pub certificate: Certificate,
+    pub challenge_nonce: Vec<u8>,
+    pub challenge_signature: Vec<u8>,
+    pub client_nonce: Vec<u8>,
+}
+
+/// The RSU's acceptance, proving it holds its certified key.
+#[derive(Debug, Clone)]
+pub struct HandshakeAccept {
+    pub session_id: String,
+    pub expires_at: DateTime<Utc>,
+    pub client_nonce_signature: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum HandshakeError {
+    /// The node has no certificate to present; see `EdgeNode::with_identity`.
+    #[error("{0} has no certificate to present")]
+    NoIdentity(String),
+    #[error("certificate of {0} is not signed by a trusted CA or is not valid")]
+    UntrustedCertificate(String),
+    #[error("{0} did not prove possession of its certified key")]
+    BadSignature(String),
+    /// The nonce was never issued by this node, was already used, or timed out.
+    #[error("unknown or expired challenge")]
+    UnknownChallenge,
+    #[error("unknown or expired session {0}")]
+    InvalidSession(String),
+    #[error(transparent)]
+    Edge(#[from] EdgeError),
+}
+
+pub(crate) fn new_nonce() -> Vec<u8> {
+    let mut nonce = vec![0u8; 32];
+    OsRng.fill_bytes(&mut nonce);
+    nonce
+}
+
+/// What each side signs: the other side's nonce, tagged with the signer's
+/// role and certificate so a signature cannot be reflected back or reused.
+pub(crate) fn handshake_bytes(role: &str, signer: &Certificate, nonce: &[u8]) -> Vec<u8> {
+    let mut bytes = b"BBVPKI-V2I-HANDSHAKE".to_vec();
+    bytes.extend(role.as_bytes());
+    bytes.push(0);
+    bytes.extend(signer.id.as_bytes());
+    bytes.push(0);
+    bytes.extend(nonce);
+    bytes
+}
+
+/// Runs the whole handshake between `obu` and `node` in-process and returns
+/// the session id for later `send_to_node` calls. Either side rejecting the
+/// other's certificate or signature aborts it.
+pub async fn mutual_authenticate(
+    obu: &OnBoardUnit,
+    node: &EdgeNode,
+) -> Result<String, HandshakeError> {
+    let challenge = node.begin_handshake().await?;
+    let response = obu.respond_to_handshake(&challenge).await?;
+    let accept = node.complete_handshake(&response).await?;
+    obu.verify_handshake_accept(&challenge, &response, &accept)?;
+    Ok(accept.session_id)
+}
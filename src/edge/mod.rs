@@ -1,3 +1,11 @@
+pub mod adaptive;
+pub mod error;
+pub mod handshake;
 pub mod node;
 
-pub use node::EdgeNode;
+pub use adaptive::{AdaptiveCacheConfig, CacheResizeEvent};
+pub use error::EdgeError;
+pub use handshake::{
+    HandshakeAccept, HandshakeChallenge, HandshakeError, HandshakeResponse, mutual_authenticate,
+};
+pub use node::{CacheStats, DEFAULT_CACHE_TTL, EdgeNode};
@@ -1,82 +1,473 @@
-use crate::blockchain::Blockchain;
-use crate::pki::CertificateStatus;
+use super::adaptive::{AdaptiveCacheConfig, CacheResizeEvent};
+use super::error::EdgeError;
+use super::handshake::{
+    HandshakeAccept, HandshakeChallenge, HandshakeError, HandshakeResponse, handshake_bytes,
+    new_nonce,
+};
+use crate::blockchain::merkle::merkle_root;
+use crate::blockchain::{
+    Blockchain, BlockchainTransaction, ContentStore, InMemoryContentStore, InclusionProof,
+};
+use crate::crypto::{CONTEXT_V2I_HANDSHAKE, HardwareSecurityModule, verify_ed25519, with_context};
+use crate::pki::{Certificate, CertificateAuthority, CertificateStatus, Crl};
+use crate::task::TaskRegistry;
+use crate::task::registry::cancellation;
+use chrono::Utc;
 use lru::LruCache;
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use tokio::sync::RwLock;
 use tokio::time::{Duration, Instant};
 
+/// Cache counters from `EdgeNode::get_cache_stats`, explaining the hit rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    /// Includes lookups whose entry had expired.
+    pub misses: u64,
+    pub lru_evictions: u64,
+    pub ttl_expirations: u64,
+    /// Lookups of unknown ids answered by the negative cache; counted as
+    /// neither hits nor misses.
+    pub negative_hits: u64,
+    /// Entries currently cached.
+    pub size: usize,
+}
+
 pub struct EdgeNode {
     pub node_id: String,
     cache: Arc<RwLock<LruCache<String, (CertificateStatus, Instant)>>>,
+    /// Cached statuses older than this are re-read from the chain.
+    cache_ttl: Duration,
     blockchain_ref: Arc<Blockchain>,
     cache_hits: Arc<AtomicU64>,
     cache_misses: Arc<AtomicU64>,
+    /// Lookups that found an entry past the TTL; also counted as misses.
+    ttl_expirations: Arc<AtomicU64>,
+    /// Entries pushed out of the cache to make room, including by shrinking.
+    lru_evictions: Arc<AtomicU64>,
     neighboring_nodes: Arc<RwLock<Vec<String>>>,
+    resize_events: Arc<RwLock<Vec<CacheResizeEvent>>>,
+    /// Every key each CA has used, oldest first, so certificates signed before
+    /// a key rotation still verify.
+    ca_keyring: Arc<RwLock<HashMap<String, Vec<Vec<u8>>>>>,
+    max_in_flight: Option<usize>,
+    in_flight: Arc<AtomicUsize>,
+    shed_count: Arc<AtomicU64>,
+    /// Set when `verify_network` fails; returned for every authentication.
+    network_error: Arc<RwLock<Option<EdgeError>>>,
+    /// Recently confirmed unknown cert ids, so repeated lookups skip the chain scan.
+    negative_cache: Arc<RwLock<LruCache<String, Instant>>>,
+    negative_ttl: Duration,
+    negative_hits: Arc<AtomicU64>,
+    tasks: Option<Arc<TaskRegistry>>,
+    /// The RSU's own certificate, whose key the HSM holds under `node_id`.
+    identity: Option<(Arc<HardwareSecurityModule>, Certificate)>,
+    /// Handshake nonces handed out and not yet answered.
+    pending_challenges: Arc<RwLock<HashMap<Vec<u8>, Instant>>>,
+    /// Mutually authenticated sessions: id to (vehicle id, expiry).
+    sessions: Arc<RwLock<HashMap<String, (String, Instant)>>>,
+    session_ttl: Duration,
+    /// CA asked directly on cache misses for its own certificates, instead
+    /// of scanning the chain.
+    status_responder: Option<Arc<CertificateAuthority>>,
+    responder_queries: Arc<AtomicU64>,
+    /// Holds the payloads of transactions that keep only a content hash on-chain.
+    content_store: Arc<dyn ContentStore>,
+}
+
+/// How long a vehicle has to answer a handshake challenge.
+const CHALLENGE_TTL: Duration = Duration::from_secs(30);
+
+/// Decrements the in-flight counter however an authentication returns.
+struct InFlightGuard<'a>(&'a AtomicUsize);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
+/// Cache TTL used by `EdgeNode::new`.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60);
+
 impl EdgeNode {
     pub fn new(node_id: String, cache_size: usize, blockchain: Arc<Blockchain>) -> Self {
+        Self::with_ttl(node_id, cache_size, DEFAULT_CACHE_TTL, blockchain)
+    }
+
+    /// Like `new`, but cached statuses are re-read from the chain once they
+    /// are older than `ttl` instead of `DEFAULT_CACHE_TTL`.
+    pub fn with_ttl(
+        node_id: String,
+        cache_size: usize,
+        ttl: Duration,
+        blockchain: Arc<Blockchain>,
+    ) -> Self {
         Self {
             node_id,
             cache: Arc::new(RwLock::new(LruCache::new(
                 NonZeroUsize::new(cache_size).unwrap(),
             ))),
+            cache_ttl: ttl,
             blockchain_ref: blockchain,
             cache_hits: Arc::new(AtomicU64::new(0)),
             cache_misses: Arc::new(AtomicU64::new(0)),
+            ttl_expirations: Arc::new(AtomicU64::new(0)),
+            lru_evictions: Arc::new(AtomicU64::new(0)),
             neighboring_nodes: Arc::new(RwLock::new(Vec::new())),
+            resize_events: Arc::new(RwLock::new(Vec::new())),
+            ca_keyring: Arc::new(RwLock::new(HashMap::new())),
+            max_in_flight: None,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            shed_count: Arc::new(AtomicU64::new(0)),
+            network_error: Arc::new(RwLock::new(None)),
+            negative_cache: Arc::new(RwLock::new(LruCache::new(
+                NonZeroUsize::new(cache_size).unwrap(),
+            ))),
+            negative_ttl: Duration::from_secs(1),
+            negative_hits: Arc::new(AtomicU64::new(0)),
+            tasks: None,
+            identity: None,
+            pending_challenges: Arc::new(RwLock::new(HashMap::new())),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            session_ttl: Duration::from_secs(300),
+            status_responder: None,
+            responder_queries: Arc::new(AtomicU64::new(0)),
+            content_store: Arc::new(InMemoryContentStore::new()),
         }
     }
 
+    /// Gives the node a certificate to present in V2I handshakes. `hsm` must
+    /// hold the matching key under this node's id.
+    pub fn with_identity(
+        mut self,
+        hsm: Arc<HardwareSecurityModule>,
+        certificate: Certificate,
+    ) -> Self {
+        self.identity = Some((hsm, certificate));
+        self
+    }
+
+    /// Lifetime of sessions established by `complete_handshake`.
+    pub fn with_session_ttl(mut self, ttl: Duration) -> Self {
+        self.session_ttl = ttl;
+        self
+    }
+
+    /// Re-reads a certificate's status from the chain once its cache entry is
+    /// older than `ttl`.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// How long a "not found" result is remembered. Keep this short so a
+    /// freshly issued certificate does not stay invisible for long.
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Resolves cache misses for certificates issued by `ca` through its
+    /// `check_status` responder rather than the chain. Certificates `ca`
+    /// does not know are still looked up on the chain.
+    pub fn with_status_responder(mut self, ca: Arc<CertificateAuthority>) -> Self {
+        self.status_responder = Some(ca);
+        self
+    }
+
+    /// Store to fetch off-chain certificate payloads from; see
+    /// `BlockchainTransaction::for_certificate_off_chain`.
+    pub fn with_content_store(mut self, store: Arc<dyn ContentStore>) -> Self {
+        self.content_store = store;
+        self
+    }
+
+    /// Registers the node's background tasks with `tasks`, which can stop them.
+    pub fn with_task_registry(mut self, tasks: Arc<TaskRegistry>) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+
+    /// Sheds authentications with `EdgeError::Overloaded` once `limit`
+    /// requests are already in flight, keeping tail latency bounded.
+    pub fn with_max_in_flight(mut self, limit: usize) -> Self {
+        self.max_in_flight = Some(limit);
+        self
+    }
+
+    /// Confirms the node's chain starts from `expected_genesis_hash`. On a
+    /// mismatch the node refuses all further authentications.
+    pub async fn verify_network(&self, expected_genesis_hash: &str) -> Result<(), EdgeError> {
+        let actual = self.blockchain_ref.genesis_hash().await;
+        let result = if actual == expected_genesis_hash {
+            Ok(())
+        } else {
+            Err(EdgeError::WrongNetwork {
+                expected: expected_genesis_hash.to_string(),
+                actual,
+            })
+        };
+        *self.network_error.write().await = result.clone().err();
+        result
+    }
+
     pub async fn authenticate_certificate(
         &self,
         cert_id: &str,
-    ) -> Result<(CertificateStatus, u128), String> {
+    ) -> Result<(CertificateStatus, u128), EdgeError> {
         let start = Instant::now();
 
+        if let Some(err) = self.network_error.read().await.clone() {
+            return Err(err);
+        }
+
+        let in_flight = self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let _guard = InFlightGuard(&self.in_flight);
+        if let Some(limit) = self.max_in_flight
+            && in_flight >= limit
+        {
+            self.shed_count.fetch_add(1, Ordering::Relaxed);
+            return Err(EdgeError::Overloaded { in_flight });
+        }
+
         {
             let mut cache = self.cache.write().await;
-            if let Some((status, _)) = cache.get(cert_id) {
-                self.cache_hits.fetch_add(1, Ordering::Relaxed);
-                let latency = start.elapsed().as_nanos();
-                return Ok((status.clone(), latency));
+            if let Some((status, cached_at)) = cache.get(cert_id) {
+                if !self.is_stale(*cached_at) {
+                    self.cache_hits.fetch_add(1, Ordering::Relaxed);
+                    let latency = start.elapsed().as_nanos();
+                    return Ok((status.clone(), latency));
+                }
+                self.ttl_expirations.fetch_add(1, Ordering::Relaxed);
+                cache.pop(cert_id);
+            }
+        }
+
+        {
+            let mut negative = self.negative_cache.write().await;
+            if let Some(cached_at) = negative.get(cert_id) {
+                if cached_at.elapsed() < self.negative_ttl {
+                    self.negative_hits.fetch_add(1, Ordering::Relaxed);
+                    return Err(EdgeError::NotFound);
+                }
+                negative.pop(cert_id);
             }
         }
 
         self.cache_misses.fetch_add(1, Ordering::Relaxed);
 
-        let status = self.query_blockchain(cert_id).await?;
+        let status = match self.query_responder(cert_id).await {
+            Some(status) => Ok(status),
+            None => self.query_blockchain(cert_id).await,
+        };
+        let status = match status {
+            Ok(status) => status,
+            Err(EdgeError::NotFound) => {
+                self.negative_cache
+                    .write()
+                    .await
+                    .put(cert_id.to_string(), Instant::now());
+                return Err(EdgeError::NotFound);
+            }
+            Err(e) => return Err(e),
+        };
 
-        self.cache
-            .write()
-            .await
-            .put(cert_id.to_string(), (status.clone(), Instant::now()));
+        self.cache_status(cert_id.to_string(), status.clone(), Instant::now())
+            .await;
 
         let latency = start.elapsed().as_nanos();
         Ok((status, latency))
     }
 
-    async fn query_blockchain(&self, cert_id: &str) -> Result<CertificateStatus, String> {
+    async fn query_responder(&self, cert_id: &str) -> Option<CertificateStatus> {
+        let ca = self.status_responder.as_ref()?;
+        if Certificate::id_namespace(cert_id) != Some(ca.ca_id.as_str()) {
+            return None;
+        }
+        self.responder_queries.fetch_add(1, Ordering::Relaxed);
+        ca.check_status(cert_id).await.status
+    }
+
+    /// Cache misses answered by the status responder instead of the chain.
+    pub fn get_responder_queries(&self) -> u64 {
+        self.responder_queries.load(Ordering::Relaxed)
+    }
+
+    /// Resolves the status from the latest on-chain transaction for `cert_id`
+    /// and returns it with that transaction's inclusion proof, so the caller
+    /// can check the claim with `InclusionProof::verify` instead of trusting
+    /// this node. The certificate gets the same expiry, trust and signature
+    /// checks as `authenticate_certificate`. Bypasses the cache, which holds
+    /// no proofs. With `redacted` the proof carries only a commitment to the
+    /// certificate, so the caller learns the status without learning whose
+    /// certificate it is.
+    pub async fn authenticate_with_proof(
+        &self,
+        cert_id: &str,
+        redacted: bool,
+    ) -> Result<(CertificateStatus, InclusionProof), EdgeError> {
+        if let Some(err) = self.network_error.read().await.clone() {
+            return Err(err);
+        }
+        let proof = self
+            .blockchain_ref
+            .inclusion_proof(cert_id)
+            .await
+            .ok_or(EdgeError::NotFound)?;
+        let status = proof
+            .transaction
+            .tx_type
+            .resulting_status()
+            .ok_or(EdgeError::NotFound)?;
+        let history = self.certificate_history(cert_id).await;
+        let status = self.check_status(cert_id, status, &history).await?;
+        let proof = if redacted { proof.redact() } else { proof };
+        Ok((status, proof))
+    }
+
+    async fn query_blockchain(&self, cert_id: &str) -> Result<CertificateStatus, EdgeError> {
         tokio::time::sleep(Duration::from_micros(100)).await;
 
+        let history = self.certificate_history(cert_id).await;
+        // the newest transaction for the cert decides its status
+        let newest = history.first().ok_or(EdgeError::NotFound)?;
+        let status = newest
+            .tx_type
+            .resulting_status()
+            .unwrap_or(CertificateStatus::Active);
+        self.check_status(cert_id, status, &history).await
+    }
+
+    /// Every on-chain transaction for `cert_id`, newest first.
+    async fn certificate_history(&self, cert_id: &str) -> Vec<BlockchainTransaction> {
         let chain = self.blockchain_ref.chain.read().await;
-        for block in chain.iter().rev() {
-            for tx in &block.transactions {
-                if tx.tx_id.contains(cert_id) {
-                    return Ok(CertificateStatus::Active);
-                }
+        chain
+            .iter()
+            .rev()
+            .flat_map(|block| block.transactions.iter().rev())
+            .filter(|tx| tx.tx_id == cert_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Checks the certificate in `history` before `status`, the one its
+    /// newest transaction gives, is served.
+    async fn check_status(
+        &self,
+        cert_id: &str,
+        status: CertificateStatus,
+        history: &[BlockchainTransaction],
+    ) -> Result<CertificateStatus, EdgeError> {
+        // status transactions need not carry the certificate, so take it
+        // from the newest one that does
+        let cert = self.newest_certificate(history).await;
+        // revocations and suspensions only ever reject, but an active status
+        // is only served for a certificate whose signature can be checked
+        let Some(cert) = cert else {
+            return match status {
+                CertificateStatus::Active => Err(EdgeError::NotFound),
+                status => Ok(status),
+            };
+        };
+
+        if let Some(namespace) = Certificate::id_namespace(cert_id)
+            && cert.issuer_ca != namespace
+        {
+            return Err(EdgeError::IssuerMismatch {
+                cert_id: cert_id.to_string(),
+                issuer_ca: cert.issuer_ca,
+            });
+        }
+        if status != CertificateStatus::Active {
+            return Ok(status);
+        }
+        if cert.is_expired() {
+            return Ok(CertificateStatus::Expired);
+        }
+        if self.get_ca_keys(&cert.issuer_ca).await.is_empty() {
+            return Err(EdgeError::UntrustedIssuer(cert.issuer_ca));
+        }
+        if !self.verify_certificate_signature(&cert).await {
+            return Err(EdgeError::InvalidSignature(cert_id.to_string()));
+        }
+        Ok(status)
+    }
+
+    /// First certificate in `history` that decodes, fetching off-chain
+    /// payloads from the content store.
+    async fn newest_certificate(&self, history: &[BlockchainTransaction]) -> Option<Certificate> {
+        for tx in history {
+            if let Ok(cert) = tx
+                .decode_certificate_from(self.content_store.as_ref())
+                .await
+            {
+                return Some(cert);
             }
         }
-        Err("Certificate not found".to_string())
+        None
+    }
+
+    pub fn get_in_flight_count(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    pub fn get_shed_count(&self) -> u64 {
+        self.shed_count.load(Ordering::Relaxed)
+    }
+
+    /// Lookups whose cached status had outlived the TTL and was re-read from
+    /// the chain. These are included in the miss count, not the hit rate.
+    pub fn get_ttl_expired_count(&self) -> u64 {
+        self.ttl_expirations.load(Ordering::Relaxed)
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl
+    }
+
+    /// Lookups answered from the negative cache without scanning the chain.
+    pub fn get_negative_cache_hits(&self) -> u64 {
+        self.negative_hits.load(Ordering::Relaxed)
     }
 
     pub async fn propagate_revocation(&self, cert_id: &str) {
-        self.cache.write().await.put(
-            cert_id.to_string(),
-            (CertificateStatus::Revoked, Instant::now()),
-        );
+        self.propagate_status(cert_id, CertificateStatus::Revoked)
+            .await;
+    }
+
+    /// Pushes any status change into the cache, including reversible ones
+    /// such as a suspension being lifted.
+    pub async fn propagate_status(&self, cert_id: &str, status: CertificateStatus) {
+        self.negative_cache.write().await.pop(cert_id);
+        self.cache_status(cert_id.to_string(), status, Instant::now())
+            .await;
+    }
+
+    /// Inserts or refreshes an entry, counting any entry it pushes out.
+    async fn cache_status(&self, cert_id: String, status: CertificateStatus, cached_at: Instant) {
+        let evicted = self
+            .cache
+            .write()
+            .await
+            .push(cert_id.clone(), (status, cached_at));
+        if evicted.is_some_and(|(evicted_id, _)| evicted_id != cert_id) {
+            self.lru_evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub async fn get_cache_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
+            lru_evictions: self.lru_evictions.load(Ordering::Relaxed),
+            ttl_expirations: self.ttl_expirations.load(Ordering::Relaxed),
+            negative_hits: self.negative_hits.load(Ordering::Relaxed),
+            size: self.cache.read().await.len(),
+        }
     }
 
     pub async fn get_cache_hit_rate(&self) -> f64 {
@@ -91,7 +482,390 @@ impl EdgeNode {
         (hits as f64 / total as f64) * 100.0
     }
 
+    fn is_stale(&self, cached_at: Instant) -> bool {
+        cached_at.elapsed() >= self.cache_ttl
+    }
+
+    /// Snapshot of the cache for a warm restart, least recently used first so
+    /// `import_cache` restores the same recency order. Entries already past
+    /// the TTL are left out, since an `Instant` cannot be carried across
+    /// processes to check their age later.
+    pub async fn export_cache(&self) -> Vec<(String, CertificateStatus)> {
+        self.cache
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|(_, (_, cached_at))| !self.is_stale(*cached_at))
+            .map(|(id, (status, _))| (id.clone(), status.clone()))
+            .collect()
+    }
+
+    /// Loads entries from `export_cache`. Each starts a fresh TTL from now.
+    pub async fn import_cache(&self, entries: Vec<(String, CertificateStatus)>) {
+        let now = Instant::now();
+        for (cert_id, status) in entries {
+            self.cache_status(cert_id, status, now).await;
+        }
+    }
+
+    pub async fn get_cache_capacity(&self) -> usize {
+        self.cache.read().await.cap().get()
+    }
+
+    /// Starts a background task that, every `config.interval`, grows the cache
+    /// when the hit rate over the last interval is below target and shrinks it
+    /// when the rate is comfortably above target. Abort the handle, or shut
+    /// down the node's task registry, to stop it.
+    pub fn spawn_adaptive_sizing(
+        self: Arc<Self>,
+        config: AdaptiveCacheConfig,
+    ) -> tokio::task::JoinHandle<()> {
+        let cancelled = cancellation(self.tasks.as_ref());
+        let tasks = self.tasks.clone();
+        let name = format!("adaptive-cache:{}", self.node_id);
+
+        let handle = tokio::spawn(async move {
+            tokio::pin!(cancelled);
+            let mut ticker = tokio::time::interval(config.interval);
+            ticker.tick().await;
+            let mut last_hits = self.cache_hits.load(Ordering::Relaxed);
+            let mut last_misses = self.cache_misses.load(Ordering::Relaxed);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = &mut cancelled => break,
+                }
+                let hits = self.cache_hits.load(Ordering::Relaxed);
+                let misses = self.cache_misses.load(Ordering::Relaxed);
+                let window_hits = hits - last_hits;
+                let window_total = window_hits + (misses - last_misses);
+                last_hits = hits;
+                last_misses = misses;
+
+                if window_total == 0 {
+                    continue;
+                }
+                let hit_rate = (window_hits as f64 / window_total as f64) * 100.0;
+                self.adjust_cache_size(&config, hit_rate).await;
+            }
+        });
+        if let Some(tasks) = tasks {
+            tasks.register(name, &handle);
+        }
+        handle
+    }
+
+    async fn adjust_cache_size(&self, config: &AdaptiveCacheConfig, hit_rate: f64) {
+        let mut cache = self.cache.write().await;
+        let old_size = cache.cap().get();
+
+        let new_size = if hit_rate < config.target_hit_rate {
+            (old_size + config.step).min(config.max_size)
+        } else if hit_rate > config.target_hit_rate + config.shrink_margin {
+            old_size.saturating_sub(config.step).max(config.min_size)
+        } else {
+            old_size
+        };
+
+        if new_size == old_size {
+            return;
+        }
+        if let Some(size) = NonZeroUsize::new(new_size) {
+            let before = cache.len();
+            cache.resize(size);
+            let evicted = before - cache.len();
+            drop(cache);
+            self.lru_evictions
+                .fetch_add(evicted as u64, Ordering::Relaxed);
+            self.resize_events.write().await.push(CacheResizeEvent {
+                timestamp: Utc::now(),
+                old_size,
+                new_size,
+                hit_rate,
+            });
+        }
+    }
+
+    pub async fn get_resize_events(&self) -> Vec<CacheResizeEvent> {
+        self.resize_events.read().await.clone()
+    }
+
+    pub async fn trust_ca(&self, ca_id: &str, public_key: Vec<u8>) {
+        let mut keyring = self.ca_keyring.write().await;
+        let keys = keyring.entry(ca_id.to_string()).or_default();
+        if !keys.contains(&public_key) {
+            keys.push(public_key);
+        }
+    }
+
+    pub async fn get_ca_keys(&self, ca_id: &str) -> Vec<Vec<u8>> {
+        self.ca_keyring
+            .read()
+            .await
+            .get(ca_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Seeds the CA keyring from the `TrustAnchor` transactions in the genesis
+    /// block, so the trust store comes from the network rather than separate
+    /// configuration. Pair with `verify_network` to be sure the genesis is the
+    /// expected one. Returns the number of keys added.
+    pub async fn load_genesis_trust_anchors(&self) -> usize {
+        let records: Vec<_> = {
+            let chain = self.blockchain_ref.chain.read().await;
+            let Some(genesis) = chain.first() else {
+                return 0;
+            };
+            if genesis.merkle_root != merkle_root(&genesis.transactions) {
+                log::warn!(
+                    "{}: genesis block does not match its Merkle root",
+                    self.node_id
+                );
+                return 0;
+            }
+            genesis
+                .transactions
+                .iter()
+                .filter_map(|tx| tx.trust_anchor_record())
+                .collect()
+        };
+
+        let mut keyring = self.ca_keyring.write().await;
+        let mut added = 0;
+        for record in records {
+            let keys = keyring.entry(record.ca_id).or_default();
+            for key in record.public_keys {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
+
+    /// Replays `KeyRotation` transactions from the chain. A rotation is only
+    /// accepted when it is signed by a key already trusted for that CA, so a
+    /// new key is learned through the chain of continuity signatures.
+    /// Returns the number of keys added.
+    pub async fn sync_ca_keyring(&self) -> usize {
+        let records: Vec<_> = {
+            let chain = self.blockchain_ref.chain.read().await;
+            chain
+                .iter()
+                .flat_map(|b| b.transactions.iter())
+                .filter_map(|tx| tx.key_rotation_record())
+                .collect()
+        };
+
+        let mut keyring = self.ca_keyring.write().await;
+        let mut added = 0;
+        for record in records {
+            let Some(keys) = keyring.get_mut(&record.ca_id) else {
+                continue;
+            };
+            if keys.contains(&record.old_public_key)
+                && !keys.contains(&record.new_public_key)
+                && record.verify()
+            {
+                keys.push(record.new_public_key);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Warms the cache with every revocation on `crl`, after checking it is
+    /// current and signed by a trusted key of its issuer. Entries for another
+    /// CA's certificates are skipped. Returns how many entries were cached.
+    pub async fn ingest_crl(&self, crl: &Crl) -> Result<usize, EdgeError> {
+        if !crl.is_current_at(Utc::now()) {
+            return Err(EdgeError::StaleCrl {
+                issuer: crl.issuer.clone(),
+                next_update: crl.next_update.to_rfc3339(),
+            });
+        }
+        let trusted = self
+            .ca_keyring
+            .read()
+            .await
+            .get(&crl.issuer)
+            .is_some_and(|keys| keys.iter().any(|key| crl.verify(key)));
+        if !trusted {
+            return Err(EdgeError::UntrustedCrl(crl.issuer.clone()));
+        }
+
+        let mut cached = 0;
+        for entry in &crl.entries {
+            // a CA may only revoke certificates in its own namespace
+            if Certificate::id_namespace(&entry.cert_id) != Some(crl.issuer.as_str()) {
+                log::warn!(
+                    "{}: skipped CRL entry {} outside issuer {}",
+                    self.node_id,
+                    entry.cert_id,
+                    crl.issuer
+                );
+                continue;
+            }
+            self.propagate_revocation(&entry.cert_id).await;
+            cached += 1;
+        }
+        Ok(cached)
+    }
+
+    /// Checks a certificate against any key its issuer has used.
+    pub async fn verify_certificate_signature(&self, cert: &Certificate) -> bool {
+        let keyring = self.ca_keyring.read().await;
+        keyring
+            .get(&cert.issuer_ca)
+            .is_some_and(|keys| keys.iter().any(|key| cert.verify(key)))
+    }
+
+    /// Opens a V2I handshake: presents this node's certificate and a nonce
+    /// for the vehicle to sign.
+    pub async fn begin_handshake(&self) -> Result<HandshakeChallenge, HandshakeError> {
+        let (_, certificate) = self
+            .identity
+            .as_ref()
+            .ok_or_else(|| HandshakeError::NoIdentity(self.node_id.clone()))?;
+        let nonce = new_nonce();
+        let mut pending = self.pending_challenges.write().await;
+        pending.retain(|_, issued| issued.elapsed() < CHALLENGE_TTL);
+        pending.insert(nonce.clone(), Instant::now());
+        Ok(HandshakeChallenge {
+            certificate: certificate.clone(),
+            nonce,
+        })
+    }
+
+    /// Checks the vehicle's certificate against the CA keyring and its
+    /// signature over our nonce, then signs the vehicle's nonce and opens a
+    /// session. Each challenge can be answered once.
+    pub async fn complete_handshake(
+        &self,
+        response: &HandshakeResponse,
+    ) -> Result<HandshakeAccept, HandshakeError> {
+        let (hsm, certificate) = self
+            .identity
+            .as_ref()
+            .ok_or_else(|| HandshakeError::NoIdentity(self.node_id.clone()))?;
+        let issued = self
+            .pending_challenges
+            .write()
+            .await
+            .remove(&response.challenge_nonce);
+        if issued.is_none_or(|issued| issued.elapsed() >= CHALLENGE_TTL) {
+            return Err(HandshakeError::UnknownChallenge);
+        }
+
+        let vehicle_cert = &response.certificate;
+        if !vehicle_cert.is_valid() || !self.verify_certificate_signature(vehicle_cert).await {
+            return Err(HandshakeError::UntrustedCertificate(
+                vehicle_cert.vehicle_id.clone(),
+            ));
+        }
+        let signed = with_context(
+            CONTEXT_V2I_HANDSHAKE,
+            &handshake_bytes("vehicle", vehicle_cert, &response.challenge_nonce),
+        );
+        if !verify_ed25519(
+            &vehicle_cert.public_key,
+            &signed,
+            &response.challenge_signature,
+        ) {
+            return Err(HandshakeError::BadSignature(
+                vehicle_cert.vehicle_id.clone(),
+            ));
+        }
+
+        let client_nonce_signature = hsm
+            .sign_certificate(
+                &self.node_id,
+                &with_context(
+                    CONTEXT_V2I_HANDSHAKE,
+                    &handshake_bytes("node", certificate, &response.client_nonce),
+                ),
+            )
+            .await
+            .map_err(|_| HandshakeError::NoIdentity(self.node_id.clone()))?;
+
+        let session_id = hex::encode(new_nonce());
+        let expires_at = Utc::now()
+            + chrono::Duration::from_std(self.session_ttl).unwrap_or(chrono::Duration::MAX);
+        let mut sessions = self.sessions.write().await;
+        sessions.retain(|_, (_, expiry)| *expiry > Instant::now());
+        sessions.insert(
+            session_id.clone(),
+            (
+                vehicle_cert.vehicle_id.clone(),
+                Instant::now() + self.session_ttl,
+            ),
+        );
+
+        Ok(HandshakeAccept {
+            session_id,
+            expires_at,
+            client_nonce_signature,
+        })
+    }
+
+    /// Vehicle id behind a live session, if any.
+    pub async fn session_peer(&self, session_id: &str) -> Option<String> {
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .filter(|(_, expiry)| *expiry > Instant::now())
+            .map(|(vehicle_id, _)| vehicle_id.clone())
+    }
+
+    /// `authenticate_certificate` on behalf of a mutually authenticated vehicle.
+    pub async fn authenticate_in_session(
+        &self,
+        session_id: &str,
+        cert_id: &str,
+    ) -> Result<(CertificateStatus, u128), HandshakeError> {
+        if self.session_peer(session_id).await.is_none() {
+            return Err(HandshakeError::InvalidSession(session_id.to_string()));
+        }
+        Ok(self.authenticate_certificate(cert_id).await?)
+    }
+
+    /// Records `node_id` as a neighbor for `V2VNetwork::propagate_revocation_to_neighbors`.
+    /// The id must match a node registered on the same network to be reached;
+    /// duplicates and the node's own id are ignored.
     pub async fn add_neighboring_node(&self, node_id: String) {
-        self.neighboring_nodes.write().await.push(node_id);
+        if node_id == self.node_id {
+            return;
+        }
+        let mut neighbors = self.neighboring_nodes.write().await;
+        if !neighbors.contains(&node_id) {
+            neighbors.push(node_id);
+        }
+    }
+
+    pub async fn neighbors(&self) -> Vec<String> {
+        self.neighboring_nodes.read().await.clone()
+    }
+}
+
+/// Test-only inspection API, enabled by the `test-harness` feature.
+#[cfg(feature = "test-harness")]
+impl EdgeNode {
+    /// Cached entries from most to least recently used.
+    pub async fn cache_contents(&self) -> Vec<(String, CertificateStatus)> {
+        self.cache
+            .read()
+            .await
+            .iter()
+            .map(|(id, (status, _))| (id.clone(), status.clone()))
+            .collect()
+    }
+
+    pub async fn neighbors_snapshot(&self) -> Vec<String> {
+        self.neighboring_nodes.read().await.clone()
     }
 }
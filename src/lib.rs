@@ -4,4 +4,6 @@ pub mod edge;
 pub mod metrics;
 pub mod network;
 pub mod pki;
+pub mod task;
+pub mod time;
 pub mod vehicle;
@@ -1,59 +1,243 @@
-mod blockchain;
-mod crypto;
-mod edge;
-mod metrics;
-mod network;
-mod pki;
-mod vehicle;
-
-use crate::blockchain::{Blockchain, BlockchainTransaction, TransactionType};
-use crate::crypto::HardwareSecurityModule;
-use crate::edge::EdgeNode;
-use crate::metrics::PerformanceMetrics;
-use crate::network::{HyperledgerFabricGateway, LedgerGateway, V2VNetwork};
-use crate::pki::CertificateAuthority;
-use crate::vehicle::{BBVPKIClientSDK, OnBoardUnit};
+use bb_vpki::blockchain::{
+    Blockchain, BlockchainTransaction, ConsensusEngine, DataEncoding, GenesisConfig, PbftSimulated,
+    ProofOfWork, TransactionType,
+};
+use bb_vpki::crypto::{HardwareSecurityModule, KeyAlgorithm, verify_ed25519};
+use bb_vpki::edge::EdgeNode;
+use bb_vpki::metrics::{MetricTargets, PerformanceMetrics};
+use bb_vpki::network::{HyperledgerFabricGateway, LedgerGateway, V2VNetwork};
+use bb_vpki::pki::{Certificate, CertificateAuthority, CertificateStatus, RevocationError};
+use bb_vpki::task::TaskRegistry;
+use bb_vpki::vehicle::{BBVPKIClientSDK, OnBoardUnit};
 
 use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tokio::time::Instant;
+use tokio::time::{Duration, Instant, MissedTickBehavior};
+
+/// Outcome of a paced issuance run.
+#[derive(Debug, Clone)]
+pub struct SustainedIssuanceResult {
+    pub target_tps: f64,
+    pub achieved_tps: f64,
+    pub issued: usize,
+    pub latency_p50_ms: f64,
+    pub latency_p95_ms: f64,
+    pub latency_p99_ms: f64,
+    /// Largest delay between a scheduled issuance slot and when it actually fired.
+    pub max_schedule_lag_ms: f64,
+    /// Whether the achieved rate stayed within 5% of the target.
+    pub kept_up: bool,
+}
+
+/// One offered-load step of an issuance sweep.
+#[derive(Debug, Clone)]
+pub struct SweepPoint {
+    pub offered_tps: f64,
+    pub achieved_tps: f64,
+    pub latency_p50_ms: f64,
+    pub latency_p95_ms: f64,
+    pub latency_p99_ms: f64,
+}
+
+impl From<&SustainedIssuanceResult> for SweepPoint {
+    fn from(result: &SustainedIssuanceResult) -> Self {
+        Self {
+            offered_tps: result.target_tps,
+            achieved_tps: result.achieved_tps,
+            latency_p50_ms: result.latency_p50_ms,
+            latency_p95_ms: result.latency_p95_ms,
+            latency_p99_ms: result.latency_p99_ms,
+        }
+    }
+}
+
+/// Writes a sweep as CSV, one row per offered rate.
+fn save_sweep_csv(points: &[SweepPoint], filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut wtr = csv::Writer::from_path(filename)?;
+    wtr.write_record([
+        "offered_tps",
+        "achieved_tps",
+        "latency_p50_ms",
+        "latency_p95_ms",
+        "latency_p99_ms",
+    ])?;
+    for point in points {
+        wtr.write_record([
+            point.offered_tps.to_string(),
+            point.achieved_tps.to_string(),
+            point.latency_p50_ms.to_string(),
+            point.latency_p95_ms.to_string(),
+            point.latency_p99_ms.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Time-to-global-revocation: how long until each edge node serves `Revoked`.
+#[derive(Debug, Clone)]
+pub struct RevocationConvergence {
+    /// Per node, ms from the start of revocation until the node returned
+    /// `Revoked`; `None` if it had not converged by the timeout.
+    pub per_node_ms: Vec<(String, Option<f64>)>,
+    /// Until the last node converged; `None` if any node did not.
+    pub full_convergence_ms: Option<f64>,
+}
+
+// a fresh, valid vehicle key; the CA refuses weak keys such as all zeros
+fn generate_public_key() -> Vec<u8> {
+    ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng)
+        .verifying_key()
+        .to_bytes()
+        .to_vec()
+}
+
+// nearest-rank percentile over an already sorted slice
+fn nearest_rank(sorted: &[u128], quant: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let idx = ((quant * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1;
+    sorted[idx] as f64
+}
+
+/// Average time (ms) to mine a one-transaction block at `difficulty` with
+/// `workers` nonce-search threads.
+async fn benchmark_mining(difficulty: u32, workers: usize, blocks: usize) -> f64 {
+    let chain = Blockchain::new_with_consensus(Arc::new(
+        ProofOfWork::new(difficulty).with_workers(workers),
+    ));
+    let start = Instant::now();
+    for i in 0..blocks {
+        chain
+            .add_transaction(BlockchainTransaction::new(
+                format!("MINE-BENCH-{}", i),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+        chain.mine_pending_transactions().await;
+    }
+    start.elapsed().as_secs_f64() * 1000.0 / blocks as f64
+}
+
+/// Average consensus latency (ms) per block on a chain sealed by `engine`,
+/// from the chain's own consensus metrics.
+async fn benchmark_consensus(engine: Arc<dyn ConsensusEngine>, blocks: usize) -> f64 {
+    let chain = Blockchain::new_with_consensus(engine);
+    for i in 0..blocks {
+        chain
+            .add_transaction(BlockchainTransaction::new(
+                format!("CONSENSUS-BENCH-{}", i),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+        chain.mine_pending_transactions().await;
+    }
+    chain.get_average_consensus_latency_ms().await
+}
 
-pub struct BBVPKISystem {
+/// Average time (μs) for an HSM to sign a certificate-sized payload with a
+/// key of `algorithm`, and to verify the result.
+async fn benchmark_key_algorithm(algorithm: KeyAlgorithm, iterations: usize) -> (f64, f64) {
+    let hsm = HardwareSecurityModule::new();
+    let public_key = hsm
+        .generate_ca_keypair_with_algorithm("CA-BENCH", algorithm)
+        .await;
+    let payload = [0x42u8; 256];
+
+    let mut signatures = Vec::with_capacity(iterations);
+    let start = Instant::now();
+    for _ in 0..iterations {
+        signatures.push(hsm.sign_certificate("CA-BENCH", &payload).await.unwrap());
+    }
+    let sign_us = start.elapsed().as_secs_f64() * 1e6 / iterations as f64;
+
+    let start = Instant::now();
+    for signature in &signatures {
+        assert!(algorithm.verify(&public_key, &payload, signature));
+    }
+    let verify_us = start.elapsed().as_secs_f64() * 1e6 / iterations as f64;
+    (sign_us, verify_us)
+}
+
+/// Why a `BBVPKISystem` could not be built.
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SystemError {
+    /// The benchmarks need at least one of every component.
+    #[error("system needs at least one {0}")]
+    NoComponents(&'static str),
+}
+
+/// How long `issue_and_confirm` waits for a certificate to become queryable.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Generic over the ledger gateway so submits go through its native async
+/// methods rather than the boxing `DynLedgerGateway` adapter.
+pub struct BBVPKISystem<G: LedgerGateway = HyperledgerFabricGateway> {
     pub cas: Vec<Arc<CertificateAuthority>>,
     pub blockchain: Arc<Blockchain>,
     pub edge_nodes: Vec<Arc<EdgeNode>>,
     pub vehicles: Vec<Arc<Mutex<OnBoardUnit>>>,
     pub hsm: Arc<HardwareSecurityModule>,
     pub network: Arc<V2VNetwork>,
-    pub gateway: Option<Arc<dyn LedgerGateway>>,
+    pub gateway: Option<Arc<G>>,
+    /// Encoding used for certificate payloads written to the chain.
+    pub data_encoding: DataEncoding,
+    /// Extra attempts the revocation benchmark makes after a transient failure.
+    pub revocation_retries: u32,
+    /// Gateway writes that failed and still need to be replayed.
+    gateway_retry_queue: Arc<Mutex<Vec<BlockchainTransaction>>>,
     start_time: Instant,
+    /// Background tasks of the system's components, stopped by `shutdown`.
+    tasks: Arc<TaskRegistry>,
 }
 
-impl BBVPKISystem {
+impl<G: LedgerGateway + 'static> BBVPKISystem<G> {
     pub async fn new(
         num_cas: usize,
         num_edge_nodes: usize,
         num_vehicles: usize,
-        gateway: Option<Arc<dyn LedgerGateway>>,
-    ) -> Self {
-        let blockchain = Arc::new(Blockchain::new(2));
+        gateway: Option<Arc<G>>,
+        tasks: Arc<TaskRegistry>,
+    ) -> Result<Self, SystemError> {
+        if num_cas == 0 {
+            return Err(SystemError::NoComponents("certificate authority"));
+        }
+        if num_edge_nodes == 0 {
+            return Err(SystemError::NoComponents("edge node"));
+        }
+        if num_vehicles == 0 {
+            return Err(SystemError::NoComponents("vehicle"));
+        }
+
         let hsm = Arc::new(HardwareSecurityModule::new());
         let network = Arc::new(V2VNetwork::new());
 
         let mut cas = Vec::new();
+        let mut genesis = GenesisConfig::new("bbvpki-benchmark");
         for i in 0..num_cas {
-            let ca = Arc::new(CertificateAuthority::new(format!("CA-{}", i), hsm.clone()).await);
+            let ca_id = format!("CA-{}", i);
+            let ca = Arc::new(CertificateAuthority::new(ca_id.clone(), hsm.clone()).await);
+            if let Some(key) = hsm.get_public_key(&ca_id).await {
+                genesis = genesis.with_trusted_ca(&ca_id, key);
+            }
             cas.push(ca);
         }
 
+        let blockchain = Arc::new(
+            Blockchain::with_genesis(2, genesis.build_block()).with_task_registry(tasks.clone()),
+        );
+
         let mut edge_nodes = Vec::new();
         for i in 0..num_edge_nodes {
-            let node = Arc::new(EdgeNode::new(
-                format!("RSU-{}", i),
-                1000,
-                blockchain.clone(),
-            ));
+            let node = Arc::new(
+                EdgeNode::new(format!("RSU-{}", i), 1000, blockchain.clone())
+                    .with_task_registry(tasks.clone()),
+            );
+            node.load_genesis_trust_anchors().await;
             network.register_edge_node(node.clone()).await;
             edge_nodes.push(node);
         }
@@ -75,7 +259,7 @@ impl BBVPKISystem {
             vehicles.push(obu);
         }
 
-        Self {
+        Ok(Self {
             cas,
             blockchain,
             edge_nodes,
@@ -83,8 +267,22 @@ impl BBVPKISystem {
             hsm,
             network,
             gateway,
+            data_encoding: DataEncoding::Json,
+            revocation_retries: 2,
+            gateway_retry_queue: Arc::new(Mutex::new(Vec::new())),
             start_time: Instant::now(),
-        }
+            tasks,
+        })
+    }
+
+    pub fn active_task_count(&self) -> usize {
+        self.tasks.active_task_count()
+    }
+
+    /// Stops every registered background task, giving them `timeout` to
+    /// finish cleanly. Returns the names of any that had to be aborted.
+    pub async fn shutdown(&self, timeout: Duration) -> Vec<String> {
+        self.tasks.shutdown(timeout).await
     }
 
     pub async fn benchmark_issuance_rate(&self, num_requests: usize) -> (f64, Option<f64>) {
@@ -97,17 +295,21 @@ impl BBVPKISystem {
             let blockchain = self.blockchain.clone();
             let gateway = self.gateway.clone();
             let latencies_ref = submit_latencies.clone();
+            let encoding = self.data_encoding;
+            let retry_queue = self.gateway_retry_queue.clone();
 
             let handle = tokio::spawn(async move {
                 let vehicle_id = format!("VEH-{}", i);
-                let public_key = vec![0u8; 32];
+                let public_key = generate_public_key();
 
-                let cert = ca.issue_certificate(vehicle_id, public_key).await;
+                let Ok(cert) = ca.issue_certificate(vehicle_id, public_key).await else {
+                    return;
+                };
 
-                let tx = BlockchainTransaction::new(
-                    cert.id.clone(),
+                let tx = BlockchainTransaction::for_certificate(
                     TransactionType::CertificateIssuance,
-                    serde_json::to_vec(&cert).unwrap(),
+                    &cert,
+                    encoding,
                 );
 
                 // Always add to blockchain for metrics (TPS, throughput, consensus latency)
@@ -116,9 +318,13 @@ impl BBVPKISystem {
                 // Also submit to gateway if present (dual-write for realism)
                 if let Some(gw) = &gateway {
                     let s = tokio::time::Instant::now();
-                    let _ = gw.submit_transaction(&tx).await;
+                    let submitted = gw.submit_transaction(&tx).await;
                     let elapsed = s.elapsed().as_millis();
-                    latencies_ref.lock().await.push(elapsed as u128);
+                    match submitted {
+                        Ok(()) => latencies_ref.lock().await.push(elapsed),
+                        // keep the write so the gateway can catch up with the local chain
+                        Err(_) => retry_queue.lock().await.push(tx),
+                    }
                 }
             });
 
@@ -142,17 +348,184 @@ impl BBVPKISystem {
         }
     }
 
-    pub async fn benchmark_revocation_latency(&self, cert_id: &str) -> f64 {
+    /// Issues certificates at a steady `target_tps` for `duration` rather than
+    /// all at once, to check whether the CAs can sustain that rate.
+    pub async fn benchmark_issuance_sustained(
+        &self,
+        target_tps: f64,
+        duration: Duration,
+    ) -> SustainedIssuanceResult {
+        let period = Duration::from_secs_f64(1.0 / target_tps.max(f64::MIN_POSITIVE));
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
         let start = Instant::now();
+        let mut handles = vec![];
+        let mut max_lag = Duration::ZERO;
+        let mut i = 0usize;
 
-        let ca = &self.cas[0];
-        let _revocation_time = match ca.revoke_certificate(cert_id).await {
-            Ok(t) => t,
-            Err(e) => {
-                println!("Warning: failed to revoke certificate '{}': {}", cert_id, e);
-                return 0.0; // indicate failure to revoke
+        while start.elapsed() < duration {
+            let scheduled = ticker.tick().await;
+            max_lag = max_lag.max(Instant::now().saturating_duration_since(scheduled));
+
+            let ca = self.cas[i % self.cas.len()].clone();
+            let blockchain = self.blockchain.clone();
+            let encoding = self.data_encoding;
+            let vehicle_id = format!("VEH-SUSTAINED-{}", i);
+            handles.push(tokio::spawn(async move {
+                let s = Instant::now();
+                let cert = ca
+                    .issue_certificate(vehicle_id, generate_public_key())
+                    .await
+                    .ok()?;
+                blockchain
+                    .add_transaction(BlockchainTransaction::for_certificate(
+                        TransactionType::CertificateIssuance,
+                        &cert,
+                        encoding,
+                    ))
+                    .await;
+                Some(s.elapsed().as_micros())
+            }));
+            i += 1;
+        }
+
+        let mut latencies_us = Vec::with_capacity(handles.len());
+        for handle in handles {
+            if let Ok(Some(us)) = handle.await {
+                latencies_us.push(us);
+            }
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+        latencies_us.sort();
+
+        let achieved_tps = latencies_us.len() as f64 / elapsed;
+        SustainedIssuanceResult {
+            target_tps,
+            achieved_tps,
+            issued: latencies_us.len(),
+            latency_p50_ms: nearest_rank(&latencies_us, 0.50) / 1000.0,
+            latency_p95_ms: nearest_rank(&latencies_us, 0.95) / 1000.0,
+            latency_p99_ms: nearest_rank(&latencies_us, 0.99) / 1000.0,
+            max_schedule_lag_ms: max_lag.as_secs_f64() * 1000.0,
+            kept_up: achieved_tps >= target_tps * 0.95,
+        }
+    }
+
+    /// Runs `benchmark_issuance_sustained` at each rate in turn, giving the
+    /// offered-load vs throughput vs latency saturation curve. The pending
+    /// pool is mined between rates so each step starts from an empty pool.
+    pub async fn sweep_issuance(
+        &self,
+        rates: &[f64],
+        duration_per_rate: Duration,
+    ) -> Vec<SweepPoint> {
+        let mut points = Vec::with_capacity(rates.len());
+        for &rate in rates {
+            let result = self
+                .benchmark_issuance_sustained(rate, duration_per_rate)
+                .await;
+            points.push(SweepPoint::from(&result));
+            self.blockchain.mine_pending_transactions().await;
+        }
+        points
+    }
+
+    /// Issues a certificate and returns only once it is mined and an edge node
+    /// authenticates it, so callers can use it immediately. Mining is
+    /// triggered directly; if another task already took the transaction into
+    /// a block, this waits for that block instead.
+    pub async fn issue_and_confirm(
+        &self,
+        vehicle_id: String,
+        public_key: Vec<u8>,
+    ) -> Result<Certificate, String> {
+        let cert = self.cas[0]
+            .issue_certificate(vehicle_id, public_key)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.blockchain
+            .add_transaction(BlockchainTransaction::for_certificate(
+                TransactionType::CertificateIssuance,
+                &cert,
+                self.data_encoding,
+            ))
+            .await;
+
+        let confirm = async {
+            loop {
+                self.blockchain.mine_pending_transactions().await;
+                if self.blockchain.is_confirmed(&cert.id).await {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
             }
         };
+        tokio::time::timeout(CONFIRMATION_TIMEOUT, confirm)
+            .await
+            .map_err(|_| {
+                format!(
+                    "{} not confirmed within {:?}",
+                    cert.id, CONFIRMATION_TIMEOUT
+                )
+            })?;
+
+        self.edge_nodes[0]
+            .authenticate_certificate(&cert.id)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(cert)
+    }
+
+    /// Replays queued gateway writes once the gateway reports it is connected
+    /// again. Writes that still fail stay queued. Returns how many were reconciled.
+    pub async fn drain_gateway_retry_queue(&self) -> usize {
+        let Some(gw) = &self.gateway else {
+            return 0;
+        };
+        if !gw.is_connected().await {
+            return 0;
+        }
+
+        let queued = std::mem::take(&mut *self.gateway_retry_queue.lock().await);
+        let mut reconciled = 0;
+        let mut still_pending = Vec::new();
+        for tx in queued {
+            if gw.submit_transaction(&tx).await.is_ok() {
+                reconciled += 1;
+            } else {
+                still_pending.push(tx);
+            }
+        }
+        self.gateway_retry_queue.lock().await.extend(still_pending);
+        reconciled
+    }
+
+    pub async fn get_pending_gateway_writes(&self) -> usize {
+        self.gateway_retry_queue.lock().await.len()
+    }
+
+    /// Time (ms) to revoke `cert_id` and propagate it. Transient failures are
+    /// retried up to `revocation_retries` times; revocation is idempotent,
+    /// so a retry never double-revokes.
+    pub async fn benchmark_revocation_latency(
+        &self,
+        cert_id: &str,
+    ) -> Result<f64, RevocationError> {
+        let start = Instant::now();
+
+        let ca = &self.cas[0];
+        let mut attempt = 0;
+        loop {
+            match ca.revoke_with_reason(cert_id, "benchmark").await {
+                Ok(_) => break,
+                Err(RevocationError::Unrecorded(_)) if attempt < self.revocation_retries => {
+                    attempt += 1;
+                    tokio::time::sleep(Duration::from_millis(10 * attempt as u64)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
 
         let tx = BlockchainTransaction::new(
             cert_id.to_string(),
@@ -161,32 +534,106 @@ impl BBVPKISystem {
         );
         self.blockchain.add_transaction(tx).await;
 
-        for node in &self.edge_nodes {
-            node.propagate_revocation(cert_id).await;
+        // the revoking RSU hears first and fans out to its neighbors
+        if let Some(origin) = self.edge_nodes.first() {
+            origin.propagate_revocation(cert_id).await;
+            self.network
+                .propagate_revocation_to_neighbors(&origin.node_id, cert_id)
+                .await;
         }
 
-        start.elapsed().as_millis() as f64
+        Ok(start.elapsed().as_millis() as f64)
     }
 
-    // Returns per-request authentication latencies in microseconds
-    pub async fn benchmark_authentication_delay(&self, num_requests: usize) -> Vec<u128> {
-        let edge_node = &self.edge_nodes[0];
+    /// Time (ms) to renew `cert_id` for `validity_days` and queue the
+    /// renewal transaction that records the supersession on-chain.
+    pub async fn benchmark_renewal_latency(
+        &self,
+        cert_id: &str,
+        validity_days: i64,
+    ) -> Result<f64, String> {
+        let start = Instant::now();
+        let renewed = self.cas[0]
+            .renew_certificate(cert_id, validity_days)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.blockchain
+            .add_transaction(BlockchainTransaction::renewal(&renewed, cert_id))
+            .await;
+        Ok(start.elapsed().as_secs_f64() * 1000.0)
+    }
 
-        // Issue a real certificate and pre-populate cache with it to test cache hits
-        let test_cert = self.cas[0]
-            .issue_certificate("VEH-AUTH-BENCHMARK".to_string(), vec![0u8; 32])
+    /// Revokes `cert_id`, propagates to all edge nodes concurrently and polls
+    /// each until its `authenticate_certificate` returns `Revoked`.
+    pub async fn benchmark_revocation_convergence(
+        &self,
+        cert_id: &str,
+        timeout: Duration,
+    ) -> Result<RevocationConvergence, RevocationError> {
+        let start = Instant::now();
+        self.cas[0].revoke_with_reason(cert_id, "benchmark").await?;
+        self.blockchain
+            .add_transaction(BlockchainTransaction::new(
+                cert_id.to_string(),
+                TransactionType::CertificateRevocation,
+                vec![],
+            ))
             .await;
-        // Add cert to blockchain so authentication queries can find it
-        let tx = BlockchainTransaction::new(
-            test_cert.id.clone(),
-            TransactionType::CertificateIssuance,
-            serde_json::to_vec(&test_cert).unwrap(),
-        );
-        self.blockchain.add_transaction(tx).await;
-        self.blockchain.mine_pending_transactions().await;
 
-        // warm the cache once
-        edge_node.authenticate_certificate(&test_cert.id).await.ok();
+        let handles: Vec<_> = self
+            .edge_nodes
+            .iter()
+            .map(|node| {
+                let node = node.clone();
+                let cert_id = cert_id.to_string();
+                tokio::spawn(async move {
+                    node.propagate_revocation(&cert_id).await;
+                    let converged = tokio::time::timeout_at(start + timeout, async {
+                        while !matches!(
+                            node.authenticate_certificate(&cert_id).await,
+                            Ok((CertificateStatus::Revoked, _))
+                        ) {
+                            tokio::time::sleep(Duration::from_millis(1)).await;
+                        }
+                    })
+                    .await;
+                    let ms = converged
+                        .ok()
+                        .map(|_| start.elapsed().as_secs_f64() * 1000.0);
+                    (node.node_id.clone(), ms)
+                })
+            })
+            .collect();
+
+        let mut per_node_ms = Vec::with_capacity(handles.len());
+        for handle in handles {
+            if let Ok(result) = handle.await {
+                per_node_ms.push(result);
+            }
+        }
+        let full_convergence_ms = per_node_ms
+            .iter()
+            .map(|(_, ms)| *ms)
+            .collect::<Option<Vec<f64>>>()
+            .map(|all| all.into_iter().fold(0.0, f64::max));
+
+        Ok(RevocationConvergence {
+            per_node_ms,
+            full_convergence_ms,
+        })
+    }
+
+    // Returns per-request authentication latencies in microseconds
+    pub async fn benchmark_authentication_delay(
+        &self,
+        num_requests: usize,
+    ) -> Result<Vec<u128>, String> {
+        let edge_node = &self.edge_nodes[0];
+
+        // Issue a real certificate; confirming it also warms RSU-0's cache
+        let test_cert = self
+            .issue_and_confirm("VEH-AUTH-BENCHMARK".to_string(), generate_public_key())
+            .await?;
 
         let mut latencies_us: Vec<u128> = Vec::with_capacity(num_requests);
         for _ in 0..num_requests {
@@ -197,7 +644,30 @@ impl BBVPKISystem {
             latencies_us.push(ns / 1000);
         }
 
-        latencies_us
+        Ok(latencies_us)
+    }
+
+    /// Average cold-cache lookup of `cert_id` in μs, resolved by scanning the
+    /// chain and by asking the issuing CA's status responder. Each lookup
+    /// uses a fresh node so neither path is served from cache.
+    pub async fn benchmark_status_sources(&self, cert_id: &str, iterations: usize) -> (f64, f64) {
+        let mut chain_us = 0.0;
+        let mut responder_us = 0.0;
+        for i in 0..iterations {
+            let on_chain = EdgeNode::new(format!("RSU-CHAIN-{}", i), 1, self.blockchain.clone());
+            on_chain.load_genesis_trust_anchors().await;
+            let start = Instant::now();
+            let _ = on_chain.authenticate_certificate(cert_id).await;
+            chain_us += start.elapsed().as_secs_f64() * 1e6;
+
+            let ocsp = EdgeNode::new(format!("RSU-OCSP-{}", i), 1, self.blockchain.clone())
+                .with_status_responder(self.cas[0].clone());
+            let start = Instant::now();
+            let _ = ocsp.authenticate_certificate(cert_id).await;
+            responder_us += start.elapsed().as_secs_f64() * 1e6;
+        }
+        let n = iterations.max(1) as f64;
+        (chain_us / n, responder_us / n)
     }
 
     pub async fn benchmark_message_operations(&self, num_iterations: usize) -> (f64, f64) {
@@ -224,6 +694,46 @@ impl BBVPKISystem {
         (avg_signing, avg_verification)
     }
 
+    /// Signs `batch_size` messages across the fleet, then verifies the whole
+    /// batch with `workers` threads on the blocking pool, as an RSU checking
+    /// many vehicles' messages at once would. Returns verifications per second.
+    pub async fn benchmark_parallel_verification(&self, batch_size: usize, workers: usize) -> f64 {
+        let mut batch = Vec::with_capacity(batch_size);
+        for i in 0..batch_size {
+            let obu = self.vehicles[i % self.vehicles.len()].lock().await;
+            let message = format!("V2V message {} from {}", i, obu.vehicle_id).into_bytes();
+            let signature = obu.sign_message(&message).await.unwrap();
+            batch.push((obu.public_key.clone(), message, signature));
+        }
+
+        let workers = workers.max(1);
+        let (verified, elapsed) = tokio::task::spawn_blocking(move || {
+            let start = std::time::Instant::now();
+            let chunk_len = batch.len().div_ceil(workers).max(1);
+            let verified: usize = std::thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .chunks(chunk_len)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .filter(|(key, message, signature)| {
+                                    verify_ed25519(key, message, signature)
+                                })
+                                .count()
+                        })
+                    })
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).sum()
+            });
+            (verified, start.elapsed())
+        })
+        .await
+        .expect("verification workers panicked");
+
+        verified as f64 / elapsed.as_secs_f64()
+    }
+
     pub async fn simulate_system_reliability(&self) -> bool {
         println!("Testing system reliability with node failures...");
 
@@ -231,10 +741,13 @@ impl BBVPKISystem {
         let remaining_cas = self.cas.len() - 1;
 
         if remaining_cas > 0 {
-            let cert = self.cas[1]
-                .issue_certificate("VEH-RELIABILITY-TEST".to_string(), vec![0u8; 32])
-                .await;
-            println!("  → CA-1 issued certificate: {}", cert.id);
+            match self.cas[1]
+                .issue_certificate("VEH-RELIABILITY-TEST".to_string(), generate_public_key())
+                .await
+            {
+                Ok(cert) => println!("  → CA-1 issued certificate: {}", cert.id),
+                Err(e) => println!("  → CA-1 issuance failed: {}", e),
+            }
         }
 
         println!("  → Simulating RSU-0 failure");
@@ -266,23 +779,96 @@ impl BBVPKISystem {
             metrics.certificate_issuance_rate
         );
 
+        let pending_writes = self.get_pending_gateway_writes().await;
+        if pending_writes > 0 {
+            let reconciled = self.drain_gateway_retry_queue().await;
+            println!(
+                "      ! {} gateway writes failed, {} reconciled after retry\n",
+                pending_writes, reconciled
+            );
+        }
+        metrics.pending_gateway_writes = self.get_pending_gateway_writes().await;
+
         println!("[*] Mining blockchain transactions...");
-        self.blockchain.mine_pending_transactions().await;
-        println!("      ✓ Block mined\n");
+        match self.blockchain.mine_pending_transactions().await {
+            Some(mined) => println!(
+                "      ✓ Block #{} mined: {} txs, nonce {}, {} ms\n",
+                mined.block_index, mined.tx_count, mined.nonce, mined.elapsed_ms
+            ),
+            None => println!("      - Nothing pending to mine\n"),
+        }
+
+        let mining = self.blockchain.get_mining_stats().await;
+        metrics.blocks_mined = mining.blocks_mined;
+        metrics.avg_txs_per_block = mining.avg_txs_per_block();
+        metrics.avg_block_fill_ratio = mining.avg_block_fill_ratio();
 
         println!("[2/8] Benchmarking revocation latency...");
         // create a certificate specifically to test revocation latency so we revoke a known cert
-        let cert_to_revoke = self.cas[0]
-            .issue_certificate("VEH-REVOC-TEST".to_string(), vec![0u8; 32])
-            .await;
-        metrics.revocation_latency_ms = self.benchmark_revocation_latency(&cert_to_revoke.id).await;
-        println!(
-            "      ✓ Completed: {:.2} ms\n",
-            metrics.revocation_latency_ms
-        );
+        match self.cas[0]
+            .issue_certificate("VEH-REVOC-TEST".to_string(), generate_public_key())
+            .await
+        {
+            Ok(cert_to_revoke) => match self.benchmark_revocation_latency(&cert_to_revoke.id).await
+            {
+                Ok(ms) => {
+                    metrics.revocation_latency_ms = Some(ms);
+                    println!("      ✓ Completed: {:.2} ms\n", ms);
+                }
+                Err(e) => {
+                    metrics.revocation_latency_ms = None;
+                    println!("      ✗ Revocation failed: {}\n", e);
+                }
+            },
+            Err(e) => println!("      ✗ Revocation latency skipped: {}\n", e),
+        }
+
+        match self.cas[0]
+            .issue_certificate("VEH-CONVERGENCE-TEST".to_string(), generate_public_key())
+            .await
+        {
+            Ok(cert) => match self
+                .benchmark_revocation_convergence(&cert.id, Duration::from_secs(2))
+                .await
+            {
+                Ok(convergence) => {
+                    metrics.revocation_convergence_ms = convergence.full_convergence_ms;
+                    match convergence.full_convergence_ms {
+                        Some(ms) => println!(
+                            "      ✓ All {} nodes serve Revoked after {:.2} ms\n",
+                            convergence.per_node_ms.len(),
+                            ms
+                        ),
+                        None => println!("      ✗ Not every node converged\n"),
+                    }
+                }
+                Err(e) => println!("      ✗ Convergence benchmark failed: {}\n", e),
+            },
+            Err(e) => println!("      ✗ Convergence benchmark skipped: {}\n", e),
+        }
+
+        match self.cas[0]
+            .issue_certificate("VEH-RENEWAL-TEST".to_string(), generate_public_key())
+            .await
+        {
+            Ok(renewal_cert) => match self.benchmark_renewal_latency(&renewal_cert.id, 365).await {
+                Ok(ms) => {
+                    metrics.renewal_latency_ms = Some(ms);
+                    println!("      ✓ Renewal: {:.2} ms\n", ms);
+                }
+                Err(e) => println!("      ✗ Renewal failed: {}\n", e),
+            },
+            Err(e) => println!("      ✗ Renewal latency skipped: {}\n", e),
+        }
 
         println!("[3/8] Benchmarking authentication delay...");
-        let auth_latencies = self.benchmark_authentication_delay(500).await;
+        let auth_latencies = self
+            .benchmark_authentication_delay(500)
+            .await
+            .unwrap_or_else(|e| {
+                println!("      ✗ Authentication benchmark skipped: {}", e);
+                Vec::new()
+            });
         if auth_latencies.is_empty() {
             metrics.authentication_delay_us = 0.0;
             metrics.authentication_p50_us = 0.0;
@@ -295,21 +881,12 @@ impl BBVPKISystem {
             // compute percentiles (p50, p95, p99) using same nearest-rank approach
             let mut vals = auth_latencies.clone();
             vals.sort();
-            let n = vals.len();
-            let p_idx = |quant: f64| -> usize {
-                let idx = (quant * n as f64).ceil() as isize - 1;
-                if idx < 0 {
-                    0usize
-                } else if (idx as usize) >= n {
-                    n - 1
-                } else {
-                    idx as usize
-                }
-            };
-
-            metrics.authentication_p50_us = vals[p_idx(0.50)] as f64;
-            metrics.authentication_p95_us = vals[p_idx(0.95)] as f64;
-            metrics.authentication_p99_us = vals[p_idx(0.99)] as f64;
+            metrics.authentication_p50_us = nearest_rank(&vals, 0.50);
+            metrics.authentication_p95_us = nearest_rank(&vals, 0.95);
+            metrics.authentication_p99_us = nearest_rank(&vals, 0.99);
+            for us in &auth_latencies {
+                metrics.authentication_histogram_us.observe(*us as f64);
+            }
         }
 
         println!(
@@ -320,22 +897,50 @@ impl BBVPKISystem {
             metrics.authentication_p99_us
         );
 
+        match self
+            .issue_and_confirm("VEH-OCSP-BENCHMARK".to_string(), generate_public_key())
+            .await
+        {
+            Ok(cert) => {
+                let (chain_us, responder_us) = self.benchmark_status_sources(&cert.id, 100).await;
+                println!(
+                    "      ✓ Cold lookup: chain scan {:.2} μs, CA responder {:.2} μs\n",
+                    chain_us, responder_us
+                );
+            }
+            Err(e) => println!("      ✗ Status source comparison skipped: {}\n", e),
+        }
+
         println!("[4/8] Benchmarking message signing and verification...");
         let (sign_time, verify_time) = self.benchmark_message_operations(1000).await;
         metrics.message_signing_time_us = sign_time;
         metrics.message_verification_time_us = verify_time;
         println!(
-            "      ✓ Signing: {:.2} μs, Verification: {:.2} μs\n",
+            "      ✓ Signing: {:.2} μs, Verification: {:.2} μs",
             sign_time, verify_time
         );
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        metrics.verification_throughput_per_sec =
+            self.benchmark_parallel_verification(2000, workers).await;
+        println!(
+            "      ✓ Parallel verification ({} workers): {:.0} msgs/sec\n",
+            workers, metrics.verification_throughput_per_sec
+        );
 
         println!("[5/8] Calculating edge node cache hit rate...");
         let mut total_hit_rate = 0.0;
         for node in &self.edge_nodes {
             total_hit_rate += node.get_cache_hit_rate().await;
+            let stats = node.get_cache_stats().await;
+            metrics.cache_lru_evictions += stats.lru_evictions;
+            metrics.cache_ttl_expirations += stats.ttl_expirations;
         }
         metrics.cache_hit_rate = total_hit_rate / self.edge_nodes.len() as f64;
-        println!("      ✓ Completed: {:.2}%\n", metrics.cache_hit_rate);
+        println!("      ✓ Completed: {:.2}%", metrics.cache_hit_rate);
+        println!(
+            "      ✓ Evictions: {} LRU, {} TTL\n",
+            metrics.cache_lru_evictions, metrics.cache_ttl_expirations
+        );
 
         println!("[6/8] Calculating blockchain throughput...");
         metrics.blockchain_tps = self.blockchain.get_transaction_throughput(10).await;
@@ -355,6 +960,12 @@ impl BBVPKISystem {
         metrics.consensus_p50_ms = c_p50;
         metrics.consensus_p95_ms = c_p95;
         metrics.consensus_p99_ms = c_p99;
+        for ms in self.blockchain.get_consensus_latencies_ms().await {
+            metrics.consensus_histogram_ms.observe(ms as f64);
+        }
+
+        metrics.queue_wait_ms = self.blockchain.get_average_queue_wait_ms().await;
+        metrics.queue_wait_p95_ms = self.blockchain.get_queue_wait_percentiles_ms().await.1;
 
         println!("[7/8] Testing blockchain storage management...");
         metrics.blockchain_size_mb =
@@ -409,21 +1020,41 @@ async fn main() {
     println!("  → V2V Network layer\n");
 
     // initialize optional Hyperledger Fabric gateway (stub)
-    let fabric_gateway = Arc::new(HyperledgerFabricGateway::new(
-        "bbvpki-channel".to_string(),
-        "bbvpki_chaincode".to_string(),
-    ));
-    if let Err(e) = fabric_gateway.connect().await {
+    let tasks = Arc::new(TaskRegistry::new());
+    let fabric_gateway = Arc::new(
+        HyperledgerFabricGateway::new("bbvpki-channel".to_string(), "bbvpki_chaincode".to_string())
+            .with_task_registry(tasks.clone()),
+    );
+    if let Err(e) = LedgerGateway::connect(&*fabric_gateway).await {
         println!("Warning: failed to connect Fabric gateway: {}", e);
     }
+    // keeps `is_connected` current so the retry queue only drains when the peers are up
+    fabric_gateway
+        .clone()
+        .spawn_health_monitor(Duration::from_secs(1));
 
-    let system = BBVPKISystem::new(3, 5, 100, Some(fabric_gateway)).await;
+    let system = BBVPKISystem::new(3, 5, 100, Some(fabric_gateway), tasks)
+        .await
+        .expect("benchmark system sizes are non-zero");
 
     println!("✓ System initialized successfully\n");
 
     let metrics = system.run_comprehensive_benchmark().await;
 
-    metrics.print_report();
+    let targets = MetricTargets::default();
+    metrics.print_report(&targets);
+
+    // the previous run's row is the baseline, so compare before appending
+    match PerformanceMetrics::load_from_csv("metrics.csv") {
+        Ok(runs) => {
+            if let Some(baseline) = runs.last() {
+                let delta = metrics.compare(baseline);
+                println!("Compared with run at {}:", baseline.timestamp);
+                println!("{}", delta.summary());
+            }
+        }
+        Err(e) => println!("No baseline comparison: {}\n", e),
+    }
 
     println!("╔═══════════════════════════════════════════════════════╗");
     println!("║          Saving Metrics to CSV Files                 ║");
@@ -434,19 +1065,34 @@ async fn main() {
         Err(e) => println!("✗ Error saving metrics.csv: {}", e),
     }
 
-    match metrics.save_detailed_csv("metrics_detailed.csv") {
+    match metrics.save_to_json("metrics.json") {
+        Ok(_) => println!("✓ Metrics saved to metrics.json"),
+        Err(e) => println!("✗ Error saving metrics.json: {}", e),
+    }
+
+    match metrics.save_detailed_csv("metrics_detailed.csv", &targets) {
         Ok(_) => println!("✓ Detailed metrics saved to metrics_detailed.csv"),
         Err(e) => println!("✗ Error saving metrics_detailed.csv: {}", e),
     }
 
+    match metrics.save_histograms_csv("metrics_histograms.csv") {
+        Ok(_) => println!("✓ Latency histograms saved to metrics_histograms.csv"),
+        Err(e) => println!("✗ Error saving metrics_histograms.csv: {}", e),
+    }
+
     println!("\n╔═══════════════════════════════════════════════════════╗");
     println!("║          Client SDK Demonstration                     ║");
     println!("╚═══════════════════════════════════════════════════════╝\n");
 
-    let mut client_sdk = BBVPKIClientSDK::new("VEH-SDK-DEMO".to_string()).await;
+    let mut client_sdk = BBVPKIClientSDK::new("VEH-SDK-DEMO".to_string())
+        .await
+        .with_enrollment_ca(system.cas[0].clone());
 
-    if let Ok(_) = client_sdk.initialize().await {
+    if client_sdk.initialize().await.is_ok() {
         println!("  ✓ SDK initialized");
+        if let Some(cert) = client_sdk.obu().lock().await.get_certificate() {
+            println!("  ✓ Enrolled via CSR: {}", cert.id);
+        }
 
         let test_message = b"Emergency brake warning!";
         if let Ok(signature) = client_sdk.sign_v2v_message(test_message).await {
@@ -454,6 +1100,68 @@ async fn main() {
         }
     }
 
+    println!("\n╔═══════════════════════════════════════════════════════╗");
+    println!("║          Throughput vs Latency Sweep                  ║");
+    println!("╚═══════════════════════════════════════════════════════╝\n");
+
+    let sweep = system
+        .sweep_issuance(&[250.0, 500.0, 1000.0, 2000.0], Duration::from_secs(1))
+        .await;
+    println!(
+        "  {:>10} {:>10} {:>9} {:>9} {:>9}",
+        "offered", "achieved", "p50 ms", "p95 ms", "p99 ms"
+    );
+    for point in &sweep {
+        println!(
+            "  {:>10.0} {:>10.1} {:>9.3} {:>9.3} {:>9.3}",
+            point.offered_tps,
+            point.achieved_tps,
+            point.latency_p50_ms,
+            point.latency_p95_ms,
+            point.latency_p99_ms
+        );
+    }
+    match save_sweep_csv(&sweep, "sweep.csv") {
+        Ok(_) => println!("\n  ✓ Sweep saved to sweep.csv"),
+        Err(e) => println!("\n  ✗ Error saving sweep.csv: {}", e),
+    }
+
+    println!("\n╔═══════════════════════════════════════════════════════╗");
+    println!("║          Mining Parallelism (difficulty 4)            ║");
+    println!("╚═══════════════════════════════════════════════════════╝\n");
+
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let serial_ms = benchmark_mining(4, 1, 10).await;
+    let parallel_ms = benchmark_mining(4, workers, 10).await;
+    println!("  → 1 worker:   {:.2} ms/block", serial_ms);
+    println!("  → {} workers: {:.2} ms/block", workers, parallel_ms);
+
+    println!("\n╔═══════════════════════════════════════════════════════╗");
+    println!("║          CA Signature Algorithms (per operation)      ║");
+    println!("╚═══════════════════════════════════════════════════════╝\n");
+
+    for algorithm in [KeyAlgorithm::Ed25519, KeyAlgorithm::EcdsaSecp256k1] {
+        let (sign_us, verify_us) = benchmark_key_algorithm(algorithm, 200).await;
+        println!(
+            "  → {:?}: sign {:.2} μs, verify {:.2} μs",
+            algorithm, sign_us, verify_us
+        );
+    }
+
+    println!("\n╔═══════════════════════════════════════════════════════╗");
+    println!("║          Consensus Engines (latency per block)        ║");
+    println!("╚═══════════════════════════════════════════════════════╝\n");
+
+    let pow_ms = benchmark_consensus(Arc::new(ProofOfWork::new(4)), 10).await;
+    let pbft = PbftSimulated::new(4);
+    let (validators, quorum) = (pbft.validators(), pbft.quorum());
+    let pbft_ms = benchmark_consensus(Arc::new(pbft), 10).await;
+    println!("  → PoW (difficulty 4):  {:.2} ms/block", pow_ms);
+    println!(
+        "  → PBFT ({} validators, quorum {}): {:.2} ms/block",
+        validators, quorum, pbft_ms
+    );
+
     println!("\n╔═══════════════════════════════════════════════════════╗");
     println!("║              Benchmark Complete!                      ║");
     println!("╚═══════════════════════════════════════════════════════╝\n");
@@ -461,10 +1169,15 @@ async fn main() {
     println!("Key Findings:");
     let auth_ms = metrics.authentication_delay_us / 1000.0;
     println!(
-        "  • Authentication delay: {:.3} ms ({:.0} μs) (Target: <3ms) {}",
+        "  • Authentication delay: {:.3} ms ({:.0} μs) (Target: <{}ms) {}",
         auth_ms,
         metrics.authentication_delay_us,
-        if auth_ms < 3.0 { "✓" } else { "✗" }
+        targets.max_authentication_delay_us / 1000.0,
+        if metrics.authentication_delay_us < targets.max_authentication_delay_us {
+            "✓"
+        } else {
+            "✗"
+        }
     );
     println!(
         "  • Cache effectiveness: {:.1}% hit rate",
@@ -476,5 +1189,57 @@ async fn main() {
     );
     println!("  • System reliability: Fault-tolerant design validated ✓");
     println!("\nMetrics exported to CSV files in current directory.");
+
+    let running = system.active_task_count();
+    let aborted = system.shutdown(Duration::from_secs(2)).await;
+    println!(
+        "\nStopped {} background task(s), {} aborted after timeout.",
+        running,
+        aborted.len()
+    );
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn build(
+        num_cas: usize,
+        num_edge_nodes: usize,
+        num_vehicles: usize,
+    ) -> Result<BBVPKISystem, SystemError> {
+        BBVPKISystem::new(
+            num_cas,
+            num_edge_nodes,
+            num_vehicles,
+            None,
+            Arc::new(TaskRegistry::new()),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_zero_cas() {
+        assert_eq!(
+            build(0, 1, 1).await.err(),
+            Some(SystemError::NoComponents("certificate authority"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_zero_edge_nodes() {
+        assert_eq!(
+            build(1, 0, 1).await.err(),
+            Some(SystemError::NoComponents("edge node"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_zero_vehicles() {
+        assert_eq!(
+            build(1, 1, 0).await.err(),
+            Some(SystemError::NoComponents("vehicle"))
+        );
+    }
+}
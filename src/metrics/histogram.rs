@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+/// Latency distribution over fixed buckets. Unlike the p50/p95/p99 summary it
+/// shows the shape, e.g. separate humps for cache hits and misses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// Inclusive upper bounds, ascending. Values above the last bound land in
+    /// an implicit `+Inf` bucket.
+    bounds: Vec<f64>,
+    /// One count per bound, plus the `+Inf` bucket.
+    counts: Vec<u64>,
+    sum: f64,
+}
+
+impl Histogram {
+    /// Bounds are sorted and deduplicated; NaN bounds are dropped.
+    pub fn new(mut bounds: Vec<f64>) -> Self {
+        bounds.retain(|b| !b.is_nan());
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        Self {
+            counts: vec![0; bounds.len() + 1],
+            bounds,
+            sum: 0.0,
+        }
+    }
+
+    /// Default buckets for authentication latency in microseconds: cache hits
+    /// fall in the low buckets, chain lookups further up.
+    pub fn auth_latency_us() -> Self {
+        Self::new(vec![
+            1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 3000.0, 10000.0,
+        ])
+    }
+
+    /// Default buckets for consensus latency in milliseconds.
+    pub fn consensus_latency_ms() -> Self {
+        Self::new(vec![
+            1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0,
+        ])
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        let bucket = self.bounds.partition_point(|bound| *bound < value);
+        self.counts[bucket] += 1;
+        self.sum += value;
+    }
+
+    /// Empties the buckets, keeping the bounds.
+    pub fn reset(&mut self) {
+        self.counts.iter_mut().for_each(|c| *c = 0);
+        self.sum = 0.0;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// `(upper_bound, count)` per bucket, not cumulative; the last bound is
+    /// `f64::INFINITY`.
+    pub fn buckets(&self) -> Vec<(f64, u64)> {
+        self.bounds
+            .iter()
+            .copied()
+            .chain(std::iter::once(f64::INFINITY))
+            .zip(self.counts.iter().copied())
+            .collect()
+    }
+
+    /// Same bounds and counts, with sums within `tol`.
+    pub fn approx_eq(&self, other: &Self, tol: f64) -> bool {
+        self.bounds == other.bounds
+            && self.counts == other.counts
+            && (self.sum - other.sum).abs() <= tol
+    }
+
+    /// Prometheus text-format lines for a histogram called `name`, with
+    /// cumulative `le` buckets, `_sum` and `_count`.
+    pub fn to_prometheus(&self, name: &str) -> String {
+        let mut out = format!("# TYPE {} histogram\n", name);
+        let mut cumulative = 0;
+        for (bound, count) in self.buckets() {
+            cumulative += count;
+            let le = if bound.is_infinite() {
+                "+Inf".to_string()
+            } else {
+                bound.to_string()
+            };
+            out.push_str(&format!(
+                "{}_bucket{{le=\"{}\"}} {}\n",
+                name, le, cumulative
+            ));
+        }
+        out.push_str(&format!("{}_sum {}\n", name, self.sum));
+        out.push_str(&format!("{}_count {}\n", name, cumulative));
+        out
+    }
+}
@@ -1,3 +1,9 @@
+pub mod histogram;
 pub mod performance;
+pub mod regression;
+pub mod targets;
 
-pub use performance::PerformanceMetrics;
+pub use histogram::Histogram;
+pub use performance::{METRICS_SCHEMA_VERSION, PerformanceMetrics};
+pub use regression::{Better, DEFAULT_REGRESSION_THRESHOLD_PERCENT, MetricChange, MetricsDelta};
+pub use targets::MetricTargets;
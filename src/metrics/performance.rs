@@ -1,30 +1,78 @@
+use super::histogram::Histogram;
+use super::regression::{Better, DEFAULT_REGRESSION_THRESHOLD_PERCENT, MetricChange, MetricsDelta};
+use super::targets::MetricTargets;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::Write;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Version of the JSON layout written by `PerformanceMetrics::to_json_string`.
+/// Bump when a field is renamed or changes meaning; added fields are
+/// tolerated by older readers.
+pub const METRICS_SCHEMA_VERSION: &str = "1";
+
+/// JSON document form: the metrics fields plus the schema version.
+#[derive(Serialize)]
+struct MetricsDocument<'a> {
+    schema_version: &'static str,
+    #[serde(flatten)]
+    metrics: &'a PerformanceMetrics,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
     pub timestamp: String,
     pub certificate_issuance_rate: f64,
-    pub revocation_latency_ms: f64,
+    /// `None` when the benchmark revocation failed.
+    pub revocation_latency_ms: Option<f64>,
+    /// Until every edge node served the revocation; `None` if some never did.
+    pub revocation_convergence_ms: Option<f64>,
+    /// Renew a certificate and queue the renewal transaction; `None` if it failed.
+    #[serde(default)]
+    pub renewal_latency_ms: Option<f64>,
     pub authentication_delay_us: f64,
     pub authentication_p50_us: f64,
     pub authentication_p95_us: f64,
     pub authentication_p99_us: f64,
     pub message_signing_time_us: f64,
     pub message_verification_time_us: f64,
+    /// Aggregate rate verifying a batch of messages across all cores.
+    pub verification_throughput_per_sec: f64,
     pub cache_hit_rate: f64,
     pub cache_miss_rate: f64,
+    /// Entries pushed out by cache capacity, summed over edge nodes.
+    #[serde(default)]
+    pub cache_lru_evictions: u64,
+    /// Lookups that found their entry past the cache TTL.
+    #[serde(default)]
+    pub cache_ttl_expirations: u64,
     pub consensus_latency_ms: f64,
     pub consensus_p50_ms: f64,
     pub consensus_p95_ms: f64,
     pub consensus_p99_ms: f64,
+    /// Pending-pool wait before mining, reported apart from consensus latency.
+    pub queue_wait_ms: f64,
+    pub queue_wait_p95_ms: f64,
     pub blockchain_tps: f64,
+    pub blocks_mined: u64,
+    pub avg_txs_per_block: f64,
+    /// `None` while blocks have no transaction cap.
+    pub avg_block_fill_ratio: Option<f64>,
     pub blockchain_size_mb: f64,
     pub pruned_blocks: usize,
+    pub pending_gateway_writes: usize,
     pub deprecated_count: usize,
     pub system_uptime_secs: u64,
+    #[serde(default = "Histogram::auth_latency_us")]
+    pub authentication_histogram_us: Histogram,
+    #[serde(default = "Histogram::consensus_latency_ms")]
+    pub consensus_histogram_ms: Histogram,
+}
+
+impl Default for PerformanceMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PerformanceMetrics {
@@ -32,28 +80,110 @@ impl PerformanceMetrics {
         Self {
             timestamp: Utc::now().to_rfc3339(),
             certificate_issuance_rate: 0.0,
-            revocation_latency_ms: 0.0,
+            revocation_latency_ms: None,
+            revocation_convergence_ms: None,
+            renewal_latency_ms: None,
             authentication_delay_us: 0.0,
             authentication_p50_us: 0.0,
             authentication_p95_us: 0.0,
             authentication_p99_us: 0.0,
             message_signing_time_us: 0.0,
             message_verification_time_us: 0.0,
+            verification_throughput_per_sec: 0.0,
             cache_hit_rate: 0.0,
             cache_miss_rate: 0.0,
+            cache_lru_evictions: 0,
+            cache_ttl_expirations: 0,
             consensus_latency_ms: 0.0,
             consensus_p50_ms: 0.0,
             consensus_p95_ms: 0.0,
             consensus_p99_ms: 0.0,
+            queue_wait_ms: 0.0,
+            queue_wait_p95_ms: 0.0,
             blockchain_tps: 0.0,
+            blocks_mined: 0,
+            avg_txs_per_block: 0.0,
+            avg_block_fill_ratio: None,
             blockchain_size_mb: 0.0,
             pruned_blocks: 0,
+            pending_gateway_writes: 0,
             deprecated_count: 0,
             system_uptime_secs: 0,
+            authentication_histogram_us: Histogram::auth_latency_us(),
+            consensus_histogram_ms: Histogram::consensus_latency_ms(),
         }
     }
 
-    pub fn print_report(&self) {
+    /// Replaces the default histogram buckets, e.g. to zoom in on the
+    /// cache-hit range. Call before recording any latencies.
+    pub fn with_histogram_buckets(mut self, auth_us: Vec<f64>, consensus_ms: Vec<f64>) -> Self {
+        self.authentication_histogram_us = Histogram::new(auth_us);
+        self.consensus_histogram_ms = Histogram::new(consensus_ms);
+        self
+    }
+
+    /// Compares two runs: float fields within `tol` of each other, everything
+    /// else exactly. A failed measurement (`None`) only matches another `None`.
+    pub fn approx_eq(&self, other: &Self, tol: f64) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= tol;
+        let close_opt = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => close(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+
+        self.timestamp == other.timestamp
+            && close(
+                self.certificate_issuance_rate,
+                other.certificate_issuance_rate,
+            )
+            && close_opt(self.revocation_latency_ms, other.revocation_latency_ms)
+            && close_opt(
+                self.revocation_convergence_ms,
+                other.revocation_convergence_ms,
+            )
+            && close_opt(self.renewal_latency_ms, other.renewal_latency_ms)
+            && close(self.authentication_delay_us, other.authentication_delay_us)
+            && close(self.authentication_p50_us, other.authentication_p50_us)
+            && close(self.authentication_p95_us, other.authentication_p95_us)
+            && close(self.authentication_p99_us, other.authentication_p99_us)
+            && close(self.message_signing_time_us, other.message_signing_time_us)
+            && close(
+                self.message_verification_time_us,
+                other.message_verification_time_us,
+            )
+            && close(
+                self.verification_throughput_per_sec,
+                other.verification_throughput_per_sec,
+            )
+            && close(self.cache_hit_rate, other.cache_hit_rate)
+            && close(self.cache_miss_rate, other.cache_miss_rate)
+            && self.cache_lru_evictions == other.cache_lru_evictions
+            && self.cache_ttl_expirations == other.cache_ttl_expirations
+            && close(self.consensus_latency_ms, other.consensus_latency_ms)
+            && close(self.consensus_p50_ms, other.consensus_p50_ms)
+            && close(self.consensus_p95_ms, other.consensus_p95_ms)
+            && close(self.consensus_p99_ms, other.consensus_p99_ms)
+            && close(self.queue_wait_ms, other.queue_wait_ms)
+            && close(self.queue_wait_p95_ms, other.queue_wait_p95_ms)
+            && close(self.blockchain_tps, other.blockchain_tps)
+            && self.blocks_mined == other.blocks_mined
+            && close(self.avg_txs_per_block, other.avg_txs_per_block)
+            && close_opt(self.avg_block_fill_ratio, other.avg_block_fill_ratio)
+            && close(self.blockchain_size_mb, other.blockchain_size_mb)
+            && self.pruned_blocks == other.pruned_blocks
+            && self.pending_gateway_writes == other.pending_gateway_writes
+            && self.deprecated_count == other.deprecated_count
+            && self.system_uptime_secs == other.system_uptime_secs
+            && self
+                .authentication_histogram_us
+                .approx_eq(&other.authentication_histogram_us, tol)
+            && self
+                .consensus_histogram_ms
+                .approx_eq(&other.consensus_histogram_ms, tol)
+    }
+
+    pub fn print_report(&self, targets: &MetricTargets) {
         println!("\n╔═══════════════════════════════════════════════════════╗");
         println!("║       BB-VPKI Performance Evaluation Report          ║");
         println!("╠═══════════════════════════════════════════════════════╣");
@@ -64,45 +194,362 @@ impl PerformanceMetrics {
             "║    → {:<46.2} certs/sec ║",
             self.certificate_issuance_rate
         );
+        println!(
+            "║    → Target: {:<41} ║",
+            format!(">{} certs/sec", targets.min_issuance_rate)
+        );
         println!("║                                                       ║");
         println!("║ 2. Certificate Revocation Latency                    ║");
-        println!("║    → {:<46.2} ms ║", self.revocation_latency_ms);
+        match self.revocation_latency_ms {
+            Some(ms) => println!("║    → {:<46.2} ms ║", ms),
+            None => println!("║    → {:<49} ║", "FAILED"),
+        }
+        match self.revocation_convergence_ms {
+            Some(ms) => println!("║    → All nodes: {:<35.2} ms ║", ms),
+            None => println!("║    → All nodes: {:<38} ║", "NOT CONVERGED"),
+        }
+        println!(
+            "║    → Target: {:<41} ║",
+            format!("<{} ms", targets.max_revocation_latency_ms)
+        );
+        match self.renewal_latency_ms {
+            Some(ms) => println!("║    → Renewal: {:<37.2} ms ║", ms),
+            None => println!("║    → Renewal: {:<40} ║", "FAILED"),
+        }
         println!("║                                                       ║");
         println!("║ 3. Authentication Delay                               ║");
         println!("║    → Avg: {:<40.2} μs ║", self.authentication_delay_us);
         println!("║    → p50: {:<41.2} μs ║", self.authentication_p50_us);
         println!("║    → p95: {:<41.2} μs ║", self.authentication_p95_us);
         println!("║    → p99: {:<41.2} μs ║", self.authentication_p99_us);
-        println!("║    → Target: <3000 μs (<3ms) for safety-critical apps ║");
+        println!(
+            "║    → Target: {:<41} ║",
+            format!("<{} μs", targets.max_authentication_delay_us)
+        );
         println!("║                                                       ║");
         println!("║ 4. Message Signing Time                               ║");
         println!("║    → {:<46.2} μs ║", self.message_signing_time_us);
+        println!(
+            "║    → Target: {:<41} ║",
+            format!("<{} μs", targets.max_signing_time_us)
+        );
         println!("║                                                       ║");
         println!("║ 5. Message Verification Time                          ║");
         println!("║    → {:<46.2} μs ║", self.message_verification_time_us);
+        println!(
+            "║    → Parallel: {:<30.0} msgs/sec ║",
+            self.verification_throughput_per_sec
+        );
+        println!(
+            "║    → Target: {:<41} ║",
+            format!("<{} μs", targets.max_verification_time_us)
+        );
         println!("║                                                       ║");
         println!("║ 6. Edge Node Cache Hit Rate                           ║");
         println!("║    → {:<46.2}% ║", self.cache_hit_rate);
         println!("║    → Miss Rate: {:<42.2}% ║", self.cache_miss_rate);
+        println!("║    → LRU evictions: {:<34} ║", self.cache_lru_evictions);
+        println!(
+            "║    → TTL expirations: {:<32} ║",
+            self.cache_ttl_expirations
+        );
+        println!(
+            "║    → Target: {:<41} ║",
+            format!(">{}%", targets.min_cache_hit_rate)
+        );
         println!("║                                                       ║");
         println!("║ 7. Blockchain Transaction Throughput                  ║");
         println!("║    → {:<46.2} TPS ║", self.blockchain_tps);
+        println!(
+            "║    → Target: {:<41} ║",
+            format!(">{} TPS", targets.min_blockchain_tps)
+        );
+        println!("║    → Blocks mined: {:<35} ║", self.blocks_mined);
+        println!("║    → Txs/block: {:<38.2} ║", self.avg_txs_per_block);
+        match self.avg_block_fill_ratio {
+            Some(ratio) => println!("║    → Block fill: {:<36.2}% ║", ratio * 100.0),
+            None => println!("║    → Block fill: {:<37} ║", "unbounded"),
+        }
         println!("║                                                       ║");
         println!("║ 9. Consensus Latency                                  ║");
         println!("║    → Avg: {:<40.2} ms ║", self.consensus_latency_ms);
         println!("║    → p50: {:<41.2} ms ║", self.consensus_p50_ms);
         println!("║    → p95: {:<41.2} ms ║", self.consensus_p95_ms);
         println!("║    → p99: {:<41.2} ms ║", self.consensus_p99_ms);
+        println!("║    → Queue wait avg: {:<30.2} ms ║", self.queue_wait_ms);
+        println!(
+            "║    → Queue wait p95: {:<30.2} ms ║",
+            self.queue_wait_p95_ms
+        );
         println!("║                                                       ║");
         println!("║ 8. Blockchain Storage Management                      ║");
         println!("║    → Size: {:<43.2} MB ║", self.blockchain_size_mb);
         println!("║    → Pruned blocks: {:<34} ║", self.pruned_blocks);
+        println!(
+            "║    → Pending gateway writes: {:<25} ║",
+            self.pending_gateway_writes
+        );
         println!("║                                                       ║");
         println!("║ System Uptime: {:<38} sec ║", self.system_uptime_secs);
         println!("║ Deprecated Certificates: {:<26} ║", self.deprecated_count);
         println!("╚═══════════════════════════════════════════════════════╝\n");
     }
 
+    /// Every field plus `schema_version`, pretty-printed.
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(&MetricsDocument {
+            schema_version: METRICS_SCHEMA_VERSION,
+            metrics: self,
+        })
+        .unwrap_or_default()
+    }
+
+    /// Parses `to_json_string` output; the schema version is not checked.
+    pub fn from_json_str(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /// Overwrites `filename` with `to_json_string`, unlike the appending CSV.
+    pub fn save_to_json(&self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(filename, self.to_json_string())?;
+        Ok(())
+    }
+
+    /// Numeric fields under their CSV column names; `None` for unset options.
+    fn gauges(&self) -> Vec<(&'static str, Option<f64>)> {
+        vec![
+            (
+                "certificate_issuance_rate_certs_per_sec",
+                Some(self.certificate_issuance_rate),
+            ),
+            ("revocation_latency_ms", self.revocation_latency_ms),
+            ("revocation_convergence_ms", self.revocation_convergence_ms),
+            ("renewal_latency_ms", self.renewal_latency_ms),
+            (
+                "authentication_delay_us",
+                Some(self.authentication_delay_us),
+            ),
+            ("authentication_p50_us", Some(self.authentication_p50_us)),
+            ("authentication_p95_us", Some(self.authentication_p95_us)),
+            ("authentication_p99_us", Some(self.authentication_p99_us)),
+            (
+                "message_signing_time_us",
+                Some(self.message_signing_time_us),
+            ),
+            (
+                "message_verification_time_us",
+                Some(self.message_verification_time_us),
+            ),
+            (
+                "verification_throughput_per_sec",
+                Some(self.verification_throughput_per_sec),
+            ),
+            ("cache_hit_rate_percent", Some(self.cache_hit_rate)),
+            ("cache_miss_rate_percent", Some(self.cache_miss_rate)),
+            ("cache_lru_evictions", Some(self.cache_lru_evictions as f64)),
+            (
+                "cache_ttl_expirations",
+                Some(self.cache_ttl_expirations as f64),
+            ),
+            ("consensus_latency_ms", Some(self.consensus_latency_ms)),
+            ("consensus_p50_ms", Some(self.consensus_p50_ms)),
+            ("consensus_p95_ms", Some(self.consensus_p95_ms)),
+            ("consensus_p99_ms", Some(self.consensus_p99_ms)),
+            ("queue_wait_ms", Some(self.queue_wait_ms)),
+            ("queue_wait_p95_ms", Some(self.queue_wait_p95_ms)),
+            ("blockchain_tps", Some(self.blockchain_tps)),
+            ("blocks_mined", Some(self.blocks_mined as f64)),
+            ("avg_txs_per_block", Some(self.avg_txs_per_block)),
+            ("avg_block_fill_ratio", self.avg_block_fill_ratio),
+            ("blockchain_size_mb", Some(self.blockchain_size_mb)),
+            ("pruned_blocks", Some(self.pruned_blocks as f64)),
+            (
+                "pending_gateway_writes",
+                Some(self.pending_gateway_writes as f64),
+            ),
+            (
+                "deprecated_certificates",
+                Some(self.deprecated_count as f64),
+            ),
+            ("system_uptime_secs", Some(self.system_uptime_secs as f64)),
+        ]
+    }
+
+    /// Inverse of `gauges`; unknown columns are ignored.
+    fn set_gauge(&mut self, column: &str, value: Option<f64>) {
+        let whole = value.unwrap_or_default() as u64;
+        match column {
+            "certificate_issuance_rate_certs_per_sec" => {
+                self.certificate_issuance_rate = value.unwrap_or_default()
+            }
+            "revocation_latency_ms" => self.revocation_latency_ms = value,
+            "revocation_convergence_ms" => self.revocation_convergence_ms = value,
+            "renewal_latency_ms" => self.renewal_latency_ms = value,
+            "authentication_delay_us" => self.authentication_delay_us = value.unwrap_or_default(),
+            "authentication_p50_us" => self.authentication_p50_us = value.unwrap_or_default(),
+            "authentication_p95_us" => self.authentication_p95_us = value.unwrap_or_default(),
+            "authentication_p99_us" => self.authentication_p99_us = value.unwrap_or_default(),
+            "message_signing_time_us" => self.message_signing_time_us = value.unwrap_or_default(),
+            "message_verification_time_us" => {
+                self.message_verification_time_us = value.unwrap_or_default()
+            }
+            "verification_throughput_per_sec" => {
+                self.verification_throughput_per_sec = value.unwrap_or_default()
+            }
+            "cache_hit_rate_percent" => self.cache_hit_rate = value.unwrap_or_default(),
+            "cache_miss_rate_percent" => self.cache_miss_rate = value.unwrap_or_default(),
+            "cache_lru_evictions" => self.cache_lru_evictions = whole,
+            "cache_ttl_expirations" => self.cache_ttl_expirations = whole,
+            "consensus_latency_ms" => self.consensus_latency_ms = value.unwrap_or_default(),
+            "consensus_p50_ms" => self.consensus_p50_ms = value.unwrap_or_default(),
+            "consensus_p95_ms" => self.consensus_p95_ms = value.unwrap_or_default(),
+            "consensus_p99_ms" => self.consensus_p99_ms = value.unwrap_or_default(),
+            "queue_wait_ms" => self.queue_wait_ms = value.unwrap_or_default(),
+            "queue_wait_p95_ms" => self.queue_wait_p95_ms = value.unwrap_or_default(),
+            "blockchain_tps" => self.blockchain_tps = value.unwrap_or_default(),
+            "blocks_mined" => self.blocks_mined = whole,
+            "avg_txs_per_block" => self.avg_txs_per_block = value.unwrap_or_default(),
+            "avg_block_fill_ratio" => self.avg_block_fill_ratio = value,
+            "blockchain_size_mb" => self.blockchain_size_mb = value.unwrap_or_default(),
+            "pruned_blocks" => self.pruned_blocks = whole as usize,
+            "pending_gateway_writes" => self.pending_gateway_writes = whole as usize,
+            "deprecated_certificates" => self.deprecated_count = whole as usize,
+            "system_uptime_secs" => self.system_uptime_secs = whole,
+            _ => {}
+        }
+    }
+
+    /// Reads every row written by `save_to_csv`. Columns are matched by the
+    /// file's header, so files from older versions with fewer columns load
+    /// with the missing fields at their defaults. Rows appended under an older
+    /// header are read with the current column layout. Histograms are not
+    /// stored in the CSV and come back empty.
+    pub fn load_from_csv(filename: &str) -> Result<Vec<Self>, Box<dyn std::error::Error>> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_path(filename)?;
+        let header: Vec<String> = rdr.headers()?.iter().map(str::to_string).collect();
+        let current: Vec<String> = std::iter::once("timestamp")
+            .chain(Self::new().gauges().into_iter().map(|(name, _)| name))
+            .map(str::to_string)
+            .collect();
+
+        let mut runs = Vec::new();
+        for (row, record) in rdr.records().enumerate() {
+            let record = record?;
+            let columns = if record.len() == header.len() {
+                &header
+            } else if record.len() == current.len() {
+                &current
+            } else {
+                return Err(format!(
+                    "row {}: {} fields, expected {} or {}",
+                    row + 1,
+                    record.len(),
+                    header.len(),
+                    current.len()
+                )
+                .into());
+            };
+
+            let mut metrics = Self::new();
+            for (column, field) in columns.iter().zip(record.iter()) {
+                if column == "timestamp" {
+                    metrics.timestamp = field.to_string();
+                    continue;
+                }
+                let value = if field.is_empty() {
+                    None
+                } else {
+                    Some(
+                        field
+                            .parse::<f64>()
+                            .map_err(|e| format!("row {}, column {}: {}", row + 1, column, e))?,
+                    )
+                };
+                metrics.set_gauge(column, value);
+            }
+            runs.push(metrics);
+        }
+        Ok(runs)
+    }
+
+    /// `compare_with_threshold` at `DEFAULT_REGRESSION_THRESHOLD_PERCENT`.
+    pub fn compare(&self, baseline: &PerformanceMetrics) -> MetricsDelta {
+        self.compare_with_threshold(baseline, DEFAULT_REGRESSION_THRESHOLD_PERCENT)
+    }
+
+    /// Percent change of every numeric field set in both runs. A field
+    /// regressed if it moved the wrong way by more than `threshold_percent`;
+    /// counts such as `blocks_mined` are reported but never regress.
+    pub fn compare_with_threshold(
+        &self,
+        baseline: &PerformanceMetrics,
+        threshold_percent: f64,
+    ) -> MetricsDelta {
+        let changes = self
+            .gauges()
+            .into_iter()
+            .zip(baseline.gauges())
+            .filter_map(|((metric, current), (_, base))| {
+                let (current, base) = (current?, base?);
+                let percent_change = if base != 0.0 {
+                    Some((current - base) / base.abs() * 100.0)
+                } else if current == 0.0 {
+                    Some(0.0)
+                } else {
+                    None
+                };
+                let better = Better::for_column(metric);
+                let regressed = match (better, percent_change) {
+                    (Some(Better::Lower), Some(pct)) => pct > threshold_percent,
+                    (Some(Better::Higher), Some(pct)) => pct < -threshold_percent,
+                    _ => false,
+                };
+                Some(MetricChange {
+                    metric,
+                    baseline: base,
+                    current,
+                    percent_change,
+                    better,
+                    regressed,
+                })
+            })
+            .collect();
+        MetricsDelta {
+            threshold_percent,
+            changes,
+        }
+    }
+
+    /// Prometheus text exposition: one `bbvpki_`-prefixed gauge per numeric
+    /// field, labelled with the run timestamp, followed by both latency
+    /// histograms. Unset optional fields are left out rather than reported
+    /// as zero.
+    pub fn to_prometheus(&self) -> String {
+        let timestamp = self.timestamp.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::new();
+        for (name, value) in self.gauges() {
+            let Some(value) = value else { continue };
+            out.push_str(&format!("# TYPE bbvpki_{} gauge\n", name));
+            out.push_str(&format!(
+                "bbvpki_{}{{timestamp=\"{}\"}} {}\n",
+                name, timestamp, value
+            ));
+        }
+        out.push_str(
+            &self
+                .authentication_histogram_us
+                .to_prometheus("bbvpki_authentication_latency_us"),
+        );
+        out.push_str(
+            &self
+                .consensus_histogram_ms
+                .to_prometheus("bbvpki_consensus_latency_ms"),
+        );
+        out
+    }
+
     pub fn save_to_csv(&self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
         let file_exists = std::path::Path::new(filename).exists();
         let file = std::fs::OpenOptions::new()
@@ -113,49 +560,83 @@ impl PerformanceMetrics {
         let mut wtr = csv::Writer::from_writer(file);
 
         if !file_exists {
-            wtr.write_record(&[
+            wtr.write_record([
                 "timestamp",
                 "certificate_issuance_rate_certs_per_sec",
                 "revocation_latency_ms",
+                "revocation_convergence_ms",
+                "renewal_latency_ms",
                 "authentication_delay_us",
                 "authentication_p50_us",
                 "authentication_p95_us",
                 "authentication_p99_us",
                 "message_signing_time_us",
                 "message_verification_time_us",
+                "verification_throughput_per_sec",
                 "cache_hit_rate_percent",
                 "cache_miss_rate_percent",
+                "cache_lru_evictions",
+                "cache_ttl_expirations",
                 "consensus_latency_ms",
                 "consensus_p50_ms",
                 "consensus_p95_ms",
                 "consensus_p99_ms",
+                "queue_wait_ms",
+                "queue_wait_p95_ms",
                 "blockchain_tps",
+                "blocks_mined",
+                "avg_txs_per_block",
+                "avg_block_fill_ratio",
                 "blockchain_size_mb",
                 "pruned_blocks",
+                "pending_gateway_writes",
                 "deprecated_certificates",
                 "system_uptime_secs",
             ])?;
         }
 
-        wtr.write_record(&[
+        wtr.write_record([
             &self.timestamp,
             &self.certificate_issuance_rate.to_string(),
-            &self.revocation_latency_ms.to_string(),
+            &self
+                .revocation_latency_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default(),
+            &self
+                .revocation_convergence_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default(),
+            &self
+                .renewal_latency_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default(),
             &self.authentication_delay_us.to_string(),
             &self.authentication_p50_us.to_string(),
             &self.authentication_p95_us.to_string(),
             &self.authentication_p99_us.to_string(),
             &self.message_signing_time_us.to_string(),
             &self.message_verification_time_us.to_string(),
+            &self.verification_throughput_per_sec.to_string(),
             &self.cache_hit_rate.to_string(),
             &self.cache_miss_rate.to_string(),
+            &self.cache_lru_evictions.to_string(),
+            &self.cache_ttl_expirations.to_string(),
             &self.consensus_latency_ms.to_string(),
             &self.consensus_p50_ms.to_string(),
             &self.consensus_p95_ms.to_string(),
             &self.consensus_p99_ms.to_string(),
+            &self.queue_wait_ms.to_string(),
+            &self.queue_wait_p95_ms.to_string(),
             &self.blockchain_tps.to_string(),
+            &self.blocks_mined.to_string(),
+            &self.avg_txs_per_block.to_string(),
+            &self
+                .avg_block_fill_ratio
+                .map(|ratio| ratio.to_string())
+                .unwrap_or_default(),
             &self.blockchain_size_mb.to_string(),
             &self.pruned_blocks.to_string(),
+            &self.pending_gateway_writes.to_string(),
             &self.deprecated_count.to_string(),
             &self.system_uptime_secs.to_string(),
         ])?;
@@ -164,42 +645,73 @@ impl PerformanceMetrics {
         Ok(())
     }
 
-    pub fn save_detailed_csv(&self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// Writes both latency histograms as `metric,upper_bound,count` rows,
+    /// one per bucket; the last bound of each is `inf`.
+    pub fn save_histograms_csv(&self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut wtr = csv::Writer::from_path(filename)?;
+        wtr.write_record(["metric", "upper_bound", "count"])?;
+        for (metric, histogram) in [
+            (
+                "authentication_latency_us",
+                &self.authentication_histogram_us,
+            ),
+            ("consensus_latency_ms", &self.consensus_histogram_ms),
+        ] {
+            for (bound, count) in histogram.buckets() {
+                wtr.write_record([metric, &bound.to_string(), &count.to_string()])?;
+            }
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    pub fn save_detailed_csv(
+        &self,
+        filename: &str,
+        targets: &MetricTargets,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let status = |pass: bool| if pass { "PASS" } else { "FAIL" };
         let mut file = File::create(filename)?;
 
         writeln!(file, "BB-VPKI Performance Metrics Report")?;
         writeln!(file, "Timestamp,{}", self.timestamp)?;
-        writeln!(file, "")?;
+        writeln!(file)?;
         writeln!(file, "Metric,Value,Unit,Target,Status")?;
         writeln!(
             file,
-            "Certificate Issuance Rate,{:.2},certs/sec,>1000,{}",
+            "Certificate Issuance Rate,{:.2},certs/sec,>{},{}",
             self.certificate_issuance_rate,
-            if self.certificate_issuance_rate > 1000.0 {
-                "PASS"
-            } else {
-                "FAIL"
-            }
-        )?;
-        writeln!(
-            file,
-            "Revocation Latency,{:.2},ms,<100,{}",
-            self.revocation_latency_ms,
-            if self.revocation_latency_ms < 100.0 {
-                "PASS"
-            } else {
-                "FAIL"
-            }
+            targets.min_issuance_rate,
+            status(self.certificate_issuance_rate > targets.min_issuance_rate)
         )?;
+        match self.revocation_latency_ms {
+            Some(ms) => writeln!(
+                file,
+                "Revocation Latency,{:.2},ms,<{},{}",
+                ms,
+                targets.max_revocation_latency_ms,
+                status(ms < targets.max_revocation_latency_ms)
+            )?,
+            None => writeln!(
+                file,
+                "Revocation Latency,FAILED,ms,<{},FAIL",
+                targets.max_revocation_latency_ms
+            )?,
+        }
+        match self.revocation_convergence_ms {
+            Some(ms) => writeln!(file, "Revocation Convergence,{:.2},ms,N/A,INFO", ms)?,
+            None => writeln!(file, "Revocation Convergence,NOT CONVERGED,ms,N/A,FAIL")?,
+        }
+        match self.renewal_latency_ms {
+            Some(ms) => writeln!(file, "Renewal Latency,{:.2},ms,N/A,INFO", ms)?,
+            None => writeln!(file, "Renewal Latency,FAILED,ms,N/A,FAIL")?,
+        }
         writeln!(
             file,
-            "Authentication Delay,{:.2},μs,<3000,{}",
+            "Authentication Delay,{:.2},μs,<{},{}",
             self.authentication_delay_us,
-            if self.authentication_delay_us < 3000.0 {
-                "PASS"
-            } else {
-                "FAIL"
-            }
+            targets.max_authentication_delay_us,
+            status(self.authentication_delay_us < targets.max_authentication_delay_us)
         )?;
         writeln!(
             file,
@@ -218,44 +730,57 @@ impl PerformanceMetrics {
         )?;
         writeln!(
             file,
-            "Message Signing Time,{:.2},μs,<100,{}",
+            "Message Signing Time,{:.2},μs,<{},{}",
             self.message_signing_time_us,
-            if self.message_signing_time_us < 100.0 {
-                "PASS"
-            } else {
-                "FAIL"
-            }
+            targets.max_signing_time_us,
+            status(self.message_signing_time_us < targets.max_signing_time_us)
         )?;
         writeln!(
             file,
-            "Message Verification Time,{:.2},μs,<100,{}",
+            "Message Verification Time,{:.2},μs,<{},{}",
             self.message_verification_time_us,
-            if self.message_verification_time_us < 100.0 {
-                "PASS"
-            } else {
-                "FAIL"
-            }
+            targets.max_verification_time_us,
+            status(self.message_verification_time_us < targets.max_verification_time_us)
         )?;
         writeln!(
             file,
-            "Cache Hit Rate,{:.2},%,>85,{}",
+            "Parallel Verification Throughput,{:.0},msgs/sec,N/A,INFO",
+            self.verification_throughput_per_sec
+        )?;
+        writeln!(
+            file,
+            "Cache Hit Rate,{:.2},%,>{},{}",
             self.cache_hit_rate,
-            if self.cache_hit_rate > 85.0 {
-                "PASS"
-            } else {
-                "FAIL"
-            }
+            targets.min_cache_hit_rate,
+            status(self.cache_hit_rate > targets.min_cache_hit_rate)
         )?;
         writeln!(
             file,
-            "Blockchain TPS,{:.2},transactions/sec,>100,{}",
+            "Cache LRU Evictions,{},entries,N/A,INFO",
+            self.cache_lru_evictions
+        )?;
+        writeln!(
+            file,
+            "Cache TTL Expirations,{},lookups,N/A,INFO",
+            self.cache_ttl_expirations
+        )?;
+        writeln!(
+            file,
+            "Blockchain TPS,{:.2},transactions/sec,>{},{}",
             self.blockchain_tps,
-            if self.blockchain_tps > 100.0 {
-                "PASS"
-            } else {
-                "FAIL"
-            }
+            targets.min_blockchain_tps,
+            status(self.blockchain_tps > targets.min_blockchain_tps)
+        )?;
+        writeln!(file, "Blocks Mined,{},blocks,N/A,INFO", self.blocks_mined)?;
+        writeln!(
+            file,
+            "Transactions per Block,{:.2},transactions,N/A,INFO",
+            self.avg_txs_per_block
         )?;
+        match self.avg_block_fill_ratio {
+            Some(ratio) => writeln!(file, "Block Fill Ratio,{:.2},%,N/A,INFO", ratio * 100.0)?,
+            None => writeln!(file, "Block Fill Ratio,UNBOUNDED,%,N/A,INFO")?,
+        }
         writeln!(
             file,
             "Consensus Latency,{:.2},ms,N/A,INFO",
@@ -276,12 +801,25 @@ impl PerformanceMetrics {
             "Consensus Latency p99,{:.2},ms,N/A,INFO",
             self.consensus_p99_ms
         )?;
+        writeln!(file, "Queue Wait,{:.2},ms,N/A,INFO", self.queue_wait_ms)?;
+        writeln!(
+            file,
+            "Queue Wait p95,{:.2},ms,N/A,INFO",
+            self.queue_wait_p95_ms
+        )?;
         writeln!(
             file,
             "Blockchain Size,{:.2},MB,N/A,INFO",
             self.blockchain_size_mb
         )?;
         writeln!(file, "Pruned Blocks,{},blocks,N/A,INFO", self.pruned_blocks)?;
+        writeln!(
+            file,
+            "Pending Gateway Writes,{},transactions,<={},{}",
+            self.pending_gateway_writes,
+            targets.max_pending_gateway_writes,
+            status(self.pending_gateway_writes <= targets.max_pending_gateway_writes)
+        )?;
         writeln!(
             file,
             "Deprecated Certificates,{},count,N/A,INFO",
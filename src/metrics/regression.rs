@@ -0,0 +1,89 @@
+/// Percent change past which `PerformanceMetrics::compare` flags a regression.
+pub const DEFAULT_REGRESSION_THRESHOLD_PERCENT: f64 = 10.0;
+
+/// Which way a metric moves when performance gets worse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Better {
+    Lower,
+    Higher,
+}
+
+impl Better {
+    /// Direction for a `save_to_csv` column; `None` for counts and sizes
+    /// that describe the run rather than how fast it was.
+    pub(crate) fn for_column(column: &str) -> Option<Self> {
+        match column {
+            "certificate_issuance_rate_certs_per_sec"
+            | "verification_throughput_per_sec"
+            | "cache_hit_rate_percent"
+            | "blockchain_tps" => Some(Better::Higher),
+            "cache_miss_rate_percent" => Some(Better::Lower),
+            _ if column.ends_with("_ms") || column.ends_with("_us") => Some(Better::Lower),
+            _ => None,
+        }
+    }
+}
+
+/// One metric present in both runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricChange {
+    /// `save_to_csv` column name.
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    /// `None` when the baseline is zero and the current value is not.
+    pub percent_change: Option<f64>,
+    pub better: Option<Better>,
+    /// Moved the wrong way by more than the threshold.
+    pub regressed: bool,
+}
+
+/// Result of `PerformanceMetrics::compare`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsDelta {
+    pub threshold_percent: f64,
+    pub changes: Vec<MetricChange>,
+}
+
+impl MetricsDelta {
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricChange> {
+        self.changes.iter().filter(|c| c.regressed)
+    }
+
+    pub fn has_regression(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+
+    /// Process exit code for CI: 1 if anything regressed, else 0.
+    pub fn exit_code(&self) -> i32 {
+        i32::from(self.has_regression())
+    }
+
+    /// One line per regressed metric, then an overall PASS/FAIL line.
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        let mut count = 0;
+        for change in self.regressions() {
+            count += 1;
+            out.push_str(&format!(
+                "REGRESSION {}: {} -> {} ({:+.1}%)\n",
+                change.metric,
+                change.baseline,
+                change.current,
+                change.percent_change.unwrap_or_default()
+            ));
+        }
+        if count == 0 {
+            out.push_str(&format!(
+                "PASS: no metric regressed by more than {}%\n",
+                self.threshold_percent
+            ));
+        } else {
+            out.push_str(&format!(
+                "FAIL: {} metric(s) regressed by more than {}%\n",
+                count, self.threshold_percent
+            ));
+        }
+        out
+    }
+}
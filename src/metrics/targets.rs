@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// PASS/FAIL thresholds used when reporting `PerformanceMetrics`. Defaults
+/// match the original safety-critical deployment targets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricTargets {
+    pub min_issuance_rate: f64,
+    pub max_revocation_latency_ms: f64,
+    pub max_authentication_delay_us: f64,
+    pub max_signing_time_us: f64,
+    pub max_verification_time_us: f64,
+    pub min_cache_hit_rate: f64,
+    pub min_blockchain_tps: f64,
+    pub max_pending_gateway_writes: usize,
+}
+
+impl Default for MetricTargets {
+    fn default() -> Self {
+        Self {
+            min_issuance_rate: 1000.0,
+            max_revocation_latency_ms: 100.0,
+            max_authentication_delay_us: 3000.0,
+            max_signing_time_us: 100.0,
+            max_verification_time_us: 100.0,
+            min_cache_hit_rate: 85.0,
+            min_blockchain_tps: 100.0,
+            max_pending_gateway_writes: 0,
+        }
+    }
+}
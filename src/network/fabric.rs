@@ -1,15 +1,22 @@
 use crate::blockchain::BlockchainTransaction;
 use crate::network::gateway::LedgerGateway;
-use async_trait::async_trait;
+use crate::task::TaskRegistry;
+use crate::task::registry::cancellation;
 use hex;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tokio::time::{Duration, sleep};
 
 pub struct HyperledgerFabricGateway {
     channel_name: String,
     chaincode_name: String,
-    connected: Arc<Mutex<bool>>,
+    /// Current connection state; subscribers are notified on every change.
+    connected: watch::Sender<bool>,
+    /// Simulated reachability of the Fabric peers, checked by `probe`.
+    reachable: Arc<AtomicBool>,
+    tasks: Option<Arc<TaskRegistry>>,
 }
 
 impl HyperledgerFabricGateway {
@@ -17,18 +24,74 @@ impl HyperledgerFabricGateway {
         Self {
             channel_name,
             chaincode_name,
-            connected: Arc::new(Mutex::new(false)),
+            connected: watch::Sender::new(false),
+            reachable: Arc::new(AtomicBool::new(true)),
+            tasks: None,
         }
     }
 
+    /// Registers the health monitor with `tasks`, which can stop it.
+    pub fn with_task_registry(mut self, tasks: Arc<TaskRegistry>) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+
+    /// Simulates the network path to the peers going down or coming back.
+    /// Only the health monitor (or the next submit) notices the change.
+    pub fn set_reachable(&self, reachable: bool) {
+        self.reachable.store(reachable, Ordering::Relaxed);
+    }
+
+    /// Receives the new connection state each time it changes.
+    pub fn subscribe_connection(&self) -> watch::Receiver<bool> {
+        self.connected.subscribe()
+    }
+
+    /// Heartbeat round-trip to the peers.
+    async fn probe(&self) -> bool {
+        sleep(Duration::from_millis(5)).await;
+        self.reachable.load(Ordering::Relaxed)
+    }
+
+    /// Starts a background heartbeat that probes the peers every `interval`
+    /// and updates the connection state. Abort the handle, or shut down the
+    /// gateway's task registry, to stop it.
+    pub fn spawn_health_monitor(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let cancelled = cancellation(self.tasks.as_ref());
+        let tasks = self.tasks.clone();
+
+        let handle = tokio::spawn(async move {
+            tokio::pin!(cancelled);
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = &mut cancelled => break,
+                }
+                let healthy = self.probe().await;
+                self.connected.send_if_modified(|connected| {
+                    let changed = *connected != healthy;
+                    *connected = healthy;
+                    changed
+                });
+            }
+        });
+        if let Some(tasks) = tasks {
+            tasks.register("fabric-health-monitor", &handle);
+        }
+        handle
+    }
+
     async fn connect_internal(&self) -> Result<(), String> {
         println!("Connecting to Hyperledger Fabric network...");
         println!("  → Channel: {}", self.channel_name);
         println!("  → Chaincode: {}", self.chaincode_name);
 
         sleep(Duration::from_millis(100)).await;
-        let mut guard = self.connected.lock().await;
-        *guard = true;
+        if !self.reachable.load(Ordering::Relaxed) {
+            return Err("Fabric peers unreachable".to_string());
+        }
+        self.connected.send_replace(true);
 
         println!("  ✓ Connected to HLF network");
         Ok(())
@@ -39,10 +102,14 @@ impl HyperledgerFabricGateway {
         _function: &str,
         _args: Vec<String>,
     ) -> Result<Vec<u8>, String> {
-        let guard = self.connected.lock().await;
-        if !*guard {
+        if !*self.connected.borrow() {
             return Err("Not connected to HLF network".to_string());
         }
+        if !self.reachable.load(Ordering::Relaxed) {
+            // a failed submit is how a dropped connection shows up without the monitor
+            self.connected.send_replace(false);
+            return Err("Lost connection to HLF network".to_string());
+        }
 
         // silent submit (no println for each tx to reduce noise during benchmarks)
         sleep(Duration::from_millis(50)).await;
@@ -51,7 +118,6 @@ impl HyperledgerFabricGateway {
     }
 }
 
-#[async_trait]
 impl LedgerGateway for HyperledgerFabricGateway {
     async fn connect(&self) -> Result<(), String> {
         self.connect_internal().await
@@ -64,6 +130,10 @@ impl LedgerGateway for HyperledgerFabricGateway {
             crate::blockchain::TransactionType::CertificateRevocation => "revokeCertificate",
             crate::blockchain::TransactionType::CertificateRenewal => "renewCertificate",
             crate::blockchain::TransactionType::DeprecationArchive => "archiveCertificate",
+            crate::blockchain::TransactionType::KeyRotation => "rotateCaKey",
+            crate::blockchain::TransactionType::CertificateSuspension => "suspendCertificate",
+            crate::blockchain::TransactionType::CertificateReinstatement => "reinstateCertificate",
+            crate::blockchain::TransactionType::TrustAnchor => "addTrustAnchor",
         };
 
         let args = vec![tx.tx_id.clone(), hex::encode(&tx.data)];
@@ -71,4 +141,8 @@ impl LedgerGateway for HyperledgerFabricGateway {
             .await
             .map(|_| ())
     }
+
+    async fn is_connected(&self) -> bool {
+        *self.connected.borrow()
+    }
 }
@@ -1,8 +1,53 @@
 use crate::blockchain::BlockchainTransaction;
-use async_trait::async_trait;
+use std::future::Future;
+use std::pin::Pin;
 
-#[async_trait]
+/// A ledger that certificate transactions are mirrored to. Methods are native
+/// async, so calls on a concrete gateway do not box their futures. Where a
+/// gateway must be held as a trait object, use `DynLedgerGateway`.
 pub trait LedgerGateway: Send + Sync {
-    async fn connect(&self) -> Result<(), String>;
-    async fn submit_transaction(&self, tx: &BlockchainTransaction) -> Result<(), String>;
+    fn connect(&self) -> impl Future<Output = Result<(), String>> + Send;
+
+    fn submit_transaction(
+        &self,
+        tx: &BlockchainTransaction,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// Whether submits are currently expected to succeed. Gateways without a
+    /// notion of connection state report `true`.
+    fn is_connected(&self) -> impl Future<Output = bool> + Send {
+        async { true }
+    }
+}
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Object-safe adapter over `LedgerGateway` for `Arc<dyn DynLedgerGateway>`.
+/// Implemented for every gateway; each call boxes one future.
+pub trait DynLedgerGateway: Send + Sync {
+    fn connect(&self) -> BoxFuture<'_, Result<(), String>>;
+
+    fn submit_transaction<'a>(
+        &'a self,
+        tx: &'a BlockchainTransaction,
+    ) -> BoxFuture<'a, Result<(), String>>;
+
+    fn is_connected(&self) -> BoxFuture<'_, bool>;
+}
+
+impl<G: LedgerGateway> DynLedgerGateway for G {
+    fn connect(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(LedgerGateway::connect(self))
+    }
+
+    fn submit_transaction<'a>(
+        &'a self,
+        tx: &'a BlockchainTransaction,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(LedgerGateway::submit_transaction(self, tx))
+    }
+
+    fn is_connected(&self) -> BoxFuture<'_, bool> {
+        Box::pin(LedgerGateway::is_connected(self))
+    }
 }
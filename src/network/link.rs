@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+/// Quality of a directed V2V link.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkQuality {
+    /// Probability in `[0, 1]` that a message on this link is lost.
+    pub loss_probability: f64,
+    /// One-way delivery delay.
+    pub latency: Duration,
+}
+
+impl Default for LinkQuality {
+    /// A perfect link: nothing lost, no delay.
+    fn default() -> Self {
+        Self {
+            loss_probability: 0.0,
+            latency: Duration::ZERO,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::blockchain::BlockchainTransaction;
+use crate::network::gateway::LedgerGateway;
+use std::sync::Mutex as StdMutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Zero-latency `LedgerGateway` that records accepted submissions so tests
+/// can assert on exactly what was written.
+pub struct MockGateway {
+    submitted: StdMutex<Vec<BlockchainTransaction>>,
+    failing: AtomicBool,
+}
+
+impl Default for MockGateway {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockGateway {
+    pub fn new() -> Self {
+        Self {
+            submitted: StdMutex::new(Vec::new()),
+            failing: AtomicBool::new(false),
+        }
+    }
+
+    /// While failing, submits are rejected (and not recorded) and the
+    /// gateway reports itself disconnected.
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.store(failing, Ordering::Relaxed);
+    }
+
+    /// Accepted transactions, in submission order.
+    pub fn submitted(&self) -> Vec<BlockchainTransaction> {
+        self.submitted.lock().unwrap().clone()
+    }
+}
+
+impl LedgerGateway for MockGateway {
+    async fn connect(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn submit_transaction(&self, tx: &BlockchainTransaction) -> Result<(), String> {
+        if self.failing.load(Ordering::Relaxed) {
+            return Err("MockGateway configured to fail".to_string());
+        }
+        self.submitted.lock().unwrap().push(tx.clone());
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        !self.failing.load(Ordering::Relaxed)
+    }
+}
@@ -1,7 +1,13 @@
 pub mod fabric;
 pub mod gateway;
+pub mod link;
+#[cfg(feature = "test-harness")]
+pub mod mock;
 pub mod v2v;
 
 pub use fabric::HyperledgerFabricGateway;
-pub use gateway::LedgerGateway;
-pub use v2v::V2VNetwork;
+pub use gateway::{DynLedgerGateway, LedgerGateway};
+pub use link::LinkQuality;
+#[cfg(feature = "test-harness")]
+pub use mock::MockGateway;
+pub use v2v::{BroadcastReport, V2VNetwork};
@@ -1,14 +1,45 @@
+use super::link::LinkQuality;
 use crate::edge::EdgeNode;
-use crate::vehicle::OnBoardUnit;
+use crate::vehicle::{OnBoardUnit, SignedMessage};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 use tokio::sync::{Mutex, RwLock};
 
+/// Outcome of `V2VNetwork::broadcast_message`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BroadcastReport {
+    /// Receivers whose link delivered the message, in arrival order.
+    pub receivers: Vec<String>,
+    pub delivered: usize,
+    /// Receivers that checked the attached signature against the sender's key.
+    pub verified_ok: usize,
+    /// Receivers that rejected the signature, or could not check it because
+    /// the sender is not registered. Always 0 for unsigned messages.
+    pub verify_failed: usize,
+}
+
 pub struct V2VNetwork {
     nodes: Arc<RwLock<HashMap<String, Arc<EdgeNode>>>>,
     vehicles: Arc<RwLock<HashMap<String, Arc<Mutex<OnBoardUnit>>>>>,
     message_counter: Arc<AtomicUsize>,
+    // std mutex so the counters can be reset from sync code; never held across an await
+    messages_by_sender: Arc<StdMutex<HashMap<String, usize>>>,
+    default_link: LinkQuality,
+    /// Per (sender, receiver) overrides of `default_link`.
+    links: Arc<StdMutex<HashMap<(String, String), LinkQuality>>>,
+    /// Drives packet loss; seeded so a simulation run can be replayed.
+    rng: Arc<StdMutex<StdRng>>,
+}
+
+impl Default for V2VNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl V2VNetwork {
@@ -17,6 +48,65 @@ impl V2VNetwork {
             nodes: Arc::new(RwLock::new(HashMap::new())),
             vehicles: Arc::new(RwLock::new(HashMap::new())),
             message_counter: Arc::new(AtomicUsize::new(0)),
+            messages_by_sender: Arc::new(StdMutex::new(HashMap::new())),
+            default_link: LinkQuality::default(),
+            links: Arc::new(StdMutex::new(HashMap::new())),
+            rng: Arc::new(StdMutex::new(StdRng::seed_from_u64(0))),
+        }
+    }
+
+    /// Reseeds the packet-loss RNG; the same seed and topology reproduce the
+    /// same deliveries.
+    pub fn with_seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Quality of every link without an explicit `set_link`.
+    pub fn with_default_link(mut self, quality: LinkQuality) -> Self {
+        self.default_link = quality;
+        self
+    }
+
+    pub fn set_link(&self, from: &str, to: &str, quality: LinkQuality) {
+        self.links
+            .lock()
+            .unwrap()
+            .insert((from.to_string(), to.to_string()), quality);
+    }
+
+    /// Decides which receivers get a message from `sender_id` and with what
+    /// delay. Receivers are visited in id order so the RNG draws are reproducible.
+    fn sample_deliveries<'a>(
+        &self,
+        sender_id: &str,
+        receivers: impl Iterator<Item = &'a String>,
+    ) -> Vec<(String, Duration)> {
+        let mut receivers: Vec<&String> = receivers.filter(|id| *id != sender_id).collect();
+        receivers.sort();
+
+        let links = self.links.lock().unwrap();
+        let mut rng = self.rng.lock().unwrap();
+        receivers
+            .into_iter()
+            .filter_map(|receiver| {
+                let link = links
+                    .get(&(sender_id.to_string(), receiver.clone()))
+                    .copied()
+                    .unwrap_or(self.default_link);
+                let loss = link.loss_probability.clamp(0.0, 1.0);
+                if loss > 0.0 && rng.gen_bool(loss) {
+                    return None;
+                }
+                Some((receiver.clone(), link.latency))
+            })
+            .collect()
+    }
+
+    fn count_sent(&self, sender_id: &str) {
+        self.message_counter.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut per_sender) = self.messages_by_sender.lock() {
+            *per_sender.entry(sender_id.to_string()).or_insert(0) += 1;
         }
     }
 
@@ -24,27 +114,130 @@ impl V2VNetwork {
         self.nodes.write().await.insert(node.node_id.clone(), node);
     }
 
+    /// Pushes a revocation from `origin_node_id` into the cache of each of its
+    /// neighbors registered on this network, returning how many were
+    /// updated. Neighbor ids with no registered node are skipped.
+    pub async fn propagate_revocation_to_neighbors(
+        &self,
+        origin_node_id: &str,
+        cert_id: &str,
+    ) -> usize {
+        let neighbors: Vec<Arc<EdgeNode>> = {
+            let nodes = self.nodes.read().await;
+            let Some(origin) = nodes.get(origin_node_id) else {
+                return 0;
+            };
+            origin
+                .neighbors()
+                .await
+                .iter()
+                .filter_map(|id| nodes.get(id).cloned())
+                .collect()
+        };
+        for neighbor in &neighbors {
+            neighbor.propagate_revocation(cert_id).await;
+        }
+        neighbors.len()
+    }
+
     pub async fn register_vehicle(&self, vehicle: Arc<Mutex<OnBoardUnit>>) {
         let id = vehicle.lock().await.vehicle_id.clone();
         self.vehicles.write().await.insert(id, vehicle);
     }
 
-    pub async fn broadcast_message(&self, sender_id: &str, _message: Vec<u8>) -> usize {
-        self.message_counter.fetch_add(1, Ordering::Relaxed);
+    /// Broadcasts to every other registered vehicle over its link. Lost
+    /// deliveries are dropped; returns once the slowest delivery has arrived.
+    /// With a `signature`, each receiver checks it over `message` against the
+    /// registered sender's public key, so the sender's OBU must not be locked
+    /// by the caller.
+    pub async fn broadcast_message(
+        &self,
+        sender_id: &str,
+        message: Vec<u8>,
+        signature: Option<&[u8]>,
+    ) -> BroadcastReport {
+        self.count_sent(sender_id);
 
         let vehicles = self.vehicles.read().await;
-        let mut delivered = 0;
+        let mut deliveries = self.sample_deliveries(sender_id, vehicles.keys());
+        deliveries.sort_by_key(|(_, latency)| *latency);
+        if let Some((_, slowest)) = deliveries.last() {
+            tokio::time::sleep(*slowest).await;
+        }
 
-        for (vehicle_id, _) in vehicles.iter() {
-            if vehicle_id != sender_id {
-                delivered += 1;
+        let mut report = BroadcastReport {
+            delivered: deliveries.len(),
+            ..BroadcastReport::default()
+        };
+        if let Some(signature) = signature {
+            let sender_key = match vehicles.get(sender_id) {
+                Some(sender) => Some(sender.lock().await.public_key.clone()),
+                None => None,
+            };
+            for (vehicle_id, _) in &deliveries {
+                let accepted = match &sender_key {
+                    Some(key) => vehicles[vehicle_id]
+                        .lock()
+                        .await
+                        .verify_message(&message, signature, key),
+                    None => false,
+                };
+                if accepted {
+                    report.verified_ok += 1;
+                } else {
+                    report.verify_failed += 1;
+                }
             }
         }
+        report.receivers = deliveries.into_iter().map(|(id, _)| id).collect();
+        report
+    }
+
+    /// Delivers `envelope` to every other registered vehicle its link does
+    /// not drop. Each receiver verifies the certificate and signature and
+    /// applies its replay policy itself; returns how many accepted it.
+    pub async fn broadcast_signed_message(&self, envelope: &SignedMessage) -> usize {
+        let sender_id = envelope.sender_id();
+        self.count_sent(sender_id);
 
-        delivered
+        let vehicles = self.vehicles.read().await;
+        // the sender is filtered out here, before any OBU is locked, so a
+        // sender whose OBU is locked by the caller is never waited on
+        let deliveries = self.sample_deliveries(sender_id, vehicles.keys());
+        if let Some(slowest) = deliveries.iter().map(|(_, latency)| *latency).max() {
+            tokio::time::sleep(slowest).await;
+        }
+
+        let mut accepted = 0;
+        for (vehicle_id, _) in &deliveries {
+            if vehicles[vehicle_id]
+                .lock()
+                .await
+                .receive_message(envelope)
+                .is_ok()
+            {
+                accepted += 1;
+            }
+        }
+        accepted
     }
 
     pub fn get_message_count(&self) -> usize {
         self.message_counter.load(Ordering::Relaxed)
     }
+
+    /// Clears the global and per-sender counters, e.g. between benchmark phases.
+    pub fn reset_message_count(&self) {
+        self.message_counter.store(0, Ordering::Relaxed);
+        if let Ok(mut per_sender) = self.messages_by_sender.lock() {
+            per_sender.clear();
+        }
+    }
+
+    pub async fn messages_by_sender(&self) -> HashMap<String, usize> {
+        self.messages_by_sender
+            .lock()
+            .map(|per_sender| per_sender.clone())
+            .unwrap_or_default()
+    }
 }
@@ -0,0 +1,87 @@
+use super::certificate::{CURRENT_CERT_VERSION, Certificate, CertificateStatus};
+use crate::crypto::{HardwareSecurityModule, KeyAlgorithm};
+use chrono::{DateTime, Duration, Utc};
+
+/// Assembles certificates so the hash and CA signature are always computed
+/// the same way.
+#[derive(Debug, Clone, Default)]
+pub struct CertificateBuilder {
+    id: Option<String>,
+    vehicle_id: Option<String>,
+    public_key: Option<Vec<u8>>,
+    issued_at: Option<DateTime<Utc>>,
+    validity: Option<Duration>,
+}
+
+impl CertificateBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn vehicle_id(mut self, vehicle_id: impl Into<String>) -> Self {
+        self.vehicle_id = Some(vehicle_id.into());
+        self
+    }
+
+    pub fn public_key(mut self, public_key: Vec<u8>) -> Self {
+        self.public_key = Some(public_key);
+        self
+    }
+
+    /// Defaults to the time `build` is called.
+    pub fn issued_at(mut self, issued_at: DateTime<Utc>) -> Self {
+        self.issued_at = Some(issued_at);
+        self
+    }
+
+    /// Defaults to 365 days.
+    pub fn validity(mut self, validity: Duration) -> Self {
+        self.validity = Some(validity);
+        self
+    }
+
+    /// Builds the certificate with its hash filled in but no signature.
+    fn build_unsigned(&self, ca_id: &str) -> Result<Certificate, String> {
+        let id = self.id.clone().ok_or("Certificate id not set")?;
+        let vehicle_id = self.vehicle_id.clone().ok_or("Vehicle id not set")?;
+        let public_key = self.public_key.clone().ok_or("Public key not set")?;
+        let issued_at = self.issued_at.unwrap_or_else(Utc::now);
+        let expires_at = issued_at + self.validity.unwrap_or_else(|| Duration::days(365));
+
+        let mut cert = Certificate {
+            id,
+            vehicle_id,
+            public_key,
+            issued_at,
+            expires_at,
+            issuer_ca: ca_id.to_string(),
+            status: CertificateStatus::Active,
+            certificate_hash: String::new(),
+            signature: vec![],
+            signature_algorithm: KeyAlgorithm::default(),
+            version: CURRENT_CERT_VERSION,
+        };
+        cert.certificate_hash = cert.compute_hash();
+        Ok(cert)
+    }
+
+    /// Builds the certificate and signs its canonical bytes with `ca_id`'s HSM key.
+    pub async fn build(
+        &self,
+        hsm: &HardwareSecurityModule,
+        ca_id: &str,
+    ) -> Result<Certificate, String> {
+        let mut cert = self.build_unsigned(ca_id)?;
+        let (algorithm, signature) = hsm
+            .sign_with_algorithm(ca_id, &cert.canonical_bytes())
+            .await?;
+        cert.signature_algorithm = algorithm;
+        cert.signature = signature;
+        Ok(cert)
+    }
+}
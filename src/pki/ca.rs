@@ -1,83 +1,671 @@
+use super::builder::CertificateBuilder;
 use super::certificate::{Certificate, CertificateStatus};
-use crate::crypto::HardwareSecurityModule;
-use chrono::{DateTime, Duration, Utc};
-use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use super::crl::{Crl, CrlEntry};
+use super::csr::CertificateSigningRequest;
+use super::issuance::{AllowAll, IssuanceError, PreIssuanceChecker};
+use super::misbehavior::{MisbehaviorOutcome, MisbehaviorReport};
+use super::revocation::{RevocationError, RevocationReason, RevocationRequest};
+use super::revocation_log::RevocationLog;
+use crate::blockchain::{BlockchainTransaction, KeyRotationRecord, TransactionType};
+use crate::crypto::{HardwareSecurityModule, KeyAlgorithm, is_weak_ed25519_key, verify_ed25519};
+use crate::time::{Clock, SystemClock};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{Mutex, RwLock};
+
+/// Point-in-time view of a CA for dashboards, from `statistics_snapshot`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaStatistics {
+    pub ca_id: String,
+    pub total_issued: usize,
+    pub active: usize,
+    pub revoked: usize,
+    /// Stored as `Expired`, or still `Active` with a lapsed validity period.
+    pub expired: usize,
+    pub deprecated: usize,
+    pub suspended: usize,
+    /// Certificates issued in the last 60 seconds, per second.
+    pub issuance_rate_per_sec: f64,
+    pub revocation_list_size: usize,
+    pub hsm_operations: usize,
+}
+
+/// Answer from the CA's OCSP-style responder, `check_status`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateStatusResponse {
+    pub cert_id: String,
+    /// `None` if this CA never issued the certificate.
+    pub status: Option<CertificateStatus>,
+    pub produced_at: DateTime<Utc>,
+    pub issuer: String,
+}
 
 pub struct CertificateAuthority {
     pub ca_id: String,
     hsm: Arc<HardwareSecurityModule>,
     issued_certificates: Arc<RwLock<HashMap<String, Certificate>>>,
-    revocation_list: Arc<RwLock<Vec<String>>>,
+    revocation_list: Arc<RwLock<Vec<CrlEntry>>>,
+    /// Gap between a CRL's `this_update` and `next_update`.
+    crl_lifetime: chrono::Duration,
+    issue_sequence: Arc<AtomicU64>,
+    misbehavior_reports: Arc<RwLock<HashMap<String, Vec<MisbehaviorReport>>>>,
+    misbehavior_threshold: usize,
+    operator_keys: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+    clock: Arc<dyn Clock>,
+    pre_issuance: Arc<dyn PreIssuanceChecker>,
+    revocation_log: Option<Arc<Mutex<RevocationLog>>>,
+    /// Public keys replaced by `rotate_key`, oldest first, so certificates
+    /// signed before a rotation still verify.
+    retired_keys: Arc<RwLock<Vec<Vec<u8>>>>,
+    /// Pseudonym certificate id to the real vehicle id it was issued for.
+    pseudonyms: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl CertificateAuthority {
     pub async fn new(ca_id: String, hsm: Arc<HardwareSecurityModule>) -> Self {
-        hsm.generate_ca_keypair(&ca_id).await;
+        Self::new_with_algorithm(ca_id, hsm, KeyAlgorithm::Ed25519).await
+    }
+
+    /// A CA whose signing key the HSM generates under `algorithm`.
+    pub async fn new_with_algorithm(
+        ca_id: String,
+        hsm: Arc<HardwareSecurityModule>,
+        algorithm: KeyAlgorithm,
+    ) -> Self {
+        hsm.generate_ca_keypair_with_algorithm(&ca_id, algorithm)
+            .await;
 
         Self {
             ca_id,
             hsm,
             issued_certificates: Arc::new(RwLock::new(HashMap::new())),
             revocation_list: Arc::new(RwLock::new(Vec::new())),
+            crl_lifetime: chrono::Duration::hours(1),
+            issue_sequence: Arc::new(AtomicU64::new(0)),
+            misbehavior_reports: Arc::new(RwLock::new(HashMap::new())),
+            misbehavior_threshold: 3,
+            operator_keys: Arc::new(RwLock::new(HashMap::new())),
+            clock: Arc::new(SystemClock),
+            pre_issuance: Arc::new(AllowAll),
+            revocation_log: None,
+            retired_keys: Arc::new(RwLock::new(Vec::new())),
+            pseudonyms: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    pub async fn issue_certificate(&self, vehicle_id: String, public_key: Vec<u8>) -> Certificate {
-        let cert_id = format!("CERT-{}-{}", vehicle_id, Utc::now().timestamp_millis());
-        let issued_at = Utc::now();
-        let expires_at = issued_at + Duration::days(365);
+    /// Replaces the wall clock used for issuance, revocation and expiry.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Installs a check every vehicle must pass before a certificate is issued.
+    pub fn with_pre_issuance_checker(mut self, checker: Arc<dyn PreIssuanceChecker>) -> Self {
+        self.pre_issuance = checker;
+        self
+    }
+
+    /// Appends every revocation to a signed JSONL log at `path`, continuing
+    /// after any entries already there. Check it with `verify_revocation_log`.
+    pub fn with_revocation_log(mut self, path: &str) -> Result<Self, String> {
+        self.revocation_log = Some(Arc::new(Mutex::new(RevocationLog::open(path)?)));
+        Ok(self)
+    }
+
+    /// How long relying parties may cache a CRL from `generate_crl`.
+    pub fn with_crl_lifetime(mut self, lifetime: chrono::Duration) -> Self {
+        self.crl_lifetime = lifetime;
+        self
+    }
+
+    /// Number of distinct reporters needed before an accused vehicle is revoked.
+    pub fn with_misbehavior_threshold(mut self, threshold: usize) -> Self {
+        self.misbehavior_threshold = threshold.max(1);
+        self
+    }
+
+    /// Issues a certificate valid for 365 days.
+    pub async fn issue_certificate(
+        &self,
+        vehicle_id: String,
+        public_key: Vec<u8>,
+    ) -> Result<Certificate, IssuanceError> {
+        self.issue_certificate_with_validity(vehicle_id, public_key, chrono::Duration::days(365))
+            .await
+    }
+
+    /// Issues a certificate valid for `validity` from now, e.g. a few seconds
+    /// for certificates that must expire within a benchmark run.
+    pub async fn issue_certificate_with_validity(
+        &self,
+        vehicle_id: String,
+        public_key: Vec<u8>,
+        validity: chrono::Duration,
+    ) -> Result<Certificate, IssuanceError> {
+        let cert_id = self.next_cert_id(&vehicle_id);
+        self.issue(cert_id, vehicle_id, public_key, validity).await
+    }
 
-        let mut hasher = Sha256::new();
-        hasher.update(&cert_id);
-        hasher.update(&vehicle_id);
-        hasher.update(&public_key);
-        let certificate_hash = format!("{:x}", hasher.finalize());
+    /// Issues a certificate for a vehicle-generated key once the request's
+    /// self-signature proves the vehicle holds the private key.
+    pub async fn process_csr(
+        &self,
+        csr: CertificateSigningRequest,
+    ) -> Result<Certificate, IssuanceError> {
+        if !csr.verify() {
+            return Err(IssuanceError::InvalidProofOfPossession(csr.vehicle_id));
+        }
+        self.issue_certificate(csr.vehicle_id, csr.public_key).await
+    }
 
-        let cert = Certificate {
-            id: cert_id.clone(),
+    fn next_cert_id(&self, vehicle_id: &str) -> String {
+        // the CA prefix keeps ids from different CAs apart; the sequence suffix
+        // keeps them unique when one CA issues twice for a vehicle within a millisecond
+        let seq = self.issue_sequence.fetch_add(1, Ordering::Relaxed);
+        format!(
+            "{}:CERT-{}-{}-{}",
+            self.ca_id,
             vehicle_id,
-            public_key,
-            issued_at,
-            expires_at,
-            issuer_ca: self.ca_id.clone(),
-            status: CertificateStatus::Active,
-            certificate_hash,
+            self.clock.now().timestamp_millis(),
+            seq
+        )
+    }
+
+    /// Issues a certificate under a caller-supplied id. Re-issuing an active
+    /// certificate's id for the same vehicle and key returns it unchanged;
+    /// any other reuse of the id is refused.
+    pub async fn issue_certificate_with_id(
+        &self,
+        cert_id: String,
+        vehicle_id: String,
+        public_key: Vec<u8>,
+    ) -> Result<Certificate, IssuanceError> {
+        self.issue(cert_id, vehicle_id, public_key, chrono::Duration::days(365))
+            .await
+    }
+
+    async fn issue(
+        &self,
+        cert_id: String,
+        vehicle_id: String,
+        public_key: Vec<u8>,
+        validity: chrono::Duration,
+    ) -> Result<Certificate, IssuanceError> {
+        if let Some(existing) = self.issued_certificates.read().await.get(&cert_id) {
+            return reissue(existing, &vehicle_id, &public_key);
+        }
+        if validity <= chrono::Duration::zero() {
+            return Err(IssuanceError::InvalidValidity(validity));
+        }
+        if is_weak_ed25519_key(&public_key) {
+            log::warn!(
+                "{} refused to issue {}: weak public key",
+                self.ca_id,
+                cert_id
+            );
+            return Err(IssuanceError::WeakKey(vehicle_id));
+        }
+
+        self.pre_issuance
+            .check(&vehicle_id)
+            .await
+            .map_err(IssuanceError::Rejected)?;
+
+        let builder = CertificateBuilder::new()
+            .id(cert_id.clone())
+            .vehicle_id(vehicle_id)
+            .public_key(public_key)
+            .issued_at(self.clock.now())
+            .validity(validity);
+        let cert = self.sign(&builder, &cert_id).await?;
+
+        let mut issued = self.issued_certificates.write().await;
+        // another issuance may have taken the id while this one was signing
+        if let Some(existing) = issued.get(&cert_id) {
+            return reissue(existing, cert.vehicle_id.as_str(), &cert.public_key);
+        }
+        issued.insert(cert_id, cert.clone());
+        Ok(cert)
+    }
+
+    async fn sign(
+        &self,
+        builder: &CertificateBuilder,
+        cert_id: &str,
+    ) -> Result<Certificate, IssuanceError> {
+        // the signature travels with the certificate (and into the chain
+        // transaction) so relying parties can check it was genuinely issued
+        builder.build(&self.hsm, &self.ca_id).await.map_err(|e| {
+            log::warn!("{} could not sign {}: {}", self.ca_id, cert_id, e);
+            IssuanceError::SigningFailed(format!("{}: {}", cert_id, e))
+        })
+    }
+
+    /// Issues a short-lived pseudonym certificate for each of `vehicle_id`'s
+    /// `public_keys`, valid for `validity_mins`. The keys are generated by
+    /// the vehicle (see `OnBoardUnit::generate_pseudonym_keys`); the CA never
+    /// holds their private halves. Certificate ids and the certified
+    /// "vehicle" are random tokens, so peers cannot link pseudonyms to each
+    /// other or to the vehicle; only this CA keeps the mapping, for
+    /// `revoke_pseudonyms`. Nothing is issued unless every key is usable and
+    /// every certificate in the batch could be signed.
+    pub async fn issue_pseudonym_batch(
+        &self,
+        vehicle_id: &str,
+        public_keys: Vec<Vec<u8>>,
+        validity_mins: i64,
+    ) -> Result<Vec<Certificate>, IssuanceError> {
+        let validity = chrono::Duration::minutes(validity_mins);
+        if validity <= chrono::Duration::zero() {
+            return Err(IssuanceError::InvalidValidity(validity));
+        }
+        if public_keys.iter().any(|key| is_weak_ed25519_key(key)) {
+            return Err(IssuanceError::WeakKey(vehicle_id.to_string()));
+        }
+        if let Err(reason) = self.pre_issuance.check(vehicle_id).await {
+            log::warn!(
+                "{} refused pseudonyms for {}: {}",
+                self.ca_id,
+                vehicle_id,
+                reason
+            );
+            return Err(IssuanceError::Rejected(reason));
+        }
+
+        let mut batch = Vec::with_capacity(public_keys.len());
+        for public_key in public_keys {
+            let mut token = [0u8; 16];
+            OsRng.fill_bytes(&mut token);
+            let pseudonym = format!("PSN-{}", hex::encode(token));
+            let cert_id = format!("{}:{}", self.ca_id, pseudonym);
+
+            let builder = CertificateBuilder::new()
+                .id(cert_id.clone())
+                .vehicle_id(pseudonym)
+                .public_key(public_key)
+                .issued_at(self.clock.now())
+                .validity(validity);
+            batch.push(self.sign(&builder, &cert_id).await?);
+        }
+
+        let mut certs = self.issued_certificates.write().await;
+        let mut pseudonyms = self.pseudonyms.write().await;
+        for cert in &batch {
+            certs.insert(cert.id.clone(), cert.clone());
+            pseudonyms.insert(cert.id.clone(), vehicle_id.to_string());
+        }
+        Ok(batch)
+    }
+
+    /// The real vehicle behind a pseudonym certificate, if this CA issued it.
+    pub async fn resolve_pseudonym(&self, cert_id: &str) -> Option<String> {
+        self.pseudonyms.read().await.get(cert_id).cloned()
+    }
+
+    /// Revokes every pseudonym certificate issued for `vehicle_id` and returns
+    /// their ids, including ones that were already revoked.
+    pub async fn revoke_pseudonyms(
+        &self,
+        vehicle_id: &str,
+        reason: impl Into<RevocationReason>,
+    ) -> Result<Vec<String>, RevocationError> {
+        let reason = reason.into();
+        let cert_ids: Vec<String> = self
+            .pseudonyms
+            .read()
+            .await
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == vehicle_id)
+            .map(|(cert_id, _)| cert_id.clone())
+            .collect();
+        for cert_id in &cert_ids {
+            self.revoke_with_reason(cert_id, reason.clone()).await?;
+        }
+        Ok(cert_ids)
+    }
+
+    /// Replaces `cert_id` with a fresh certificate for the same vehicle and
+    /// key, valid for `new_validity_days` from now, and marks the old one
+    /// `Deprecated`. Revoked, suspended and already-superseded certificates
+    /// cannot be renewed. Commit the result with `BlockchainTransaction::renewal`.
+    pub async fn renew_certificate(
+        &self,
+        cert_id: &str,
+        new_validity_days: i64,
+    ) -> Result<Certificate, IssuanceError> {
+        let validity = chrono::Duration::days(new_validity_days);
+        if validity <= chrono::Duration::zero() {
+            return Err(IssuanceError::InvalidValidity(validity));
+        }
+        let old = self
+            .get_certificate(cert_id)
+            .await
+            .ok_or_else(|| IssuanceError::UnknownCertificate(cert_id.to_string()))?;
+        if !matches!(
+            old.status,
+            CertificateStatus::Active | CertificateStatus::Expired
+        ) {
+            return Err(IssuanceError::NotRenewable {
+                cert_id: cert_id.to_string(),
+                status: old.status,
+            });
+        }
+
+        let new_id = self.next_cert_id(&old.vehicle_id);
+        let builder = CertificateBuilder::new()
+            .id(new_id.clone())
+            .vehicle_id(old.vehicle_id)
+            .public_key(old.public_key)
+            .issued_at(self.clock.now())
+            .validity(validity);
+        let cert = self.sign(&builder, &new_id).await?;
+
+        let mut certs = self.issued_certificates.write().await;
+        // re-check under the write lock: a concurrent revocation or renewal wins
+        match certs.get_mut(cert_id) {
+            Some(old)
+                if matches!(
+                    old.status,
+                    CertificateStatus::Active | CertificateStatus::Expired
+                ) =>
+            {
+                old.status = CertificateStatus::Deprecated;
+            }
+            Some(old) => {
+                return Err(IssuanceError::NotRenewable {
+                    cert_id: cert_id.to_string(),
+                    status: old.status.clone(),
+                });
+            }
+            None => return Err(IssuanceError::UnknownCertificate(cert_id.to_string())),
+        }
+        certs.insert(new_id, cert.clone());
+        Ok(cert)
+    }
+
+    /// Rotates this CA's signing key. The returned record should be committed
+    /// as a `KeyRotation` transaction so edge nodes learn the new key.
+    pub async fn rotate_key(&self) -> Result<KeyRotationRecord, String> {
+        let (old_public_key, new_public_key, signature) =
+            self.hsm.rotate_ca_keypair(&self.ca_id).await?;
+        let algorithm = self
+            .hsm
+            .key_algorithm(&self.ca_id)
+            .await
+            .unwrap_or_default();
+        self.retired_keys.write().await.push(old_public_key.clone());
+        Ok(KeyRotationRecord {
+            ca_id: self.ca_id.clone(),
+            old_public_key,
+            new_public_key,
+            signature,
+            algorithm,
+        })
+    }
+
+    /// This CA's current verifying key, for relying parties: 32 bytes for
+    /// ed25519, 33 for secp256k1. Empty only if the HSM has lost the key.
+    pub async fn public_key(&self) -> Vec<u8> {
+        self.hsm
+            .get_public_key(&self.ca_id)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// True if `cert` names this CA as issuer and carries a valid signature
+    /// from its current key or any key it has rotated away from.
+    pub async fn verify_certificate(&self, cert: &Certificate) -> bool {
+        if cert.issuer_ca != self.ca_id {
+            return false;
+        }
+        let current = self.hsm.get_public_key(&self.ca_id).await;
+        let retired = self.retired_keys.read().await;
+        current
+            .iter()
+            .chain(retired.iter().rev())
+            .any(|key| cert.verify(key))
+    }
+
+    pub async fn revoke_certificate(
+        &self,
+        cert_id: &str,
+        reason: RevocationReason,
+    ) -> Result<DateTime<Utc>, String> {
+        self.revoke_with_reason(cert_id, reason)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Revokes `cert_id`, recording `reason` in the revocation log if one is
+    /// configured. Idempotent: revoking an already revoked certificate
+    /// succeeds without logging it again, so failed attempts can be retried.
+    pub async fn revoke_with_reason(
+        &self,
+        cert_id: &str,
+        reason: impl Into<RevocationReason>,
+    ) -> Result<DateTime<Utc>, RevocationError> {
+        let reason = reason.into();
+        let revocation_time = self.clock.now();
+
+        let mut certs = self.issued_certificates.write().await;
+        let Some(cert) = certs.get_mut(cert_id) else {
+            return Err(RevocationError::NotFound);
         };
+        if cert.status == CertificateStatus::Revoked {
+            return Ok(revocation_time);
+        }
 
-        let cert_data = serde_json::to_vec(&cert).unwrap();
-        let _ = self.hsm.sign_certificate(&self.ca_id, &cert_data).await;
+        // logged before the status changes, so a revocation never takes
+        // effect without its audit record
+        if let Some(log) = &self.revocation_log {
+            let mut log = log.lock().await;
+            let mut entry = log.next_entry(cert_id, &reason.to_string(), revocation_time);
+            (entry.signature_algorithm, entry.signature) = self
+                .hsm
+                .sign_with_algorithm(&self.ca_id, &entry.signing_bytes())
+                .await
+                .map_err(RevocationError::Unrecorded)?;
+            log.append(&entry).map_err(RevocationError::Unrecorded)?;
+        }
 
-        self.issued_certificates
-            .write()
+        cert.status = CertificateStatus::Revoked;
+        drop(certs);
+
+        self.revocation_list.write().await.push(CrlEntry {
+            cert_id: cert_id.to_string(),
+            revocation_time,
+            reason,
+        });
+        Ok(revocation_time)
+    }
+
+    /// Signed list of every certificate this CA has revoked, valid from now
+    /// for the configured CRL lifetime. Falls back to an unsigned list, which
+    /// relying parties will reject, if the HSM cannot sign.
+    pub async fn generate_crl(&self) -> Crl {
+        let this_update = self.clock.now();
+        let mut crl = Crl {
+            issuer: self.ca_id.clone(),
+            this_update,
+            next_update: this_update + self.crl_lifetime,
+            entries: self.revocation_list.read().await.clone(),
+            signature: vec![],
+            signature_algorithm: KeyAlgorithm::default(),
+        };
+        match self
+            .hsm
+            .sign_with_algorithm(&self.ca_id, &crl.signing_bytes())
             .await
-            .insert(cert_id, cert.clone());
-        cert
+        {
+            Ok((algorithm, signature)) => {
+                crl.signature_algorithm = algorithm;
+                crl.signature = signature;
+            }
+            Err(e) => log::warn!("{} issued an unsigned CRL: {}", self.ca_id, e),
+        }
+        crl
     }
 
-    pub async fn revoke_certificate(&self, cert_id: &str) -> Result<DateTime<Utc>, String> {
-        let revocation_time = Utc::now();
+    /// Places an active certificate on hold. Unlike revocation this can be
+    /// undone with `unsuspend_certificate`.
+    pub async fn suspend_certificate(&self, cert_id: &str) -> Result<DateTime<Utc>, String> {
+        self.transition(
+            cert_id,
+            CertificateStatus::Active,
+            CertificateStatus::Suspended,
+        )
+        .await
+    }
 
+    /// Returns a suspended certificate to `Active`.
+    pub async fn unsuspend_certificate(&self, cert_id: &str) -> Result<DateTime<Utc>, String> {
+        self.transition(
+            cert_id,
+            CertificateStatus::Suspended,
+            CertificateStatus::Active,
+        )
+        .await
+    }
+
+    async fn transition(
+        &self,
+        cert_id: &str,
+        from: CertificateStatus,
+        to: CertificateStatus,
+    ) -> Result<DateTime<Utc>, String> {
         let mut certs = self.issued_certificates.write().await;
-        if let Some(cert) = certs.get_mut(cert_id) {
-            cert.status = CertificateStatus::Revoked;
-            drop(certs);
+        let cert = certs.get_mut(cert_id).ok_or("Certificate not found")?;
+        if cert.status != from {
+            return Err(format!(
+                "Certificate is {:?}, expected {:?}",
+                cert.status, from
+            ));
+        }
+        cert.status = to;
+        Ok(self.clock.now())
+    }
+
+    /// Accepts a misbehavior report signed by a vehicle holding an active
+    /// certificate from this CA. Once reports from `misbehavior_threshold`
+    /// distinct reporters accumulate, every active certificate of the accused
+    /// vehicle is revoked.
+    pub async fn submit_misbehavior_report(
+        &self,
+        report: MisbehaviorReport,
+    ) -> Result<MisbehaviorOutcome, String> {
+        if report.reporter_id == report.accused_vehicle_id {
+            return Err("Vehicles cannot report themselves".to_string());
+        }
+
+        let now = self.clock.now();
+        let reporter_verified = self
+            .issued_certificates
+            .read()
+            .await
+            .values()
+            .filter(|cert| cert.vehicle_id == report.reporter_id && cert.is_valid_at(now))
+            .any(|cert| {
+                verify_ed25519(&cert.public_key, &report.signing_bytes(), &report.signature)
+            });
+        if !reporter_verified {
+            return Err("Misbehavior report signature invalid".to_string());
+        }
 
-            self.revocation_list.write().await.push(cert_id.to_string());
-            Ok(revocation_time)
-        } else {
-            Err("Certificate not found".to_string())
+        let distinct_reporters = {
+            let mut reports = self.misbehavior_reports.write().await;
+            let accused_reports = reports
+                .entry(report.accused_vehicle_id.clone())
+                .or_default();
+            accused_reports.push(report.clone());
+            accused_reports
+                .iter()
+                .map(|r| r.reporter_id.as_str())
+                .collect::<HashSet<_>>()
+                .len()
+        };
+
+        if distinct_reporters < self.misbehavior_threshold {
+            return Ok(MisbehaviorOutcome::Recorded { distinct_reporters });
+        }
+
+        let accused_certs: Vec<String> = self
+            .issued_certificates
+            .read()
+            .await
+            .values()
+            .filter(|cert| {
+                cert.vehicle_id == report.accused_vehicle_id
+                    && cert.status == CertificateStatus::Active
+            })
+            .map(|cert| cert.id.clone())
+            .collect();
+
+        let mut revoked_cert_ids = Vec::new();
+        let mut transactions = Vec::new();
+        for cert_id in accused_certs {
+            if self
+                .revoke_with_reason(&cert_id, RevocationReason::Misbehavior)
+                .await
+                .is_ok()
+            {
+                transactions.push(BlockchainTransaction::new(
+                    cert_id.clone(),
+                    TransactionType::CertificateRevocation,
+                    report.accused_vehicle_id.as_bytes().to_vec(),
+                ));
+                revoked_cert_ids.push(cert_id);
+            }
         }
+        self.misbehavior_reports
+            .write()
+            .await
+            .remove(&report.accused_vehicle_id);
+
+        Ok(MisbehaviorOutcome::Revoked {
+            revoked_cert_ids,
+            transactions,
+        })
+    }
+
+    /// Authorizes a fleet operator's key to request revocations.
+    pub async fn register_operator(&self, operator_id: String, public_key: Vec<u8>) {
+        self.operator_keys
+            .write()
+            .await
+            .insert(operator_id, public_key);
+    }
+
+    /// Revokes a certificate on behalf of a registered operator, after
+    /// checking the request's signature against that operator's key.
+    pub async fn revoke_authorized(
+        &self,
+        request: &RevocationRequest,
+    ) -> Result<DateTime<Utc>, RevocationError> {
+        let authorized = self
+            .operator_keys
+            .read()
+            .await
+            .get(&request.operator_id)
+            .is_some_and(|key| verify_ed25519(key, &request.signing_bytes(), &request.signature));
+        if !authorized {
+            return Err(RevocationError::Unauthorized(request.operator_id.clone()));
+        }
+
+        self.revoke_with_reason(&request.cert_id, request.reason.as_str())
+            .await
     }
 
     pub async fn deprecate_expired_certificates(&self) -> Vec<String> {
         let mut deprecated = Vec::new();
+        let now = self.clock.now();
         let mut certs = self.issued_certificates.write().await;
 
         for (cert_id, cert) in certs.iter_mut() {
-            if cert.is_expired() && cert.status == CertificateStatus::Active {
+            if cert.is_expired_at(now) && cert.status == CertificateStatus::Active {
                 cert.status = CertificateStatus::Deprecated;
                 deprecated.push(cert_id.clone());
             }
@@ -86,6 +674,35 @@ impl CertificateAuthority {
         deprecated
     }
 
+    /// Current status of a single certificate, as an OCSP responder would
+    /// report it: revoked if on the revocation list, expired once its
+    /// validity has lapsed, otherwise its stored status.
+    pub async fn check_status(&self, cert_id: &str) -> CertificateStatusResponse {
+        let now = self.clock.now();
+        let revoked = self
+            .revocation_list
+            .read()
+            .await
+            .iter()
+            .any(|entry| entry.cert_id == cert_id);
+        let status = self
+            .issued_certificates
+            .read()
+            .await
+            .get(cert_id)
+            .map(|cert| match &cert.status {
+                _ if revoked => CertificateStatus::Revoked,
+                CertificateStatus::Active if cert.is_expired_at(now) => CertificateStatus::Expired,
+                status => status.clone(),
+            });
+        CertificateStatusResponse {
+            cert_id: cert_id.to_string(),
+            status,
+            produced_at: now,
+            issuer: self.ca_id.clone(),
+        }
+    }
+
     pub async fn get_certificate(&self, cert_id: &str) -> Option<Certificate> {
         self.issued_certificates.read().await.get(cert_id).cloned()
     }
@@ -93,4 +710,72 @@ impl CertificateAuthority {
     pub async fn get_total_issued(&self) -> usize {
         self.issued_certificates.read().await.len()
     }
+
+    pub async fn statistics_snapshot(&self) -> CaStatistics {
+        let now = self.clock.now();
+        let window_start = now - chrono::Duration::seconds(60);
+        let mut stats = CaStatistics {
+            ca_id: self.ca_id.clone(),
+            total_issued: 0,
+            active: 0,
+            revoked: 0,
+            expired: 0,
+            deprecated: 0,
+            suspended: 0,
+            issuance_rate_per_sec: 0.0,
+            revocation_list_size: self.revocation_list.read().await.len(),
+            hsm_operations: self.hsm.get_operation_count().await,
+        };
+
+        let mut recent = 0usize;
+        for cert in self.issued_certificates.read().await.values() {
+            stats.total_issued += 1;
+            if cert.issued_at > window_start {
+                recent += 1;
+            }
+            match cert.status {
+                CertificateStatus::Active if cert.is_expired_at(now) => stats.expired += 1,
+                CertificateStatus::Active | CertificateStatus::ExpiringSoon => stats.active += 1,
+                CertificateStatus::Revoked => stats.revoked += 1,
+                CertificateStatus::Expired => stats.expired += 1,
+                CertificateStatus::Deprecated => stats.deprecated += 1,
+                CertificateStatus::Suspended => stats.suspended += 1,
+            }
+        }
+        stats.issuance_rate_per_sec = recent as f64 / 60.0;
+
+        stats
+    }
+}
+
+/// Outcome of issuing `existing`'s id again: the same certificate if it is
+/// still active for the same vehicle and key, otherwise an error.
+fn reissue(
+    existing: &Certificate,
+    vehicle_id: &str,
+    public_key: &[u8],
+) -> Result<Certificate, IssuanceError> {
+    if existing.vehicle_id != vehicle_id || existing.public_key != public_key {
+        return Err(IssuanceError::IdConflict(existing.id.clone()));
+    }
+    if existing.status != CertificateStatus::Active {
+        return Err(IssuanceError::IdInUse {
+            cert_id: existing.id.clone(),
+            status: existing.status.clone(),
+        });
+    }
+    Ok(existing.clone())
+}
+
+/// Test-only inspection API, enabled by the `test-harness` feature.
+#[cfg(feature = "test-harness")]
+impl CertificateAuthority {
+    pub async fn revocation_snapshot(&self) -> Vec<String> {
+        self.revocation_list
+            .read()
+            .await
+            .iter()
+            .map(|entry| entry.cert_id.clone())
+            .collect()
+    }
 }
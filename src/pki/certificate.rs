@@ -1,5 +1,68 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use crate::crypto::KeyAlgorithm;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Layout version written into newly issued certificates.
+///
+/// - 1: original layout, no `version` field; the signature does not cover a version.
+/// - 2: adds `version`, which `canonical_bytes` covers.
+pub const CURRENT_CERT_VERSION: u16 = 2;
+
+/// Certificates stored before versioning existed carry no `version` field.
+fn legacy_version() -> u16 {
+    1
+}
+
+/// Accepts every known version and rejects certificates from a newer format
+/// this build cannot interpret.
+fn deserialize_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+    let version = u16::deserialize(deserializer)?;
+    if version == 0 || version > CURRENT_CERT_VERSION {
+        return Err(serde::de::Error::custom(format!(
+            "unsupported certificate version {} (this build reads 1..={})",
+            version, CURRENT_CERT_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+/// Fields a certificate must carry; `signature` and `version` have defaults.
+const REQUIRED_FIELDS: [&str; 8] = [
+    "id",
+    "vehicle_id",
+    "public_key",
+    "issued_at",
+    "expires_at",
+    "issuer_ca",
+    "status",
+    "certificate_hash",
+];
+
+/// Why `Certificate::from_json_validated` rejected its input.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ValidationError {
+    #[error("malformed certificate JSON: {0}")]
+    Malformed(String),
+    #[error("missing field `{0}`")]
+    MissingField(&'static str),
+    #[error("field `{0}` must not be empty")]
+    EmptyField(&'static str),
+    #[error("public key must be 32 bytes, got {0}")]
+    PublicKeyLength(usize),
+    #[error("expires_at ({expires_at}) must be after issued_at ({issued_at})")]
+    ValidityPeriod {
+        issued_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    },
+    #[error("unknown certificate status {0}")]
+    UnknownStatus(String),
+    #[error("unsupported certificate version {0}")]
+    UnsupportedVersion(String),
+    #[error("certificate_hash does not match the certificate contents")]
+    HashMismatch,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Certificate {
@@ -11,6 +74,15 @@ pub struct Certificate {
     pub issuer_ca: String,
     pub status: CertificateStatus,
     pub certificate_hash: String,
+    /// Issuing CA's signature over `canonical_bytes`.
+    #[serde(default)]
+    pub signature: Vec<u8>,
+    /// Algorithm of the CA key that made `signature`. Not covered by the
+    /// signature; a key only ever verifies under its own algorithm.
+    #[serde(default)]
+    pub signature_algorithm: KeyAlgorithm,
+    #[serde(default = "legacy_version", deserialize_with = "deserialize_version")]
+    pub version: u16,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -19,14 +91,173 @@ pub enum CertificateStatus {
     Revoked,
     Expired,
     Deprecated,
+    /// Temporarily held, e.g. pending an investigation. Fails authentication
+    /// like `Revoked`, but the CA can reinstate it to `Active`.
+    Suspended,
+    /// Advisory from `effective_status`: still valid, but inside the soft-expiry
+    /// band, so the holder should renew. Never stored on a certificate.
+    ExpiringSoon,
 }
 
 impl Certificate {
+    /// Parses a certificate from an external source, checking each field so
+    /// that a nonsensical certificate is rejected with the field at fault
+    /// rather than accepted or refused with an opaque serde error. Does not
+    /// check the signature; use `verify` with the issuer's key for that.
+    pub fn from_json_validated(bytes: &[u8]) -> Result<Certificate, ValidationError> {
+        let value: serde_json::Value =
+            serde_json::from_slice(bytes).map_err(|e| ValidationError::Malformed(e.to_string()))?;
+        let fields = value
+            .as_object()
+            .ok_or_else(|| ValidationError::Malformed("expected a JSON object".to_string()))?;
+
+        if let Some(missing) = REQUIRED_FIELDS.iter().find(|f| !fields.contains_key(**f)) {
+            return Err(ValidationError::MissingField(missing));
+        }
+        let status = &fields["status"];
+        match serde_json::from_value::<CertificateStatus>(status.clone()) {
+            // advisory only, never stored on a certificate
+            Ok(CertificateStatus::ExpiringSoon) | Err(_) => {
+                return Err(ValidationError::UnknownStatus(status.to_string()));
+            }
+            Ok(_) => {}
+        }
+        if let Some(version) = fields.get("version") {
+            let known = version
+                .as_u64()
+                .is_some_and(|v| (1..=CURRENT_CERT_VERSION as u64).contains(&v));
+            if !known {
+                return Err(ValidationError::UnsupportedVersion(version.to_string()));
+            }
+        }
+
+        let cert: Certificate =
+            serde_json::from_value(value).map_err(|e| ValidationError::Malformed(e.to_string()))?;
+        cert.validate()?;
+        Ok(cert)
+    }
+
+    /// Field constraints every well-formed certificate satisfies.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for (name, value) in [
+            ("id", &self.id),
+            ("vehicle_id", &self.vehicle_id),
+            ("issuer_ca", &self.issuer_ca),
+            ("certificate_hash", &self.certificate_hash),
+        ] {
+            if value.is_empty() {
+                return Err(ValidationError::EmptyField(name));
+            }
+        }
+        if self.public_key.len() != 32 {
+            return Err(ValidationError::PublicKeyLength(self.public_key.len()));
+        }
+        if self.expires_at <= self.issued_at {
+            return Err(ValidationError::ValidityPeriod {
+                issued_at: self.issued_at,
+                expires_at: self.expires_at,
+            });
+        }
+        if self.compute_hash() != self.certificate_hash {
+            return Err(ValidationError::HashMismatch);
+        }
+        Ok(())
+    }
+
     pub fn is_valid(&self) -> bool {
-        self.status == CertificateStatus::Active && self.expires_at > Utc::now()
+        self.is_valid_at(Utc::now())
     }
 
     pub fn is_expired(&self) -> bool {
-        self.expires_at < Utc::now()
+        self.is_expired_at(Utc::now())
+    }
+
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.status == CertificateStatus::Active && self.expires_at > now
+    }
+
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at < now
+    }
+
+    /// Negative once the certificate has expired.
+    pub fn time_until_expiry(&self) -> Duration {
+        self.time_until_expiry_at(Utc::now())
+    }
+
+    pub fn time_until_expiry_at(&self, now: DateTime<Utc>) -> Duration {
+        self.expires_at - now
+    }
+
+    pub fn effective_status(&self, soft_expiry: Duration) -> CertificateStatus {
+        self.effective_status_at(Utc::now(), soft_expiry)
+    }
+
+    /// The stored status, except that an active certificate reports `Expired`
+    /// once past `expires_at` and `ExpiringSoon` within `soft_expiry` of it.
+    pub fn effective_status_at(
+        &self,
+        now: DateTime<Utc>,
+        soft_expiry: Duration,
+    ) -> CertificateStatus {
+        if self.status != CertificateStatus::Active {
+            return self.status.clone();
+        }
+        let remaining = self.time_until_expiry_at(now);
+        if remaining <= Duration::zero() {
+            CertificateStatus::Expired
+        } else if remaining <= soft_expiry {
+            CertificateStatus::ExpiringSoon
+        } else {
+            CertificateStatus::Active
+        }
+    }
+
+    /// The issuing CA encoded in a namespaced id (`{ca_id}:CERT-...`), if any.
+    pub fn id_namespace(cert_id: &str) -> Option<&str> {
+        cert_id.split_once(':').map(|(ca_id, _)| ca_id)
+    }
+
+    pub fn compute_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.id);
+        hasher.update(&self.vehicle_id);
+        hasher.update(&self.public_key);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Bytes covered by the CA signature: every field except the mutable
+    /// status and the signature itself. The layout follows `version`, so
+    /// certificates signed under an older version still verify.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let fields = (
+            &self.id,
+            &self.vehicle_id,
+            &self.public_key,
+            self.issued_at,
+            self.expires_at,
+            &self.issuer_ca,
+            &self.certificate_hash,
+        );
+        match self.version {
+            1 => serde_json::to_vec(&fields),
+            version => serde_json::to_vec(&(version, fields)),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Checks the certificate hash and the CA signature using only the CA's public key.
+    pub fn verify(&self, ca_public_key: &[u8]) -> bool {
+        if self.compute_hash() != self.certificate_hash {
+            return false;
+        }
+        self.signature_algorithm
+            .verify(ca_public_key, &self.canonical_bytes(), &self.signature)
+    }
+
+    /// Full relying-party check: issued by `ca_id`, cryptographically valid
+    /// under `ca_public_key`, still active and within its validity period.
+    pub fn validate_against_ca(&self, ca_id: &str, ca_public_key: &[u8]) -> bool {
+        self.issuer_ca == ca_id && self.verify(ca_public_key) && self.is_valid()
     }
 }
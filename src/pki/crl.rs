@@ -0,0 +1,63 @@
+use super::revocation::RevocationReason;
+use crate::crypto::KeyAlgorithm;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One revoked certificate on a CRL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrlEntry {
+    pub cert_id: String,
+    pub revocation_time: DateTime<Utc>,
+    pub reason: RevocationReason,
+}
+
+/// Certificate revocation list from `CertificateAuthority::generate_crl`,
+/// signed by the issuing CA. Relying parties may cache it until `next_update`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Crl {
+    pub issuer: String,
+    pub this_update: DateTime<Utc>,
+    pub next_update: DateTime<Utc>,
+    /// In revocation order.
+    pub entries: Vec<CrlEntry>,
+    /// Issuer's signature over `signing_bytes`.
+    #[serde(default)]
+    pub signature: Vec<u8>,
+    #[serde(default)]
+    pub signature_algorithm: KeyAlgorithm,
+}
+
+impl Crl {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&(
+            "BBVPKI-CRL",
+            &self.issuer,
+            self.this_update,
+            self.next_update,
+            &self.entries,
+        ))
+        .unwrap_or_default()
+    }
+
+    pub fn verify(&self, ca_public_key: &[u8]) -> bool {
+        self.signature_algorithm
+            .verify(ca_public_key, &self.signing_bytes(), &self.signature)
+    }
+
+    /// Whether the list is still within its validity window at `now`.
+    pub fn is_current_at(&self, now: DateTime<Utc>) -> bool {
+        self.this_update <= now && now < self.next_update
+    }
+
+    pub fn contains(&self, cert_id: &str) -> bool {
+        self.entries.iter().any(|entry| entry.cert_id == cert_id)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::crypto::{CONTEXT_CSR, verify_ed25519, with_context};
+use serde::{Deserialize, Serialize};
+
+/// A vehicle's request for a certificate over its own key, from
+/// `OnBoardUnit::create_csr`. The self-signature proves the requester holds
+/// the private half of `public_key`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateSigningRequest {
+    pub vehicle_id: String,
+    pub public_key: Vec<u8>,
+    /// Signature over `signing_bytes` under `CONTEXT_CSR` with `public_key`.
+    pub signature: Vec<u8>,
+}
+
+impl CertificateSigningRequest {
+    pub fn signing_bytes(vehicle_id: &str, public_key: &[u8]) -> Vec<u8> {
+        serde_json::to_vec(&("BBVPKI-CSR", vehicle_id, public_key)).unwrap_or_default()
+    }
+
+    /// Checks the self-signature, i.e. proof of possession of the key.
+    pub fn verify(&self) -> bool {
+        let message = with_context(
+            CONTEXT_CSR,
+            &Self::signing_bytes(&self.vehicle_id, &self.public_key),
+        );
+        verify_ed25519(&self.public_key, &message, &self.signature)
+    }
+}
@@ -0,0 +1,61 @@
+use super::certificate::CertificateStatus;
+use async_trait::async_trait;
+use thiserror::Error;
+
+/// Consulted by the CA before every issuance, e.g. to look a vehicle up in an
+/// external registry. Unlike the rest of issuance this may call out to a
+/// remote service, hence async.
+#[async_trait]
+pub trait PreIssuanceChecker: Send + Sync {
+    async fn check(&self, vehicle_id: &str) -> Result<(), String>;
+}
+
+/// Default checker: every vehicle may enroll.
+pub struct AllowAll;
+
+#[async_trait]
+impl PreIssuanceChecker for AllowAll {
+    async fn check(&self, _vehicle_id: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum IssuanceError {
+    /// The pre-issuance check refused the vehicle.
+    #[error("Issuance rejected: {0}")]
+    Rejected(String),
+    /// The issuance queue is at capacity; retry later.
+    #[error("Issuance queue full ({capacity} requests waiting)")]
+    QueueFull { capacity: usize },
+    /// The public key is not a usable ed25519 key, e.g. all zeros.
+    #[error("Issuance rejected: weak or invalid public key for {0}")]
+    WeakKey(String),
+    /// A certificate request's self-signature does not verify under the
+    /// key it asks to certify.
+    #[error("Issuance rejected: {0} did not prove possession of its key")]
+    InvalidProofOfPossession(String),
+    /// The requested validity period is zero or negative.
+    #[error("Issuance rejected: validity period {0} is not positive")]
+    InvalidValidity(chrono::Duration),
+    /// The HSM could not sign the certificate; nothing was issued.
+    #[error("Issuance failed: could not sign certificate: {0}")]
+    SigningFailed(String),
+    #[error("Certificate {0} not found")]
+    UnknownCertificate(String),
+    /// The id is already issued to a different vehicle or key.
+    #[error("Certificate id {0} is already issued to a different subject")]
+    IdConflict(String),
+    /// The id belongs to a certificate that can no longer be re-issued.
+    #[error("Certificate id {cert_id} is already in use by a certificate in state {status:?}")]
+    IdInUse {
+        cert_id: String,
+        status: CertificateStatus,
+    },
+    /// Only active or expired certificates can be renewed.
+    #[error("Cannot renew certificate {cert_id} in state {status:?}")]
+    NotRenewable {
+        cert_id: String,
+        status: CertificateStatus,
+    },
+}
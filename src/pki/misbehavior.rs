@@ -0,0 +1,45 @@
+use crate::blockchain::BlockchainTransaction;
+use serde::{Deserialize, Serialize};
+
+/// A signed accusation from one vehicle against another. The signature is
+/// made by the reporter's certified key over `signing_bytes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MisbehaviorReport {
+    pub reporter_id: String,
+    pub accused_vehicle_id: String,
+    pub evidence: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl MisbehaviorReport {
+    pub fn new(reporter_id: String, accused_vehicle_id: String, evidence: Vec<u8>) -> Self {
+        Self {
+            reporter_id,
+            accused_vehicle_id,
+            evidence,
+            signature: vec![],
+        }
+    }
+
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = b"BBVPKI-MISBEHAVIOR-REPORT".to_vec();
+        bytes.extend(self.reporter_id.as_bytes());
+        bytes.push(0);
+        bytes.extend(self.accused_vehicle_id.as_bytes());
+        bytes.push(0);
+        bytes.extend(&self.evidence);
+        bytes
+    }
+}
+
+#[derive(Debug)]
+pub enum MisbehaviorOutcome {
+    /// The report was accepted; the accused has not yet reached the threshold.
+    Recorded { distinct_reporters: usize },
+    /// The threshold was reached and the accused's certificates were revoked.
+    /// The transactions must be committed to the chain by the caller.
+    Revoked {
+        revoked_cert_ids: Vec<String>,
+        transactions: Vec<BlockchainTransaction>,
+    },
+}
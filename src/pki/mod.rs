@@ -1,5 +1,21 @@
+pub mod builder;
 pub mod ca;
 pub mod certificate;
+pub mod crl;
+pub mod csr;
+pub mod issuance;
+pub mod misbehavior;
+pub mod queue;
+pub mod revocation;
+pub mod revocation_log;
 
-pub use ca::CertificateAuthority;
-pub use certificate::{Certificate, CertificateStatus};
+pub use builder::CertificateBuilder;
+pub use ca::{CaStatistics, CertificateAuthority, CertificateStatusResponse};
+pub use certificate::{CURRENT_CERT_VERSION, Certificate, CertificateStatus, ValidationError};
+pub use crl::{Crl, CrlEntry};
+pub use csr::CertificateSigningRequest;
+pub use issuance::{AllowAll, IssuanceError, PreIssuanceChecker};
+pub use misbehavior::{MisbehaviorOutcome, MisbehaviorReport};
+pub use queue::{IssuancePriority, IssuanceQueue};
+pub use revocation::{RevocationError, RevocationReason, RevocationRequest};
+pub use revocation_log::{RevocationLogEntry, verify_revocation_log};
@@ -0,0 +1,142 @@
+use super::ca::CertificateAuthority;
+use super::certificate::Certificate;
+use super::issuance::IssuanceError;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use tokio::sync::{Notify, oneshot};
+use tokio::task::JoinHandle;
+
+/// Queue priority of an issuance request; higher variants are served first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssuancePriority {
+    Routine,
+    /// E.g. re-enrollment of a vehicle whose certificate was just revoked.
+    Emergency,
+}
+
+struct QueuedRequest {
+    priority: IssuancePriority,
+    /// Arrival order; keeps requests of equal priority FIFO.
+    sequence: u64,
+    vehicle_id: String,
+    public_key: Vec<u8>,
+    reply: oneshot::Sender<Result<Certificate, IssuanceError>>,
+}
+
+impl PartialEq for QueuedRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl Eq for QueuedRequest {}
+
+impl PartialOrd for QueuedRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedRequest {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+#[derive(Default)]
+struct Pending {
+    heap: BinaryHeap<QueuedRequest>,
+    next_sequence: u64,
+}
+
+/// Bounded, prioritized front end to a CA, drained by a fixed number of
+/// worker tasks. Workers stop when the queue is dropped.
+pub struct IssuanceQueue {
+    // std mutex: never held across an await
+    pending: Arc<StdMutex<Pending>>,
+    notify: Arc<Notify>,
+    capacity: usize,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl IssuanceQueue {
+    pub fn new(ca: Arc<CertificateAuthority>, capacity: usize, workers: usize) -> Self {
+        let pending = Arc::new(StdMutex::new(Pending::default()));
+        let notify = Arc::new(Notify::new());
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let (ca, pending, notify) = (ca.clone(), pending.clone(), notify.clone());
+                tokio::spawn(async move {
+                    loop {
+                        let next = pending.lock().unwrap().heap.pop();
+                        let Some(request) = next else {
+                            notify.notified().await;
+                            continue;
+                        };
+                        let result = ca
+                            .issue_certificate(request.vehicle_id, request.public_key)
+                            .await;
+                        let _ = request.reply.send(result);
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            pending,
+            notify,
+            capacity: capacity.max(1),
+            workers,
+        }
+    }
+
+    /// Queues a request and waits for its certificate. Fails immediately with
+    /// `QueueFull` when `capacity` requests are already waiting.
+    pub async fn submit(
+        &self,
+        vehicle_id: String,
+        public_key: Vec<u8>,
+        priority: IssuancePriority,
+    ) -> Result<Certificate, IssuanceError> {
+        let (reply, response) = oneshot::channel();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if pending.heap.len() >= self.capacity {
+                return Err(IssuanceError::QueueFull {
+                    capacity: self.capacity,
+                });
+            }
+            let sequence = pending.next_sequence;
+            pending.next_sequence += 1;
+            pending.heap.push(QueuedRequest {
+                priority,
+                sequence,
+                vehicle_id,
+                public_key,
+                reply,
+            });
+        }
+        self.notify.notify_one();
+
+        response
+            .await
+            .map_err(|_| IssuanceError::Rejected("issuance queue shut down".to_string()))?
+    }
+
+    /// Requests waiting for a worker.
+    pub fn depth(&self) -> usize {
+        self.pending.lock().unwrap().heap.len()
+    }
+}
+
+impl Drop for IssuanceQueue {
+    fn drop(&mut self) {
+        for worker in &self.workers {
+            worker.abort();
+        }
+    }
+}
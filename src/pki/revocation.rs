@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Why a certificate was revoked, after the RFC 5280 reason codes plus the
+/// V2X-specific `Misbehavior`. Free-text reasons are kept as `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RevocationReason {
+    Unspecified,
+    KeyCompromise,
+    CaCompromise,
+    Superseded,
+    CessationOfOperation,
+    /// Revoked after enough misbehavior reports.
+    Misbehavior,
+    Other(String),
+}
+
+impl fmt::Display for RevocationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unspecified => f.write_str("unspecified"),
+            Self::KeyCompromise => f.write_str("keyCompromise"),
+            Self::CaCompromise => f.write_str("cACompromise"),
+            Self::Superseded => f.write_str("superseded"),
+            Self::CessationOfOperation => f.write_str("cessationOfOperation"),
+            Self::Misbehavior => f.write_str("misbehavior"),
+            Self::Other(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl From<&str> for RevocationReason {
+    /// Parses the names `Display` produces; anything else becomes `Other`.
+    fn from(reason: &str) -> Self {
+        match reason {
+            "unspecified" => Self::Unspecified,
+            "keyCompromise" => Self::KeyCompromise,
+            "cACompromise" => Self::CaCompromise,
+            "superseded" => Self::Superseded,
+            "cessationOfOperation" => Self::CessationOfOperation,
+            "misbehavior" => Self::Misbehavior,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+/// A revocation request from a fleet operator, signed by the operator's key
+/// over `signing_bytes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationRequest {
+    pub operator_id: String,
+    pub cert_id: String,
+    pub reason: String,
+    pub requested_at: DateTime<Utc>,
+    pub signature: Vec<u8>,
+}
+
+impl RevocationRequest {
+    pub fn new(operator_id: String, cert_id: String, reason: String) -> Self {
+        Self {
+            operator_id,
+            cert_id,
+            reason,
+            requested_at: Utc::now(),
+            signature: vec![],
+        }
+    }
+
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&(
+            "BBVPKI-REVOCATION-REQUEST",
+            &self.operator_id,
+            &self.cert_id,
+            &self.reason,
+            self.requested_at,
+        ))
+        .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RevocationError {
+    /// The operator is not registered or the signature does not verify.
+    #[error("Unauthorized revocation request from {0}")]
+    Unauthorized(String),
+    #[error("Certificate not found")]
+    NotFound,
+    /// The audit record could not be signed or written, so the revocation was
+    /// not applied. Transient; safe to retry.
+    #[error("Revocation could not be recorded: {0}")]
+    Unrecorded(String),
+}
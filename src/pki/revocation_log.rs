@@ -0,0 +1,142 @@
+use crate::crypto::KeyAlgorithm;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Write};
+
+/// One line of the append-only revocation log. Entries are chained by
+/// `prev_hash` and signed by the CA, so edited, dropped or reordered lines
+/// are detected by `verify_revocation_log`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationLogEntry {
+    pub sequence: u64,
+    pub cert_id: String,
+    pub reason: String,
+    pub revoked_at: DateTime<Utc>,
+    /// Hash of the previous entry's line; empty for the first entry.
+    pub prev_hash: String,
+    pub signature: Vec<u8>,
+    #[serde(default)]
+    pub signature_algorithm: KeyAlgorithm,
+}
+
+impl RevocationLogEntry {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&(
+            "BBVPKI-REVOCATION-LOG",
+            self.sequence,
+            &self.cert_id,
+            &self.reason,
+            self.revoked_at,
+            &self.prev_hash,
+        ))
+        .unwrap_or_default()
+    }
+}
+
+fn line_hash(line: &str) -> String {
+    format!("{:x}", Sha256::digest(line.as_bytes()))
+}
+
+/// Append handle kept by the CA; remembers where the chain of entries left off.
+pub(crate) struct RevocationLog {
+    path: String,
+    next_sequence: u64,
+    last_hash: String,
+}
+
+impl RevocationLog {
+    /// Opens (or starts) the log at `path`, continuing after its last entry.
+    pub(crate) fn open(path: &str) -> Result<Self, String> {
+        let mut log = Self {
+            path: path.to_string(),
+            next_sequence: 0,
+            last_hash: String::new(),
+        };
+        let file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(log),
+            Err(e) => return Err(format!("Failed to open revocation log: {}", e)),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            let entry: RevocationLogEntry =
+                serde_json::from_str(&line).map_err(|e| e.to_string())?;
+            log.next_sequence = entry.sequence + 1;
+            log.last_hash = line_hash(&line);
+        }
+        Ok(log)
+    }
+
+    /// Entry to be signed for the next revocation.
+    pub(crate) fn next_entry(
+        &self,
+        cert_id: &str,
+        reason: &str,
+        revoked_at: DateTime<Utc>,
+    ) -> RevocationLogEntry {
+        RevocationLogEntry {
+            sequence: self.next_sequence,
+            cert_id: cert_id.to_string(),
+            reason: reason.to_string(),
+            revoked_at,
+            prev_hash: self.last_hash.clone(),
+            signature: vec![],
+            signature_algorithm: KeyAlgorithm::default(),
+        }
+    }
+
+    pub(crate) fn append(&mut self, entry: &RevocationLogEntry) -> Result<(), String> {
+        let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open revocation log: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write revocation log: {}", e))?;
+        file.sync_data().map_err(|e| e.to_string())?;
+
+        self.next_sequence = entry.sequence + 1;
+        self.last_hash = line_hash(&line);
+        Ok(())
+    }
+}
+
+/// Checks every entry's signature under `ca_public_key`, that sequence
+/// numbers run 0, 1, 2, ... and that each entry links to the one before.
+/// Returns the number of entries.
+pub fn verify_revocation_log(path: &str, ca_public_key: &[u8]) -> Result<usize, String> {
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open revocation log: {}", e))?;
+    let mut prev_hash = String::new();
+    let mut count = 0;
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let entry: RevocationLogEntry = serde_json::from_str(&line)
+            .map_err(|e| format!("Line {}: malformed entry: {}", line_no + 1, e))?;
+        if entry.sequence != count as u64 {
+            return Err(format!(
+                "Line {}: expected sequence {}, found {}",
+                line_no + 1,
+                count,
+                entry.sequence
+            ));
+        }
+        if entry.prev_hash != prev_hash {
+            return Err(format!(
+                "Line {}: broken link to previous entry",
+                line_no + 1
+            ));
+        }
+        if !entry.signature_algorithm.verify(
+            ca_public_key,
+            &entry.signing_bytes(),
+            &entry.signature,
+        ) {
+            return Err(format!("Line {}: invalid signature", line_no + 1));
+        }
+        prev_hash = line_hash(&line);
+        count += 1;
+    }
+    Ok(count)
+}
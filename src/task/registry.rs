@@ -0,0 +1,89 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use tokio::sync::watch;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio::time::{Duration, Instant};
+
+/// Background tasks started by the `spawn_*` methods of components built
+/// `with_task_registry`. `shutdown` signals every task to stop at a clean
+/// point, waits for them, and aborts any that overrun the timeout.
+pub struct TaskRegistry {
+    cancel: watch::Sender<bool>,
+    tasks: StdMutex<Vec<(String, AbortHandle)>>,
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self {
+            cancel: watch::Sender::new(false),
+            tasks: StdMutex::new(Vec::new()),
+        }
+    }
+
+    pub fn register<T>(&self, name: impl Into<String>, handle: &JoinHandle<T>) {
+        self.tasks
+            .lock()
+            .unwrap()
+            .push((name.into(), handle.abort_handle()));
+    }
+
+    /// Resolves once shutdown begins, or if the registry is dropped.
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut cancel = self.cancel.subscribe();
+        async move {
+            let _ = cancel.wait_for(|cancelled| *cancelled).await;
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancel.borrow()
+    }
+
+    /// Registered tasks that have not finished yet.
+    pub fn active_task_count(&self) -> usize {
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|(_, handle)| !handle.is_finished());
+        tasks.len()
+    }
+
+    /// Signals cancellation and waits up to `timeout` for every registered
+    /// task to finish, then aborts the rest. Returns the names of the tasks
+    /// that had to be aborted.
+    pub async fn shutdown(&self, timeout: Duration) -> Vec<String> {
+        self.cancel.send_replace(true);
+        let deadline = Instant::now() + timeout;
+        while self.active_task_count() > 0 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let stragglers = std::mem::take(&mut *self.tasks.lock().unwrap());
+        stragglers
+            .into_iter()
+            .filter(|(_, handle)| !handle.is_finished())
+            .map(|(name, handle)| {
+                handle.abort();
+                name
+            })
+            .collect()
+    }
+}
+
+/// `registry.cancelled()`, or a future that never resolves without a registry.
+pub(crate) fn cancellation(
+    registry: Option<&Arc<TaskRegistry>>,
+) -> impl Future<Output = ()> + Send + 'static {
+    let cancelled = registry.map(|r| r.cancelled());
+    async move {
+        match cancelled {
+            Some(cancelled) => cancelled.await,
+            None => std::future::pending().await,
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod clock;
+
+pub use clock::{Clock, MockClock, SystemClock};
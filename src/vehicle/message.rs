@@ -0,0 +1,106 @@
+use crate::edge::EdgeError;
+use crate::pki::{Certificate, CertificateStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// A V2V message bundled with everything a peer needs to authenticate it
+/// without a lookup: the sender's signature and certificate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedMessage {
+    pub message: Vec<u8>,
+    /// Per-sender counter, increasing by one with every message it signs.
+    #[serde(default)]
+    pub sequence: u64,
+    pub sent_at: DateTime<Utc>,
+    /// Made by the certified key over `signing_bytes`.
+    pub signature: Vec<u8>,
+    pub certificate: Certificate,
+}
+
+impl SignedMessage {
+    pub fn sender_id(&self) -> &str {
+        &self.certificate.vehicle_id
+    }
+
+    /// The bytes the sender signs. Sequence and send time are covered so a
+    /// replayed message cannot be re-stamped to look fresh.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        Self::signing_bytes_for(&self.message, self.sequence, self.sent_at)
+    }
+
+    pub(crate) fn signing_bytes_for(
+        message: &[u8],
+        sequence: u64,
+        sent_at: DateTime<Utc>,
+    ) -> Vec<u8> {
+        let mut bytes = b"BBVPKI-V2V-MESSAGE".to_vec();
+        bytes.extend(sequence.to_be_bytes());
+        bytes.extend(sent_at.timestamp_micros().to_be_bytes());
+        bytes.extend(message);
+        bytes
+    }
+}
+
+/// How far a received message may stray from the receiver's expectations
+/// before it is treated as a replay. Tight windows suit dense urban traffic;
+/// long-haul deployments with patchy connectivity want looser ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayPolicy {
+    /// Largest allowed difference between `sent_at` and the receiver's clock,
+    /// in either direction.
+    pub time_window: Duration,
+    /// Largest allowed jump past the last sequence number seen from a sender.
+    pub max_seq_gap: u64,
+}
+
+impl Default for ReplayPolicy {
+    fn default() -> Self {
+        Self {
+            time_window: Duration::from_secs(5),
+            max_seq_gap: 100,
+        }
+    }
+}
+
+/// Why `OnBoardUnit::receive_message` refused a message.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum MessageRejection {
+    #[error("sender certificate is not trusted")]
+    UntrustedCertificate,
+    #[error("signature does not match the sender certificate")]
+    BadSignature,
+    #[error("sent {skew_ms} ms away from local time, outside the {window_ms} ms window")]
+    OutsideTimeWindow { skew_ms: i64, window_ms: u128 },
+    #[error("sequence {sequence} already seen (last accepted {last_seen})")]
+    Replayed { sequence: u64, last_seen: u64 },
+    #[error("sequence {sequence} jumps {gap} past the last accepted, over the limit of {max_gap}")]
+    SequenceGap {
+        sequence: u64,
+        gap: u64,
+        max_gap: u64,
+    },
+}
+
+/// Why `BBVPKIClientSDK::verify_peer_message` refused a peer's message.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PeerMessageError {
+    #[error("sender certificate {0} has expired")]
+    Expired(String),
+    #[error("sender certificate {0} is not signed by a trusted CA")]
+    UntrustedCertificate(String),
+    #[error("sender certificate {0} has been revoked")]
+    Revoked(String),
+    /// Suspended, deprecated or otherwise not usable.
+    #[error("sender certificate {cert_id} is {status:?}")]
+    Inactive {
+        cert_id: String,
+        status: CertificateStatus,
+    },
+    /// The edge node could not vouch for the certificate, e.g. it is unknown.
+    #[error("edge node could not authenticate the sender certificate: {0}")]
+    Edge(EdgeError),
+    #[error("signature does not match the sender certificate")]
+    BadSignature,
+}
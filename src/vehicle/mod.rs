@@ -1,5 +1,7 @@
+pub mod message;
 pub mod obu;
 pub mod sdk;
 
+pub use message::{MessageRejection, PeerMessageError, ReplayPolicy, SignedMessage};
 pub use obu::OnBoardUnit;
 pub use sdk::BBVPKIClientSDK;
@@ -1,8 +1,19 @@
-use crate::crypto::TrustedPlatformModule;
-use crate::pki::Certificate;
+use super::message::{MessageRejection, ReplayPolicy, SignedMessage};
+use crate::crypto::{
+    CONTEXT_CSR, CONTEXT_V2I_HANDSHAKE, CONTEXT_V2V, TrustedPlatformModule, with_context,
+};
+use crate::edge::handshake::{
+    HandshakeAccept, HandshakeChallenge, HandshakeError, HandshakeResponse, handshake_bytes,
+    new_nonce,
+};
+use crate::pki::{Certificate, CertificateSigningRequest};
+use chrono::{DateTime, Utc};
 use ed25519_dalek::Verifier;
 use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 pub struct OnBoardUnit {
     pub vehicle_id: String,
@@ -10,6 +21,29 @@ pub struct OnBoardUnit {
     key_id: String,
     certificate: Option<Certificate>,
     pub public_key: Vec<u8>,
+    /// Trust anchors for peer certificates: every key each CA is known by.
+    trusted_cas: HashMap<String, Vec<Vec<u8>>>,
+    /// Sequence number for the next message this OBU signs.
+    next_sequence: AtomicU64,
+    replay_policy: ReplayPolicy,
+    /// Highest sequence accepted from each sender.
+    last_seen_sequence: HashMap<String, u64>,
+    /// Pseudonym public key to the TPM key id holding its private half.
+    pseudonym_keys: HashMap<Vec<u8>, String>,
+}
+
+/// On-disk OBU state. The private key is only ever stored sealed by the TPM.
+#[derive(Serialize, Deserialize)]
+struct PersistedObu {
+    vehicle_id: String,
+    key_id: String,
+    sealed_key: Vec<u8>,
+    certificate: Option<Certificate>,
+    #[serde(default)]
+    trusted_cas: HashMap<String, Vec<Vec<u8>>>,
+    /// Kept so peers do not take messages after a restart for replays.
+    #[serde(default)]
+    next_sequence: u64,
 }
 
 impl OnBoardUnit {
@@ -24,31 +58,325 @@ impl OnBoardUnit {
             key_id,
             certificate: None,
             public_key,
+            trusted_cas: HashMap::new(),
+            next_sequence: AtomicU64::new(0),
+            replay_policy: ReplayPolicy::default(),
+            last_seen_sequence: HashMap::new(),
+            pseudonym_keys: HashMap::new(),
+        }
+    }
+
+    /// Saves the OBU identity to `path`. The TPM's own NV storage is written
+    /// alongside it at `<path>.tpm`.
+    pub async fn save(&self, path: &str) -> Result<(), String> {
+        let state = PersistedObu {
+            vehicle_id: self.vehicle_id.clone(),
+            key_id: self.key_id.clone(),
+            sealed_key: self.tpm.seal_key(&self.key_id).await?,
+            certificate: self.certificate.clone(),
+            trusted_cas: self.trusted_cas.clone(),
+            next_sequence: self.next_sequence.load(Ordering::Relaxed),
+        };
+        let bytes = serde_json::to_vec_pretty(&state).map_err(|e| e.to_string())?;
+
+        self.tpm.persist(&Self::tpm_nv_path(path))?;
+        std::fs::write(path, bytes).map_err(|e| format!("Failed to save OBU state: {}", e))
+    }
+
+    /// Restores an OBU previously written by `save`, keeping its key and certificate.
+    pub async fn load(vehicle_id: String, path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read OBU state: {}", e))?;
+        let state: PersistedObu = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
+        if state.vehicle_id != vehicle_id {
+            return Err(format!(
+                "OBU state belongs to {}, not {}",
+                state.vehicle_id, vehicle_id
+            ));
         }
+
+        let tpm = Arc::new(TrustedPlatformModule::open(&Self::tpm_nv_path(path))?);
+        let public_key = tpm.unseal_key(&state.key_id, &state.sealed_key).await?;
+
+        Ok(Self {
+            vehicle_id,
+            tpm,
+            key_id: state.key_id,
+            certificate: state.certificate,
+            public_key,
+            trusted_cas: state.trusted_cas,
+            next_sequence: AtomicU64::new(state.next_sequence),
+            replay_policy: ReplayPolicy::default(),
+            last_seen_sequence: HashMap::new(),
+            pseudonym_keys: HashMap::new(),
+        })
+    }
+
+    fn tpm_nv_path(path: &str) -> String {
+        format!("{}.tpm", path)
     }
 
     pub async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, String> {
-        self.tpm.sign_with_tpm(&self.key_id, message).await
+        self.tpm
+            .sign_with_tpm(&self.key_id, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Signs `message` under a protocol `context` (see `crypto::with_context`),
+    /// so the signature cannot be passed off as one made for another protocol.
+    pub async fn sign_with_context(
+        &self,
+        context: &str,
+        message: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        self.sign_message(&with_context(context, message)).await
+    }
+
+    /// Checks a signature made by `sign_with_context` under the same `context`.
+    pub fn verify_with_context(
+        &self,
+        context: &str,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> bool {
+        self.verify_message(&with_context(context, message), signature, public_key)
+    }
+
+    /// Requests a certificate for this OBU's TPM key, self-signed under
+    /// `CONTEXT_CSR` to prove the TPM holds the private key.
+    pub async fn create_csr(&self) -> Result<CertificateSigningRequest, String> {
+        let message = CertificateSigningRequest::signing_bytes(&self.vehicle_id, &self.public_key);
+        let signature = self.sign_with_context(CONTEXT_CSR, &message).await?;
+        Ok(CertificateSigningRequest {
+            vehicle_id: self.vehicle_id.clone(),
+            public_key: self.public_key.clone(),
+            signature,
+        })
     }
 
     pub fn verify_message(&self, message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
-        if public_key.len() == 32 {
-            if let Ok(pk) = VerifyingKey::from_bytes(<&[u8; 32]>::try_from(public_key).unwrap()) {
-                if let Ok(sig_array) = <&[u8; 64]>::try_from(signature) {
-                    let sig = Signature::from_bytes(sig_array);
-                    return pk.verify(message, &sig).is_ok();
-                }
-            }
+        if let Ok(pk_array) = <&[u8; 32]>::try_from(public_key)
+            && let Ok(pk) = VerifyingKey::from_bytes(pk_array)
+            && let Ok(sig_array) = <&[u8; 64]>::try_from(signature)
+        {
+            let sig = Signature::from_bytes(sig_array);
+            return pk.verify(message, &sig).is_ok();
         }
         false
     }
 
+    /// Adds `public_key` as a trust anchor for certificates issued by `ca_id`.
+    pub fn trust_ca(&mut self, ca_id: &str, public_key: Vec<u8>) {
+        let keys = self.trusted_cas.entry(ca_id.to_string()).or_default();
+        if !keys.contains(&public_key) {
+            keys.push(public_key);
+        }
+    }
+
+    /// Accepts a peer's certificate only if a trusted key of its issuer signed
+    /// it and it is active and unexpired. Check this before trusting any
+    /// signature made under the certificate's public key.
+    pub fn verify_peer_certificate(&self, cert: &Certificate) -> bool {
+        cert.is_valid() && self.is_issued_by_trusted_ca(cert)
+    }
+
+    /// Whether a trusted key of the certificate's issuer signed it, whatever
+    /// its status or expiry.
+    pub fn is_issued_by_trusted_ca(&self, cert: &Certificate) -> bool {
+        self.trusted_cas
+            .get(&cert.issuer_ca)
+            .is_some_and(|keys| keys.iter().any(|key| cert.verify(key)))
+    }
+
+    /// Signs `message` under this OBU's certificate, stamping it with the
+    /// next sequence number and the current time.
+    pub async fn sign_envelope(&self, message: &[u8]) -> Result<SignedMessage, String> {
+        let certificate = self
+            .certificate
+            .clone()
+            .ok_or("No certificate installed on this OBU")?;
+        let sequence = self.next_sequence.fetch_add(1, Ordering::Relaxed);
+        let sent_at = Utc::now();
+        let signature = self
+            .sign_with_context(
+                CONTEXT_V2V,
+                &SignedMessage::signing_bytes_for(message, sequence, sent_at),
+            )
+            .await?;
+        Ok(SignedMessage {
+            message: message.to_vec(),
+            sequence,
+            sent_at,
+            signature,
+            certificate,
+        })
+    }
+
+    /// Accepts a peer's message only if its certificate checks out against
+    /// a trusted CA and the signature was made with the certified key.
+    /// Stateless: replays pass. Use `receive_message` for delivered traffic.
+    pub fn verify_signed_message(&self, envelope: &SignedMessage) -> bool {
+        self.verify_peer_certificate(&envelope.certificate)
+            && self.verify_with_context(
+                CONTEXT_V2V,
+                &envelope.signing_bytes(),
+                &envelope.signature,
+                &envelope.certificate.public_key,
+            )
+    }
+
+    /// Answers an RSU's handshake challenge once its certificate checks out
+    /// against a trusted CA.
+    pub async fn respond_to_handshake(
+        &self,
+        challenge: &HandshakeChallenge,
+    ) -> Result<HandshakeResponse, HandshakeError> {
+        if !self.verify_peer_certificate(&challenge.certificate) {
+            return Err(HandshakeError::UntrustedCertificate(
+                challenge.certificate.vehicle_id.clone(),
+            ));
+        }
+        let certificate = self
+            .certificate
+            .clone()
+            .ok_or_else(|| HandshakeError::NoIdentity(self.vehicle_id.clone()))?;
+        let challenge_signature = self
+            .sign_with_context(
+                CONTEXT_V2I_HANDSHAKE,
+                &handshake_bytes("vehicle", &certificate, &challenge.nonce),
+            )
+            .await
+            .map_err(|_| HandshakeError::NoIdentity(self.vehicle_id.clone()))?;
+        Ok(HandshakeResponse {
+            certificate,
+            challenge_nonce: challenge.nonce.clone(),
+            challenge_signature,
+            client_nonce: new_nonce(),
+        })
+    }
+
+    /// Confirms the RSU signed our nonce with the key in the certificate it
+    /// presented, completing mutual authentication.
+    pub fn verify_handshake_accept(
+        &self,
+        challenge: &HandshakeChallenge,
+        response: &HandshakeResponse,
+        accept: &HandshakeAccept,
+    ) -> Result<(), HandshakeError> {
+        let node_cert = &challenge.certificate;
+        let signed = handshake_bytes("node", node_cert, &response.client_nonce);
+        if !self.verify_with_context(
+            CONTEXT_V2I_HANDSHAKE,
+            &signed,
+            &accept.client_nonce_signature,
+            &node_cert.public_key,
+        ) {
+            return Err(HandshakeError::BadSignature(node_cert.vehicle_id.clone()));
+        }
+        Ok(())
+    }
+
+    pub fn set_replay_policy(&mut self, policy: ReplayPolicy) {
+        self.replay_policy = policy;
+    }
+
+    pub fn replay_policy(&self) -> ReplayPolicy {
+        self.replay_policy
+    }
+
+    /// Authenticates a delivered message and enforces the replay policy,
+    /// recording its sequence number if accepted.
+    pub fn receive_message(&mut self, envelope: &SignedMessage) -> Result<(), MessageRejection> {
+        self.receive_message_at(envelope, Utc::now())
+    }
+
+    /// `receive_message` as of `now`.
+    pub fn receive_message_at(
+        &mut self,
+        envelope: &SignedMessage,
+        now: DateTime<Utc>,
+    ) -> Result<(), MessageRejection> {
+        if !self.verify_peer_certificate(&envelope.certificate) {
+            return Err(MessageRejection::UntrustedCertificate);
+        }
+        if !self.verify_with_context(
+            CONTEXT_V2V,
+            &envelope.signing_bytes(),
+            &envelope.signature,
+            &envelope.certificate.public_key,
+        ) {
+            return Err(MessageRejection::BadSignature);
+        }
+
+        let skew_ms = (now - envelope.sent_at).num_milliseconds();
+        let window_ms = self.replay_policy.time_window.as_millis();
+        if skew_ms.unsigned_abs() as u128 > window_ms {
+            return Err(MessageRejection::OutsideTimeWindow { skew_ms, window_ms });
+        }
+
+        let sequence = envelope.sequence;
+        if let Some(&last_seen) = self.last_seen_sequence.get(envelope.sender_id()) {
+            if sequence <= last_seen {
+                return Err(MessageRejection::Replayed {
+                    sequence,
+                    last_seen,
+                });
+            }
+            let gap = sequence - last_seen;
+            if gap > self.replay_policy.max_seq_gap {
+                return Err(MessageRejection::SequenceGap {
+                    sequence,
+                    gap,
+                    max_gap: self.replay_policy.max_seq_gap,
+                });
+            }
+        }
+
+        self.last_seen_sequence
+            .insert(envelope.sender_id().to_string(), sequence);
+        Ok(())
+    }
+
     pub fn set_certificate(&mut self, cert: Certificate) {
         self.certificate = Some(cert);
     }
 
+    /// Generates `count` fresh TPM keys to be certified as pseudonyms with
+    /// `CertificateAuthority::issue_pseudonym_batch`, and returns their
+    /// public keys. Pseudonym keys are not written by `save`.
+    pub async fn generate_pseudonym_keys(&mut self, count: usize) -> Vec<Vec<u8>> {
+        let mut public_keys = Vec::with_capacity(count);
+        for _ in 0..count {
+            let key_id = format!("{}-PSN-{}", self.key_id, self.pseudonym_keys.len());
+            let public_key = self.tpm.generate_key_pair(&key_id).await;
+            self.pseudonym_keys.insert(public_key.clone(), key_id);
+            public_keys.push(public_key);
+        }
+        public_keys
+    }
+
+    /// Signs `message` with the pseudonym key certified in `cert`.
+    pub async fn sign_with_pseudonym(
+        &self,
+        cert: &Certificate,
+        message: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let key_id = self
+            .pseudonym_keys
+            .get(&cert.public_key)
+            .ok_or_else(|| format!("No pseudonym key for certificate {}", cert.id))?;
+        self.tpm
+            .sign_with_tpm(key_id, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     pub async fn secure_erase_keys(&self) {
         self.tpm.secure_erase(&self.key_id).await;
+        for key_id in self.pseudonym_keys.values() {
+            self.tpm.secure_erase(key_id).await;
+        }
     }
 
     pub fn get_certificate(&self) -> Option<&Certificate> {
@@ -1,27 +1,132 @@
+use super::message::PeerMessageError;
 use super::obu::OnBoardUnit;
+use crate::crypto::CONTEXT_V2V;
+use crate::edge::{EdgeNode, HandshakeError, mutual_authenticate};
+use crate::network::V2VNetwork;
+use crate::pki::{Certificate, CertificateAuthority, CertificateStatus};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 pub struct BBVPKIClientSDK {
     obu: Arc<Mutex<OnBoardUnit>>,
+    /// CA the vehicle enrolls with during `initialize`.
+    enrollment_ca: Option<Arc<CertificateAuthority>>,
 }
 
 impl BBVPKIClientSDK {
     pub async fn new(vehicle_id: String) -> Self {
         let obu = Arc::new(Mutex::new(OnBoardUnit::new(vehicle_id).await));
-        Self { obu }
+        Self {
+            obu,
+            enrollment_ca: None,
+        }
+    }
+
+    /// Makes `initialize` enroll with `ca`: the OBU sends it a certificate
+    /// request for its TPM key and keeps the certificate it gets back.
+    pub fn with_enrollment_ca(mut self, ca: Arc<CertificateAuthority>) -> Self {
+        self.enrollment_ca = Some(ca);
+        self
     }
 
     pub async fn initialize(&mut self) -> Result<(), String> {
-        println!(
-            "Client SDK initialized for vehicle: {}",
-            self.obu.lock().await.vehicle_id
-        );
+        let mut obu = self.obu.lock().await;
+        if let Some(ca) = &self.enrollment_ca {
+            let csr = obu.create_csr().await?;
+            let cert = ca.process_csr(csr).await.map_err(|e| e.to_string())?;
+            obu.trust_ca(&ca.ca_id, ca.public_key().await);
+            obu.set_certificate(cert);
+        }
+        println!("Client SDK initialized for vehicle: {}", obu.vehicle_id);
         Ok(())
     }
 
+    /// Signs under `CONTEXT_V2V`; check with `OnBoardUnit::verify_with_context`.
     pub async fn sign_v2v_message(&self, message: &[u8]) -> Result<Vec<u8>, String> {
-        self.obu.lock().await.sign_message(message).await
+        self.obu
+            .lock()
+            .await
+            .sign_with_context(CONTEXT_V2V, message)
+            .await
+    }
+
+    /// Accepts a peer's `sign_v2v_message` signature only if `sender_cert`
+    /// is unexpired and issued by a CA this vehicle trusts, `edge` reports it
+    /// still active, and the signature verifies under the certified key.
+    pub async fn verify_peer_message(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        sender_cert: &Certificate,
+        edge: &EdgeNode,
+    ) -> Result<(), PeerMessageError> {
+        let cert_id = &sender_cert.id;
+        if sender_cert.is_expired() {
+            return Err(PeerMessageError::Expired(cert_id.clone()));
+        }
+        let obu = self.obu.lock().await;
+        // the status on the peer's copy may be stale; the edge node decides it
+        if !obu.is_issued_by_trusted_ca(sender_cert) {
+            return Err(PeerMessageError::UntrustedCertificate(cert_id.clone()));
+        }
+
+        let (status, _) = edge
+            .authenticate_certificate(cert_id)
+            .await
+            .map_err(PeerMessageError::Edge)?;
+        match status {
+            CertificateStatus::Active | CertificateStatus::ExpiringSoon => {}
+            CertificateStatus::Revoked => return Err(PeerMessageError::Revoked(cert_id.clone())),
+            CertificateStatus::Expired => return Err(PeerMessageError::Expired(cert_id.clone())),
+            status => {
+                return Err(PeerMessageError::Inactive {
+                    cert_id: cert_id.clone(),
+                    status,
+                });
+            }
+        }
+
+        if !obu.verify_with_context(CONTEXT_V2V, message, signature, &sender_cert.public_key) {
+            return Err(PeerMessageError::BadSignature);
+        }
+        Ok(())
+    }
+
+    pub async fn set_certificate(&self, cert: Certificate) {
+        self.obu.lock().await.set_certificate(cert);
+    }
+
+    /// Shared handle to the underlying OBU, e.g. to register it with a network.
+    pub fn obu(&self) -> Arc<Mutex<OnBoardUnit>> {
+        self.obu.clone()
+    }
+
+    /// Signs `message`, bundles it with this vehicle's certificate and
+    /// broadcasts it. Returns how many peers accepted it.
+    pub async fn broadcast_signed(
+        &self,
+        network: &V2VNetwork,
+        message: &[u8],
+    ) -> Result<usize, String> {
+        let envelope = self.obu.lock().await.sign_envelope(message).await?;
+        Ok(network.broadcast_signed_message(&envelope).await)
+    }
+
+    /// Mutually authenticates with `node`; pass the returned session id to
+    /// `send_to_node`.
+    pub async fn connect_to_node(&self, node: &EdgeNode) -> Result<String, HandshakeError> {
+        mutual_authenticate(&*self.obu.lock().await, node).await
+    }
+
+    /// Asks `node` for the status of `cert_id` over an established session.
+    pub async fn send_to_node(
+        &self,
+        node: &EdgeNode,
+        session_id: &str,
+        cert_id: &str,
+    ) -> Result<CertificateStatus, HandshakeError> {
+        let (status, _) = node.authenticate_in_session(session_id, cert_id).await?;
+        Ok(status)
     }
 
     #[allow(dead_code)]
@@ -1,7 +1,53 @@
+use bb_vpki::blockchain::block::Block;
+use bb_vpki::blockchain::codec;
+use bb_vpki::blockchain::{
+    BLOCK_EVENT_CAPACITY, BlockEvent, BlockLookup, Blockchain, BlockchainTransaction, ChainError,
+    ConsensusEngine, ConsensusProof, DataEncoding, DifficultyPolicy, GenesisConfig,
+    InMemoryContentStore, MineResult, MineTrigger, MiningStats, PbftSimulated, ProofOfWork,
+    RollbackError, TransactionPolicy, TransactionPriority, TransactionRejection, TransactionType,
+};
+use bb_vpki::crypto::{
+    CONTEXT_CSR, CONTEXT_V2V, EntropySource, HardwareSecurityModule, KeyAlgorithm, TpmError,
+    TrustedPlatformModule, verify_attestation,
+};
+use bb_vpki::edge::{
+    AdaptiveCacheConfig, CacheStats, DEFAULT_CACHE_TTL, EdgeError, EdgeNode, HandshakeError,
+};
+use bb_vpki::metrics::{
+    Better, DEFAULT_REGRESSION_THRESHOLD_PERCENT, Histogram, METRICS_SCHEMA_VERSION,
+    PerformanceMetrics,
+};
+use bb_vpki::network::{
+    HyperledgerFabricGateway, LedgerGateway, LinkQuality, MockGateway, V2VNetwork,
+};
+use bb_vpki::pki::{
+    CURRENT_CERT_VERSION, Certificate, CertificateAuthority, CertificateBuilder,
+    CertificateSigningRequest, CertificateStatus, Crl, IssuanceError, IssuancePriority,
+    IssuanceQueue, MisbehaviorOutcome, MisbehaviorReport, PreIssuanceChecker, RevocationError,
+    RevocationReason, RevocationRequest, ValidationError, verify_revocation_log,
+};
+use bb_vpki::task::TaskRegistry;
+use bb_vpki::time::MockClock;
+use bb_vpki::vehicle::{
+    BBVPKIClientSDK, MessageRejection, OnBoardUnit, PeerMessageError, ReplayPolicy,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_public_key() -> Vec<u8> {
+        ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng)
+            .verifying_key()
+            .to_bytes()
+            .to_vec()
+    }
+
     #[tokio::test]
     async fn test_certificate_issuance() {
         // Test implementation
@@ -16,4 +62,3942 @@ mod tests {
     async fn test_authentication() {
         // Test implementation
     }
+
+    #[tokio::test]
+    async fn test_rapid_issuance_ids_are_unique() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+
+        let mut ids = HashSet::new();
+        for _ in 0..500 {
+            let cert = ca
+                .issue_certificate("VEH-1".to_string(), test_public_key())
+                .await
+                .unwrap();
+            assert!(ids.insert(cert.id), "duplicate certificate id issued");
+        }
+
+        assert_eq!(ca.get_total_issued().await, 500);
+    }
+
+    #[tokio::test]
+    async fn test_reissue_with_supplied_id_is_idempotent() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let key = test_public_key();
+
+        let first = ca
+            .issue_certificate_with_id("CERT-FIXED".to_string(), "VEH-1".to_string(), key.clone())
+            .await
+            .unwrap();
+        let again = ca
+            .issue_certificate_with_id("CERT-FIXED".to_string(), "VEH-1".to_string(), key.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(again.id, "CERT-FIXED");
+        assert_eq!(again.certificate_hash, first.certificate_hash);
+        assert_eq!(ca.get_total_issued().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reissue_with_supplied_id_refuses_other_subjects_and_revoked_ids() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let key = test_public_key();
+        ca.issue_certificate_with_id("CERT-FIXED".to_string(), "VEH-1".to_string(), key.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ca.issue_certificate_with_id(
+                "CERT-FIXED".to_string(),
+                "VEH-1".to_string(),
+                test_public_key(),
+            )
+            .await
+            .err(),
+            Some(IssuanceError::IdConflict("CERT-FIXED".to_string()))
+        );
+        assert_eq!(
+            ca.issue_certificate_with_id(
+                "CERT-FIXED".to_string(),
+                "VEH-2".to_string(),
+                key.clone()
+            )
+            .await
+            .err(),
+            Some(IssuanceError::IdConflict("CERT-FIXED".to_string()))
+        );
+
+        ca.revoke_certificate("CERT-FIXED", RevocationReason::Unspecified)
+            .await
+            .unwrap();
+        assert_eq!(
+            ca.issue_certificate_with_id("CERT-FIXED".to_string(), "VEH-1".to_string(), key)
+                .await
+                .err(),
+            Some(IssuanceError::IdInUse {
+                cert_id: "CERT-FIXED".to_string(),
+                status: CertificateStatus::Revoked,
+            })
+        );
+        assert_eq!(
+            ca.get_certificate("CERT-FIXED").await.unwrap().status,
+            CertificateStatus::Revoked
+        );
+    }
+
+    #[tokio::test]
+    async fn test_background_miner_mines_on_pool_size() {
+        let blockchain = Arc::new(Blockchain::new(1));
+        let miner = blockchain.clone().spawn_miner(MineTrigger::PoolSize(5));
+
+        for i in 0..5 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("TX-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+        }
+
+        for _ in 0..100 {
+            if blockchain.get_chain_length().await == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(blockchain.get_chain_length().await, 2);
+        assert_eq!(blockchain.get_pending_count().await, 0);
+
+        miner.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_revocation_reaches_ca_list_and_edge_cache() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let blockchain = Arc::new(Blockchain::new(1));
+        let node = EdgeNode::new("RSU-TEST".to_string(), 16, blockchain.clone());
+
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                cert.id.clone(),
+                TransactionType::CertificateRevocation,
+                vec![],
+            ))
+            .await;
+        assert_eq!(blockchain.pending_snapshot().await.len(), 1);
+
+        ca.revoke_certificate(&cert.id, RevocationReason::Unspecified)
+            .await
+            .unwrap();
+        node.propagate_revocation(&cert.id).await;
+
+        assert_eq!(ca.revocation_snapshot().await, vec![cert.id.clone()]);
+        assert_eq!(
+            node.cache_contents().await,
+            vec![(cert.id, CertificateStatus::Revoked)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_obu_identity_survives_restart() {
+        let path = std::env::temp_dir().join(format!("obu-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let obu = OnBoardUnit::new("VEH-PERSIST".to_string()).await;
+        obu.save(path).await.unwrap();
+
+        let restored = OnBoardUnit::load("VEH-PERSIST".to_string(), path)
+            .await
+            .unwrap();
+        assert_eq!(restored.public_key, obu.public_key);
+
+        let signature = restored.sign_message(b"hello").await.unwrap();
+        assert!(obu.verify_message(b"hello", &signature, &obu.public_key));
+
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file(format!("{}.tpm", path)).ok();
+    }
+
+    #[tokio::test]
+    async fn test_issued_certificate_carries_signature() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert_eq!(cert.signature.len(), 64);
+
+        let tx_data = serde_json::to_vec(&cert).unwrap();
+        let decoded: Certificate = serde_json::from_slice(&tx_data).unwrap();
+        assert_eq!(decoded.signature, cert.signature);
+        assert_eq!(
+            ca.get_certificate(&cert.id).await.unwrap().signature,
+            cert.signature
+        );
+    }
+
+    #[tokio::test]
+    async fn test_renewal_chain_follows_supersession_links() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let blockchain = Blockchain::new(1);
+
+        let first = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let second = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let third = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+
+        blockchain
+            .add_transaction(BlockchainTransaction::renewal(&second, &first.id))
+            .await;
+        blockchain.mine_pending_transactions().await;
+        blockchain
+            .add_transaction(BlockchainTransaction::renewal(&third, &second.id))
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        let expected = vec![first.id.clone(), second.id.clone(), third.id.clone()];
+        assert_eq!(blockchain.renewal_chain(&second.id).await, expected);
+        assert_eq!(blockchain.renewal_chain(&first.id).await, expected);
+        assert_eq!(
+            blockchain.renewal_chain("CERT-UNKNOWN").await,
+            vec!["CERT-UNKNOWN".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edge_node_learns_rotated_ca_key_from_chain() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let blockchain = Arc::new(Blockchain::new(1));
+        let node = EdgeNode::new("RSU-TEST".to_string(), 16, blockchain.clone());
+
+        let before = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let record = ca.rotate_key().await.unwrap();
+        assert!(record.verify());
+        let after = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+
+        node.trust_ca("CA-TEST", record.old_public_key.clone())
+            .await;
+        assert!(!node.verify_certificate_signature(&after).await);
+
+        blockchain
+            .add_transaction(BlockchainTransaction::key_rotation(&record))
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        assert_eq!(node.sync_ca_keyring().await, 1);
+        assert!(node.verify_certificate_signature(&before).await);
+        assert!(node.verify_certificate_signature(&after).await);
+    }
+
+    #[tokio::test]
+    async fn test_certificate_roundtrips_through_every_encoding() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+
+        for encoding in [
+            DataEncoding::Json,
+            DataEncoding::Bincode,
+            DataEncoding::MsgPack,
+        ] {
+            let tx = BlockchainTransaction::for_certificate(
+                TransactionType::CertificateIssuance,
+                &cert,
+                encoding,
+            );
+            let decoded = tx.decode_certificate().unwrap();
+            assert_eq!(decoded.id, cert.id);
+            assert_eq!(decoded.signature, cert.signature);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_misbehavior_reports_revoke_at_threshold() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm)
+            .await
+            .with_misbehavior_threshold(2);
+
+        let accused = ca
+            .issue_certificate("VEH-BAD".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let mut reporters = Vec::new();
+        for i in 0..2 {
+            let obu = OnBoardUnit::new(format!("VEH-R{}", i)).await;
+            ca.issue_certificate(obu.vehicle_id.clone(), obu.public_key.clone())
+                .await
+                .unwrap();
+            reporters.push(obu);
+        }
+
+        let report_from = |obu: &OnBoardUnit| {
+            MisbehaviorReport::new(
+                obu.vehicle_id.clone(),
+                "VEH-BAD".to_string(),
+                b"flood".to_vec(),
+            )
+        };
+
+        let mut forged = report_from(&reporters[0]);
+        forged.signature = vec![0u8; 64];
+        assert!(ca.submit_misbehavior_report(forged).await.is_err());
+
+        let mut signed = Vec::new();
+        for obu in &reporters {
+            let mut report = report_from(obu);
+            report.signature = obu.sign_message(&report.signing_bytes()).await.unwrap();
+            signed.push(report);
+        }
+
+        // the same reporter twice must not count as two
+        for _ in 0..2 {
+            match ca
+                .submit_misbehavior_report(signed[0].clone())
+                .await
+                .unwrap()
+            {
+                MisbehaviorOutcome::Recorded { distinct_reporters } => {
+                    assert_eq!(distinct_reporters, 1)
+                }
+                other => panic!("unexpected outcome {:?}", other),
+            }
+        }
+
+        match ca
+            .submit_misbehavior_report(signed[1].clone())
+            .await
+            .unwrap()
+        {
+            MisbehaviorOutcome::Revoked {
+                revoked_cert_ids,
+                transactions,
+            } => {
+                assert_eq!(revoked_cert_ids, vec![accused.id.clone()]);
+                assert_eq!(transactions.len(), 1);
+            }
+            other => panic!("unexpected outcome {:?}", other),
+        }
+
+        assert_eq!(
+            ca.get_certificate(&accused.id).await.unwrap().status,
+            CertificateStatus::Revoked
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revocation_requires_registered_operator() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+
+        let operator = OnBoardUnit::new("FLEET-OPS".to_string()).await;
+        let mut request = RevocationRequest::new(
+            "FLEET-OPS".to_string(),
+            cert.id.clone(),
+            "stolen".to_string(),
+        );
+        request.signature = operator
+            .sign_message(&request.signing_bytes())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ca.revoke_authorized(&request).await,
+            Err(RevocationError::Unauthorized("FLEET-OPS".to_string()))
+        );
+
+        ca.register_operator("FLEET-OPS".to_string(), operator.public_key.clone())
+            .await;
+        let mut tampered = request.clone();
+        tampered.reason = "other".to_string();
+        assert!(ca.revoke_authorized(&tampered).await.is_err());
+
+        assert!(ca.revoke_authorized(&request).await.is_ok());
+        assert_eq!(
+            ca.get_certificate(&cert.id).await.unwrap().status,
+            CertificateStatus::Revoked
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edge_node_refuses_chain_from_other_network() {
+        let blockchain = Arc::new(Blockchain::with_genesis(
+            1,
+            Block::genesis_for_network("testnet"),
+        ));
+        let node = EdgeNode::new("RSU-TEST".to_string(), 16, blockchain);
+
+        let mainnet = Block::genesis_for_network("mainnet").hash;
+        assert!(matches!(
+            node.verify_network(&mainnet).await,
+            Err(EdgeError::WrongNetwork { .. })
+        ));
+        assert!(matches!(
+            node.authenticate_certificate("CERT-ANY").await,
+            Err(EdgeError::WrongNetwork { .. })
+        ));
+
+        let testnet = Block::genesis_for_network("testnet").hash;
+        assert!(node.verify_network(&testnet).await.is_ok());
+        assert_eq!(
+            node.authenticate_certificate("CERT-ANY").await,
+            Err(EdgeError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_off_chain_certificate_payload() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let store = InMemoryContentStore::new();
+
+        let tx = BlockchainTransaction::for_certificate_off_chain(
+            TransactionType::CertificateIssuance,
+            &cert,
+            DataEncoding::Bincode,
+            &store,
+        )
+        .await;
+
+        assert!(tx.data.is_empty());
+        assert!(tx.decode_certificate().is_err());
+        assert_eq!(
+            tx.decode_certificate_from(&store).await.unwrap().id,
+            cert.id
+        );
+        assert!(
+            tx.decode_certificate_from(&InMemoryContentStore::new())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edge_sheds_load_past_max_in_flight() {
+        const LIMIT: usize = 3;
+        let blockchain = Arc::new(Blockchain::new(1));
+        let edge = Arc::new(
+            EdgeNode::new("RSU-1".to_string(), 10, blockchain.clone()).with_max_in_flight(LIMIT),
+        );
+
+        // holding the chain keeps every cache-miss lookup in flight
+        let chain = blockchain.chain.write().await;
+        let held: Vec<_> = (0..LIMIT)
+            .map(|i| {
+                let edge = edge.clone();
+                tokio::spawn(
+                    async move { edge.authenticate_certificate(&format!("CERT-{}", i)).await },
+                )
+            })
+            .collect();
+        while edge.get_in_flight_count() < LIMIT {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(edge.get_shed_count(), 0);
+        assert_eq!(
+            edge.authenticate_certificate("CERT-NEXT").await,
+            Err(EdgeError::Overloaded { in_flight: LIMIT })
+        );
+        assert_eq!(edge.get_shed_count(), 1);
+
+        drop(chain);
+        for handle in held {
+            assert_eq!(handle.await.unwrap(), Err(EdgeError::NotFound));
+        }
+        assert_eq!(edge.get_in_flight_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_edge_authenticates_off_chain_certificate() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let store = Arc::new(InMemoryContentStore::new());
+        let blockchain = Arc::new(Blockchain::new(1));
+        blockchain
+            .add_transaction(
+                BlockchainTransaction::for_certificate_off_chain(
+                    TransactionType::CertificateIssuance,
+                    &cert,
+                    DataEncoding::Bincode,
+                    store.as_ref(),
+                )
+                .await,
+            )
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        let edge =
+            EdgeNode::new("RSU-1".to_string(), 10, blockchain.clone()).with_content_store(store);
+        edge.trust_ca("CA-TEST", ca.public_key().await).await;
+        assert_eq!(
+            edge.authenticate_certificate(&cert.id).await.unwrap().0,
+            CertificateStatus::Active
+        );
+
+        // without the payload the certificate cannot be verified
+        let blind = EdgeNode::new("RSU-2".to_string(), 10, blockchain);
+        blind.trust_ca("CA-TEST", ca.public_key().await).await;
+        assert_eq!(
+            blind.authenticate_certificate(&cert.id).await,
+            Err(EdgeError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_clock_drives_expiry_sweep() {
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm)
+            .await
+            .with_clock(clock.clone());
+
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert!(ca.deprecate_expired_certificates().await.is_empty());
+
+        clock.advance(chrono::Duration::days(366));
+        assert_eq!(
+            ca.deprecate_expired_certificates().await,
+            vec![cert.id.clone()]
+        );
+        assert_eq!(
+            ca.get_certificate(&cert.id).await.unwrap().status,
+            CertificateStatus::Deprecated
+        );
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_skips_repeat_lookups() {
+        let blockchain = Arc::new(Blockchain::new(1));
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain)
+            .with_negative_ttl(std::time::Duration::from_millis(50));
+
+        assert_eq!(
+            rsu.authenticate_certificate("CERT-UNKNOWN").await,
+            Err(EdgeError::NotFound)
+        );
+        assert_eq!(
+            rsu.authenticate_certificate("CERT-UNKNOWN").await,
+            Err(EdgeError::NotFound)
+        );
+        assert_eq!(rsu.get_negative_cache_hits(), 1);
+
+        tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+        assert!(rsu.authenticate_certificate("CERT-UNKNOWN").await.is_err());
+        assert_eq!(rsu.get_negative_cache_hits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hsm_returns_current_public_key() {
+        let hsm = HardwareSecurityModule::new();
+        assert!(hsm.get_public_key("CA-TEST").await.is_none());
+
+        let generated = hsm.generate_ca_keypair("CA-TEST").await;
+        assert_eq!(hsm.get_public_key("CA-TEST").await, Some(generated));
+
+        let (_, rotated, _) = hsm.rotate_ca_keypair("CA-TEST").await.unwrap();
+        assert_eq!(hsm.get_public_key("CA-TEST").await, Some(rotated));
+    }
+
+    #[tokio::test]
+    async fn test_parallel_mining_produces_valid_blocks() {
+        let blockchain =
+            Blockchain::new_with_consensus(Arc::new(ProofOfWork::new(2).with_workers(4)));
+        for i in 0..3 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("TX-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+            blockchain.mine_pending_transactions().await;
+        }
+
+        let chain = blockchain.chain.read().await;
+        assert_eq!(chain.len(), 4);
+        for pair in chain.windows(2) {
+            assert_eq!(pair[1].previous_hash, pair[0].hash);
+            assert!(pair[1].header().pow_hash().starts_with("00"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cert_ids_namespaced_by_issuing_ca() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca_a = CertificateAuthority::new("CA-A".to_string(), hsm.clone()).await;
+        let ca_b = CertificateAuthority::new("CA-B".to_string(), hsm).await;
+
+        let cert_a = ca_a
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let cert_b = ca_b
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert!(cert_a.id.starts_with("CA-A:CERT-VEH-1-"));
+        assert!(cert_b.id.starts_with("CA-B:CERT-VEH-1-"));
+        assert_eq!(Certificate::id_namespace(&cert_a.id), Some("CA-A"));
+
+        // CA-B issuing under CA-A's namespace is caught at authentication
+        let forged = ca_b
+            .issue_certificate_with_id(
+                "CA-A:CERT-FORGED".to_string(),
+                "VEH-2".to_string(),
+                test_public_key(),
+            )
+            .await
+            .unwrap();
+        let blockchain = Arc::new(Blockchain::new(1));
+        for cert in [&cert_a, &forged] {
+            blockchain
+                .add_transaction(BlockchainTransaction::for_certificate(
+                    TransactionType::CertificateIssuance,
+                    cert,
+                    DataEncoding::Json,
+                ))
+                .await;
+        }
+        blockchain.mine_pending_transactions().await;
+
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain);
+        // unverifiable until the node trusts the issuer
+        assert_eq!(
+            rsu.authenticate_certificate(&cert_a.id).await,
+            Err(EdgeError::UntrustedIssuer("CA-A".to_string()))
+        );
+        rsu.trust_ca("CA-A", ca_a.public_key().await).await;
+        assert!(rsu.authenticate_certificate(&cert_a.id).await.is_ok());
+        assert!(rsu.authenticate_certificate(&cert_b.id).await.is_err());
+        assert!(matches!(
+            rsu.authenticate_certificate(&forged.id).await,
+            Err(EdgeError::IssuerMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gateway_health_monitor_tracks_connection() {
+        let gateway = Arc::new(HyperledgerFabricGateway::new(
+            "test-channel".to_string(),
+            "test-chaincode".to_string(),
+        ));
+        gateway.connect().await.unwrap();
+        let mut changes = gateway.subscribe_connection();
+        let monitor = gateway
+            .clone()
+            .spawn_health_monitor(std::time::Duration::from_millis(10));
+
+        gateway.set_reachable(false);
+        changes.changed().await.unwrap();
+        assert!(!*changes.borrow_and_update());
+        assert!(!gateway.is_connected().await);
+
+        gateway.set_reachable(true);
+        changes.changed().await.unwrap();
+        assert!(*changes.borrow_and_update());
+        assert!(gateway.is_connected().await);
+
+        monitor.abort();
+    }
+
+    #[tokio::test]
+    async fn test_obu_verifies_peer_certificate_against_trusted_ca() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let peer = OnBoardUnit::new("VEH-PEER".to_string()).await;
+        let cert = ca
+            .issue_certificate(peer.vehicle_id.clone(), peer.public_key.clone())
+            .await
+            .unwrap();
+
+        let mut obu = OnBoardUnit::new("VEH-1".to_string()).await;
+        assert!(!obu.verify_peer_certificate(&cert));
+
+        obu.trust_ca("CA-TEST", vec![7u8; 32]);
+        assert!(!obu.verify_peer_certificate(&cert));
+
+        obu.trust_ca("CA-TEST", hsm.get_public_key("CA-TEST").await.unwrap());
+        assert!(obu.verify_peer_certificate(&cert));
+
+        let mut expired = cert.clone();
+        expired.expires_at = chrono::Utc::now() - chrono::Duration::days(1);
+        assert!(!obu.verify_peer_certificate(&expired));
+    }
+
+    #[tokio::test]
+    async fn test_equal_length_forks_converge_on_smaller_tip_hash() {
+        async fn node_with_fork(tx_id: &str) -> Blockchain {
+            let node = Blockchain::with_genesis(1, Block::genesis_for_network("test-net"));
+            node.add_transaction(BlockchainTransaction::new(
+                tx_id.to_string(),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+            node.mine_pending_transactions().await;
+            node
+        }
+
+        let node_a = node_with_fork("TX-A").await;
+        let node_b = node_with_fork("TX-B").await;
+        let fork_a = node_a.chain.read().await.clone();
+        let fork_b = node_b.chain.read().await.clone();
+        assert_ne!(fork_a.last().unwrap().hash, fork_b.last().unwrap().hash);
+
+        // exactly one of the two nodes switches, whichever order they hear in
+        let a_switched = node_a.consider_chain(fork_b.clone()).await;
+        let b_switched = node_b.consider_chain(fork_a.clone()).await;
+        assert_ne!(a_switched, b_switched);
+        assert_eq!(
+            node_a.chain.read().await.last().unwrap().hash,
+            node_b.chain.read().await.last().unwrap().hash
+        );
+
+        // presenting the winning fork again changes nothing
+        assert!(!node_a.consider_chain(fork_a).await);
+        assert!(!node_a.consider_chain(fork_b).await);
+    }
+
+    #[tokio::test]
+    async fn test_fast_revocation_blocks_never_carry_issuances() {
+        async fn mined_difficulties(pool: &[(&str, TransactionType)]) -> Vec<(u32, usize)> {
+            let blockchain =
+                Blockchain::new(2).with_difficulty_policy(DifficultyPolicy::FastRevocation {
+                    revocation_difficulty: 1,
+                });
+            for (tx_id, tx_type) in pool {
+                blockchain
+                    .add_transaction(BlockchainTransaction::new(
+                        tx_id.to_string(),
+                        tx_type.clone(),
+                        vec![],
+                    ))
+                    .await;
+            }
+            blockchain.mine_all_pending().await;
+            assert_eq!(blockchain.validate_chain().await, Ok(()));
+            let chain = blockchain.chain.read().await;
+            chain
+                .iter()
+                .skip(1)
+                .map(|b| (b.difficulty, b.transactions.len()))
+                .collect()
+        }
+
+        let revocations = [
+            ("CERT-1", TransactionType::CertificateRevocation),
+            ("CERT-2", TransactionType::CertificateRevocation),
+        ];
+        assert_eq!(mined_difficulties(&revocations).await, vec![(1, 2)]);
+
+        let issuances = [
+            ("CERT-3", TransactionType::CertificateIssuance),
+            ("CERT-4", TransactionType::CertificateIssuance),
+        ];
+        assert_eq!(mined_difficulties(&issuances).await, vec![(2, 2)]);
+
+        let mixed = [
+            ("CERT-3", TransactionType::CertificateIssuance),
+            ("CERT-1", TransactionType::CertificateRevocation),
+            ("CERT-4", TransactionType::CertificateIssuance),
+        ];
+        assert_eq!(mined_difficulties(&mixed).await, vec![(1, 1), (2, 2)]);
+
+        // a revocation waits for its own certificate's pending issuance
+        let same_cert = [
+            ("CERT-5", TransactionType::CertificateIssuance),
+            ("CERT-5", TransactionType::CertificateRevocation),
+        ];
+        assert_eq!(mined_difficulties(&same_cert).await, vec![(2, 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_longer_zero_difficulty_fork_is_rejected() {
+        let honest = Blockchain::with_genesis(2, Block::genesis_for_network("test-net"));
+        honest
+            .add_transaction(BlockchainTransaction::new(
+                "CERT-1".to_string(),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+        honest.mine_pending_transactions().await;
+
+        let attacker = Blockchain::with_genesis(0, Block::genesis_for_network("test-net"));
+        for i in 0..3 {
+            attacker
+                .add_transaction(BlockchainTransaction::new(
+                    format!("FAKE-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+            attacker.mine_pending_transactions().await;
+        }
+        let cheap_fork = attacker.chain.read().await.clone();
+        assert_eq!(cheap_fork.len(), 4);
+        assert!(cheap_fork.iter().skip(1).all(|b| b.difficulty == 0));
+
+        assert!(!honest.consider_chain(cheap_fork).await);
+        assert_eq!(honest.chain.read().await.len(), 2);
+        assert_eq!(
+            honest.certificate_status("CERT-1").await,
+            Some(CertificateStatus::Active)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_integrity_digest_distinguishes_forks_and_shorter_chains() {
+        async fn mine(node: &Blockchain, tx_id: &str) {
+            node.add_transaction(BlockchainTransaction::new(
+                tx_id.to_string(),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+            node.mine_pending_transactions().await;
+        }
+
+        let node_a = Blockchain::with_genesis(1, Block::genesis_for_network("test-net"));
+        mine(&node_a, "CERT-1").await;
+        let node_b = Blockchain::with_genesis(1, Block::genesis_for_network("test-net"));
+        assert!(
+            node_b
+                .consider_chain(node_a.chain.read().await.clone())
+                .await
+        );
+        assert_eq!(
+            node_a.integrity_digest().await,
+            node_b.integrity_digest().await
+        );
+
+        // a fork of the same length differs in its hash, not its tip index
+        let fork = Blockchain::with_genesis(1, Block::genesis_for_network("test-net"));
+        mine(&fork, "CERT-2").await;
+        let ours = node_a.integrity_digest().await;
+        let theirs = fork.integrity_digest().await;
+        assert_ne!(ours, theirs);
+        assert_eq!(ours.split_once(':').unwrap().0, "1");
+        assert_eq!(theirs.split_once(':').unwrap().0, "1");
+
+        // a shorter chain is told apart by its tip index
+        mine(&node_a, "CERT-3").await;
+        assert_eq!(
+            node_a.integrity_digest().await.split_once(':').unwrap().0,
+            "2"
+        );
+        assert_ne!(
+            node_a.integrity_digest().await,
+            node_b.integrity_digest().await
+        );
+    }
+
+    struct RegisteredVins(HashSet<String>);
+
+    #[async_trait::async_trait]
+    impl PreIssuanceChecker for RegisteredVins {
+        async fn check(&self, vehicle_id: &str) -> Result<(), String> {
+            if self.0.contains(vehicle_id) {
+                Ok(())
+            } else {
+                Err(format!("{} is not a registered vehicle", vehicle_id))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pre_issuance_checker_rejects_unregistered_vehicle() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let registry = RegisteredVins(HashSet::from(["VEH-OK".to_string()]));
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm)
+            .await
+            .with_pre_issuance_checker(Arc::new(registry));
+
+        assert!(
+            ca.issue_certificate("VEH-OK".to_string(), test_public_key())
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            ca.issue_certificate("VEH-UNKNOWN".to_string(), test_public_key())
+                .await,
+            Err(IssuanceError::Rejected(_))
+        ));
+        assert_eq!(ca.get_total_issued().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_revocation_log_is_signed_and_tamper_evident() {
+        let path = std::env::temp_dir().join(format!("revocations-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::remove_file(path).ok();
+
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone())
+            .await
+            .with_revocation_log(path)
+            .unwrap();
+        let ca_key = hsm.get_public_key("CA-TEST").await.unwrap();
+        for vehicle in ["VEH-1", "VEH-2"] {
+            let cert = ca
+                .issue_certificate(vehicle.to_string(), test_public_key())
+                .await
+                .unwrap();
+            ca.revoke_certificate(&cert.id, RevocationReason::Unspecified)
+                .await
+                .unwrap();
+        }
+        assert_eq!(verify_revocation_log(path, &ca_key), Ok(2));
+        assert!(verify_revocation_log(path, &[7u8; 32]).is_err());
+
+        let contents = std::fs::read_to_string(path).unwrap();
+        std::fs::write(path, contents.replacen("unspecified", "key compromise", 1)).unwrap();
+        assert!(verify_revocation_log(path, &ca_key).is_err());
+
+        // dropping the first entry breaks the sequence
+        let second_line = contents.lines().nth(1).unwrap();
+        std::fs::write(path, format!("{}\n", second_line)).unwrap();
+        assert!(verify_revocation_log(path, &ca_key).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_queue_wait_reported_apart_from_consensus_latency() {
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let blockchain = Blockchain::new(1).with_clock(clock.clone());
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "TX-1".to_string(),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+
+        clock.advance(chrono::Duration::milliseconds(500));
+        blockchain.mine_pending_transactions().await;
+
+        assert_eq!(blockchain.get_average_queue_wait_ms().await, 500.0);
+        assert_eq!(blockchain.get_average_consensus_latency_ms().await, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_mock_gateway_records_accepted_submissions() {
+        let gateway = MockGateway::new();
+        let tx = |id: &str| {
+            BlockchainTransaction::new(
+                id.to_string(),
+                TransactionType::CertificateRevocation,
+                vec![],
+            )
+        };
+
+        gateway.submit_transaction(&tx("TX-1")).await.unwrap();
+        gateway.set_failing(true);
+        assert!(gateway.submit_transaction(&tx("TX-2")).await.is_err());
+        assert!(!gateway.is_connected().await);
+        gateway.set_failing(false);
+        gateway.submit_transaction(&tx("TX-3")).await.unwrap();
+
+        let ids: Vec<String> = gateway.submitted().into_iter().map(|t| t.tx_id).collect();
+        assert_eq!(ids, vec!["TX-1".to_string(), "TX-3".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_certificates_still_deserialize_and_verify() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let ca_key = hsm.get_public_key("CA-TEST").await.unwrap();
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert_eq!(cert.version, CURRENT_CERT_VERSION);
+
+        // a certificate written before versioning: no version field, signed
+        // over the version-1 layout
+        let mut legacy = cert.clone();
+        legacy.version = 1;
+        legacy.signature = hsm
+            .sign_certificate("CA-TEST", &legacy.canonical_bytes())
+            .await
+            .unwrap();
+        let mut json = serde_json::to_value(&legacy).unwrap();
+        json.as_object_mut().unwrap().remove("version");
+
+        let restored: Certificate = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(restored.version, 1);
+        assert!(restored.verify(&ca_key));
+
+        // the signature binds the version it was made under
+        let mut downgraded = cert.clone();
+        downgraded.version = 1;
+        assert!(!downgraded.verify(&ca_key));
+
+        json["version"] = serde_json::json!(CURRENT_CERT_VERSION + 1);
+        assert!(serde_json::from_value::<Certificate>(json).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signed_broadcast_accepted_only_by_peers_trusting_the_ca() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let ca_key = hsm.get_public_key("CA-TEST").await.unwrap();
+        let network = V2VNetwork::new();
+
+        let sender = BBVPKIClientSDK::new("VEH-SENDER".to_string()).await;
+        assert!(sender.broadcast_signed(&network, b"hello").await.is_err());
+
+        let sender_key = sender.obu().lock().await.public_key.clone();
+        let cert = ca
+            .issue_certificate("VEH-SENDER".to_string(), sender_key)
+            .await
+            .unwrap();
+        sender.set_certificate(cert).await;
+        network.register_vehicle(sender.obu()).await;
+
+        let mut trusting = OnBoardUnit::new("VEH-TRUSTING".to_string()).await;
+        trusting.trust_ca("CA-TEST", ca_key);
+        let stranger = OnBoardUnit::new("VEH-STRANGER".to_string()).await;
+        network
+            .register_vehicle(Arc::new(tokio::sync::Mutex::new(trusting)))
+            .await;
+        network
+            .register_vehicle(Arc::new(tokio::sync::Mutex::new(stranger)))
+            .await;
+
+        assert_eq!(sender.broadcast_signed(&network, b"brake!").await, Ok(1));
+    }
+
+    #[tokio::test]
+    async fn test_status_index_follows_reorg_to_canonical_chain() {
+        async fn mine(node: &Blockchain, tx_id: &str, tx_type: TransactionType) {
+            node.add_transaction(BlockchainTransaction::new(
+                tx_id.to_string(),
+                tx_type,
+                vec![],
+            ))
+            .await;
+            node.mine_pending_transactions().await;
+        }
+
+        let node_a = Blockchain::with_genesis(1, Block::genesis_for_network("test-net"));
+        mine(&node_a, "CERT-1", TransactionType::CertificateIssuance).await;
+        let shared = node_a.chain.read().await.clone();
+
+        let node_b = Blockchain::with_genesis(1, Block::genesis_for_network("test-net"));
+        assert!(node_b.consider_chain(shared).await);
+        assert_eq!(
+            node_b.certificate_status("CERT-1").await,
+            Some(CertificateStatus::Active)
+        );
+
+        // branch A revokes; branch B grows longer without the revocation
+        mine(&node_a, "CERT-1", TransactionType::CertificateRevocation).await;
+        assert_eq!(
+            node_a.certificate_status("CERT-1").await,
+            Some(CertificateStatus::Revoked)
+        );
+        mine(&node_b, "CERT-2", TransactionType::CertificateIssuance).await;
+        mine(&node_b, "CERT-3", TransactionType::CertificateIssuance).await;
+
+        let branch_b = node_b.chain.read().await.clone();
+        assert!(node_a.consider_chain(branch_b).await);
+        assert_eq!(
+            node_a.certificate_status("CERT-1").await,
+            Some(CertificateStatus::Active)
+        );
+        assert_eq!(
+            node_a.certificate_status("CERT-3").await,
+            Some(CertificateStatus::Active)
+        );
+
+        // the incremental result matches a full rebuild
+        let incremental = node_a.certificate_status("CERT-1").await;
+        node_a.build_status_index().await;
+        assert_eq!(node_a.certificate_status("CERT-1").await, incremental);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_with_proof_is_independently_verifiable() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let blockchain = Arc::new(Blockchain::new(1));
+        for i in 0..5 {
+            let cert = ca
+                .issue_certificate_with_id(
+                    format!("CERT-{}", i),
+                    "VEH-1".to_string(),
+                    test_public_key(),
+                )
+                .await
+                .unwrap();
+            blockchain
+                .add_transaction(BlockchainTransaction::for_certificate(
+                    TransactionType::CertificateIssuance,
+                    &cert,
+                    DataEncoding::Json,
+                ))
+                .await;
+        }
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "CERT-3".to_string(),
+                TransactionType::CertificateRevocation,
+                vec![],
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+        let tip_hash = blockchain.chain.read().await.last().unwrap().hash.clone();
+
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain);
+        rsu.trust_ca("CA-TEST", ca.public_key().await).await;
+        let (status, proof) = rsu.authenticate_with_proof("CERT-3", false).await.unwrap();
+        assert_eq!(status, CertificateStatus::Revoked);
+        assert!(proof.verify());
+        assert_eq!(proof.header.hash, tip_hash);
+
+        let (status, proof) = rsu.authenticate_with_proof("CERT-4", false).await.unwrap();
+        assert_eq!(status, CertificateStatus::Active);
+        assert!(proof.verify());
+
+        let mut forged = proof.clone();
+        forged.transaction.tx_type = TransactionType::CertificateRevocation;
+        assert!(!forged.verify());
+
+        assert_eq!(
+            rsu.authenticate_with_proof("CERT-UNKNOWN", false)
+                .await
+                .err(),
+            Some(EdgeError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_with_proof_checks_the_certificate() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let clock = Arc::new(MockClock::new(
+            chrono::Utc::now() - chrono::Duration::days(400),
+        ));
+        let lapsed_ca = CertificateAuthority::new("CA-OLD".to_string(), hsm.clone())
+            .await
+            .with_clock(clock);
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let expired = lapsed_ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let mut forged = ca
+            .issue_certificate("VEH-2".to_string(), test_public_key())
+            .await
+            .unwrap();
+        forged.expires_at += chrono::Duration::days(3650);
+
+        let blockchain = Arc::new(Blockchain::new(1));
+        for cert in [&expired, &forged] {
+            blockchain
+                .add_transaction(BlockchainTransaction::for_certificate(
+                    TransactionType::CertificateIssuance,
+                    cert,
+                    DataEncoding::Json,
+                ))
+                .await;
+        }
+        blockchain.mine_pending_transactions().await;
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain);
+
+        assert_eq!(
+            rsu.authenticate_with_proof(&forged.id, false).await.err(),
+            Some(EdgeError::UntrustedIssuer("CA-TEST".to_string()))
+        );
+        rsu.trust_ca("CA-OLD", lapsed_ca.public_key().await).await;
+        rsu.trust_ca("CA-TEST", ca.public_key().await).await;
+
+        for redacted in [false, true] {
+            let (status, proof) = rsu
+                .authenticate_with_proof(&expired.id, redacted)
+                .await
+                .unwrap();
+            assert_eq!(status, CertificateStatus::Expired);
+            assert!(proof.verify());
+            assert_eq!(
+                rsu.authenticate_with_proof(&forged.id, redacted)
+                    .await
+                    .err(),
+                Some(EdgeError::InvalidSignature(forged.id.clone()))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revocation_is_idempotent_and_typed() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+
+        assert!(ca.revoke_with_reason(&cert.id, "test").await.is_ok());
+        assert!(ca.revoke_with_reason(&cert.id, "retry").await.is_ok());
+        assert_eq!(ca.revocation_snapshot().await, vec![cert.id.clone()]);
+        assert_eq!(
+            ca.revoke_with_reason("CERT-UNKNOWN", "test").await,
+            Err(RevocationError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lossy_links_are_reproducible_with_a_seed() {
+        async fn lossy_network(seed: u64) -> V2VNetwork {
+            let network = V2VNetwork::new()
+                .with_seed(seed)
+                .with_default_link(LinkQuality {
+                    loss_probability: 0.5,
+                    latency: std::time::Duration::ZERO,
+                });
+            for i in 0..10 {
+                let obu = OnBoardUnit::new(format!("VEH-{}", i)).await;
+                network
+                    .register_vehicle(Arc::new(tokio::sync::Mutex::new(obu)))
+                    .await;
+            }
+            network
+        }
+
+        let first = lossy_network(42).await;
+        let second = lossy_network(42).await;
+        let received = first
+            .broadcast_message("VEH-0", b"hi".to_vec(), None)
+            .await
+            .receivers;
+        assert_eq!(
+            received,
+            second
+                .broadcast_message("VEH-0", b"hi".to_vec(), None)
+                .await
+                .receivers
+        );
+        assert!(received.len() < 9);
+        assert!(!received.contains(&"VEH-0".to_string()));
+
+        // a perfect link override always delivers, after its latency
+        first.set_link(
+            "VEH-0",
+            "VEH-9",
+            LinkQuality {
+                loss_probability: 0.0,
+                latency: std::time::Duration::from_millis(20),
+            },
+        );
+        let start = std::time::Instant::now();
+        for _ in 0..5 {
+            let received = first
+                .broadcast_message("VEH-0", b"hi".to_vec(), None)
+                .await
+                .receivers;
+            assert_eq!(received.last(), Some(&"VEH-9".to_string()));
+        }
+        assert!(start.elapsed() >= std::time::Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_edge_serves_revoked_status_from_chain() {
+        let blockchain = Arc::new(Blockchain::new(1));
+        for tx_type in [
+            TransactionType::CertificateIssuance,
+            TransactionType::CertificateRevocation,
+        ] {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    "CERT-1".to_string(),
+                    tx_type,
+                    vec![],
+                ))
+                .await;
+            blockchain.mine_pending_transactions().await;
+        }
+
+        // no propagate_revocation: the node has to learn it from the chain
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain.clone());
+        assert_eq!(
+            rsu.authenticate_certificate("CERT-1").await.unwrap().0,
+            CertificateStatus::Revoked
+        );
+
+        // an active status with no certificate behind it fails closed
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "CERT-2".to_string(),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+        assert_eq!(
+            rsu.authenticate_certificate("CERT-2").await,
+            Err(EdgeError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chain_prunes_itself_past_max_length() {
+        let blockchain = Blockchain::new(1).with_max_chain_len(5);
+        assert_eq!(blockchain.max_chain_len(), Some(5));
+        for i in 0..10 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("TX-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+            blockchain.mine_pending_transactions().await;
+            assert!(blockchain.get_chain_length().await <= 5);
+        }
+
+        let chain = blockchain.chain.read().await;
+        assert_eq!(chain.first().unwrap().index, 0);
+        assert_eq!(chain.last().unwrap().index, 10);
+    }
+
+    /// Records the order vehicles reach the CA; holds the first one until released.
+    struct GatedChecker {
+        seen: std::sync::Mutex<Vec<String>>,
+        release: tokio::sync::Notify,
+    }
+
+    #[async_trait::async_trait]
+    impl PreIssuanceChecker for GatedChecker {
+        async fn check(&self, vehicle_id: &str) -> Result<(), String> {
+            let first = {
+                let mut seen = self.seen.lock().unwrap();
+                seen.push(vehicle_id.to_string());
+                seen.len() == 1
+            };
+            if first {
+                self.release.notified().await;
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_issuance_queue_serves_emergencies_first_and_bounds_depth() {
+        let checker = Arc::new(GatedChecker {
+            seen: std::sync::Mutex::new(Vec::new()),
+            release: tokio::sync::Notify::new(),
+        });
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm)
+            .await
+            .with_pre_issuance_checker(checker.clone());
+        let queue = Arc::new(IssuanceQueue::new(Arc::new(ca), 3, 1));
+
+        let submit = |vehicle: &str, priority| {
+            let queue = queue.clone();
+            let vehicle = vehicle.to_string();
+            tokio::spawn(async move { queue.submit(vehicle, test_public_key(), priority).await })
+        };
+        let blocker = submit("VEH-BLOCKER", IssuancePriority::Routine);
+        while checker.seen.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let waiting = vec![
+            submit("VEH-ROUTINE-1", IssuancePriority::Routine),
+            submit("VEH-ROUTINE-2", IssuancePriority::Routine),
+            submit("VEH-EMERGENCY", IssuancePriority::Emergency),
+        ];
+        while queue.depth() < 3 {
+            tokio::task::yield_now().await;
+        }
+        let overflow = queue
+            .submit(
+                "VEH-EXTRA".to_string(),
+                test_public_key(),
+                IssuancePriority::Emergency,
+            )
+            .await;
+        assert!(matches!(
+            overflow,
+            Err(IssuanceError::QueueFull { capacity: 3 })
+        ));
+
+        checker.release.notify_one();
+        assert!(blocker.await.unwrap().is_ok());
+        for handle in waiting {
+            assert!(handle.await.unwrap().is_ok());
+        }
+        assert_eq!(
+            *checker.seen.lock().unwrap(),
+            vec![
+                "VEH-BLOCKER",
+                "VEH-EMERGENCY",
+                "VEH-ROUTINE-1",
+                "VEH-ROUTINE-2"
+            ]
+        );
+        assert_eq!(queue.depth(), 0);
+    }
+
+    #[test]
+    fn test_performance_metrics_approx_eq() {
+        let mut baseline = PerformanceMetrics::new();
+        baseline.consensus_latency_ms = 120.0;
+        baseline.revocation_latency_ms = Some(15.0);
+        baseline.pruned_blocks = 4;
+
+        let mut rerun = baseline.clone();
+        assert_eq!(rerun, baseline);
+        rerun.consensus_latency_ms = 120.4;
+        assert!(rerun.approx_eq(&baseline, 0.5));
+        assert!(!rerun.approx_eq(&baseline, 0.1));
+
+        let mut failed = baseline.clone();
+        failed.revocation_latency_ms = None;
+        assert!(!failed.approx_eq(&baseline, 1000.0));
+
+        let mut pruned_more = baseline.clone();
+        pruned_more.pruned_blocks = 5;
+        assert!(!pruned_more.approx_eq(&baseline, 1000.0));
+
+        let round_trip: PerformanceMetrics =
+            serde_json::from_str(&serde_json::to_string(&baseline).unwrap()).unwrap();
+        assert!(round_trip.approx_eq(&baseline, 1e-9));
+    }
+
+    #[tokio::test]
+    async fn test_seeded_entropy_makes_key_generation_reproducible() {
+        let seeded = || -> EntropySource { Box::new(StdRng::seed_from_u64(7)) };
+
+        let hsm_a = HardwareSecurityModule::with_rng(seeded());
+        let hsm_b = HardwareSecurityModule::with_rng(seeded());
+        let key_a = hsm_a.generate_ca_keypair("CA-SEEDED").await;
+        assert_eq!(key_a, hsm_b.generate_ca_keypair("CA-SEEDED").await);
+        assert_ne!(
+            key_a,
+            HardwareSecurityModule::new()
+                .generate_ca_keypair("CA-SEEDED")
+                .await
+        );
+
+        let tpm_a = TrustedPlatformModule::with_rng(seeded());
+        let tpm_b = TrustedPlatformModule::with_rng(seeded());
+        assert_eq!(
+            tpm_a.generate_key_pair("k").await,
+            tpm_b.generate_key_pair("k").await
+        );
+        // same storage root key and nonce stream, so sealing is reproducible too
+        assert_eq!(
+            tpm_a.seal_key("k").await.unwrap(),
+            tpm_b.seal_key("k").await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_effective_status_reports_soft_expiry_band() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let cert = ca
+            .issue_certificate("VEH-001".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let band = chrono::Duration::days(30);
+        let at =
+            |days_before_expiry: i64| cert.expires_at - chrono::Duration::days(days_before_expiry);
+
+        assert_eq!(
+            cert.time_until_expiry_at(at(40)),
+            chrono::Duration::days(40)
+        );
+        assert_eq!(
+            cert.effective_status_at(at(40), band),
+            CertificateStatus::Active
+        );
+        assert_eq!(
+            cert.effective_status_at(at(10), band),
+            CertificateStatus::ExpiringSoon
+        );
+        assert!(cert.is_valid_at(at(10)));
+        assert!(cert.time_until_expiry_at(at(-1)) < chrono::Duration::zero());
+        assert_eq!(
+            cert.effective_status_at(at(-1), band),
+            CertificateStatus::Expired
+        );
+
+        let mut revoked = cert.clone();
+        revoked.status = CertificateStatus::Revoked;
+        assert_eq!(
+            revoked.effective_status_at(at(10), band),
+            CertificateStatus::Revoked
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mining_stats_track_blocks_and_transactions() {
+        let blockchain = Blockchain::new(1);
+        blockchain.mine_pending_transactions().await;
+        assert_eq!(blockchain.get_mining_stats().await, MiningStats::default());
+
+        for batch in [3, 1] {
+            for i in 0..batch {
+                blockchain
+                    .add_transaction(BlockchainTransaction::new(
+                        format!("TX-{}-{}", batch, i),
+                        TransactionType::CertificateIssuance,
+                        vec![],
+                    ))
+                    .await;
+            }
+            blockchain.mine_pending_transactions().await;
+        }
+
+        let stats = blockchain.get_mining_stats().await;
+        assert_eq!(stats.mine_calls, 2);
+        assert_eq!(stats.blocks_mined, 2);
+        assert_eq!(stats.transactions_mined, 4);
+        assert_eq!(stats.blocks_per_mine(), 1.0);
+        assert_eq!(stats.avg_txs_per_block(), 2.0);
+        assert_eq!(stats.avg_block_fill_ratio(), None);
+        assert_eq!(
+            MiningStats {
+                block_capacity: Some(8),
+                ..stats
+            }
+            .avg_block_fill_ratio(),
+            Some(0.25)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_edge_cache_export_import_warm_start() {
+        let blockchain = Arc::new(Blockchain::new(1));
+        let node = EdgeNode::new("RSU-OLD".to_string(), 10, blockchain.clone())
+            .with_cache_ttl(std::time::Duration::from_secs(60));
+        node.propagate_revocation("CERT-STALE").await;
+        tokio::time::advance(std::time::Duration::from_secs(61)).await;
+        node.propagate_revocation("CERT-A").await;
+        node.propagate_revocation("CERT-B").await;
+
+        let snapshot = node.export_cache().await;
+        assert_eq!(
+            snapshot,
+            vec![
+                ("CERT-A".to_string(), CertificateStatus::Revoked),
+                ("CERT-B".to_string(), CertificateStatus::Revoked),
+            ]
+        );
+
+        // survives a trip through disk
+        let restored: Vec<(String, CertificateStatus)> =
+            serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        let restarted = EdgeNode::new("RSU-NEW".to_string(), 10, blockchain)
+            .with_cache_ttl(std::time::Duration::from_secs(60));
+        restarted.import_cache(restored).await;
+
+        // imported entries answer from cache without touching the chain
+        let (status, _) = restarted.authenticate_certificate("CERT-A").await.unwrap();
+        assert_eq!(status, CertificateStatus::Revoked);
+        assert_eq!(restarted.get_cache_hit_rate().await, 100.0);
+
+        // each import starts a fresh TTL
+        tokio::time::advance(std::time::Duration::from_secs(30)).await;
+        assert!(restarted.authenticate_certificate("CERT-B").await.is_ok());
+        tokio::time::advance(std::time::Duration::from_secs(31)).await;
+        assert_eq!(
+            restarted.authenticate_certificate("CERT-B").await,
+            Err(EdgeError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_certificate_suspension_is_reversible() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let blockchain = Arc::new(Blockchain::new(1));
+        let node = EdgeNode::new("RSU-1".to_string(), 10, blockchain.clone());
+        let cert = ca
+            .issue_certificate("VEH-001".to_string(), test_public_key())
+            .await
+            .unwrap();
+
+        let record = |tx_type| async {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(cert.id.clone(), tx_type, vec![]))
+                .await;
+            blockchain.mine_pending_transactions().await;
+        };
+        record(TransactionType::CertificateIssuance).await;
+        assert!(ca.unsuspend_certificate(&cert.id).await.is_err());
+
+        ca.suspend_certificate(&cert.id).await.unwrap();
+        assert!(!ca.get_certificate(&cert.id).await.unwrap().is_valid());
+        assert!(ca.suspend_certificate(&cert.id).await.is_err());
+        record(TransactionType::CertificateSuspension).await;
+        assert_eq!(
+            blockchain.certificate_status(&cert.id).await,
+            Some(CertificateStatus::Suspended)
+        );
+        let (status, _) = node.authenticate_certificate(&cert.id).await.unwrap();
+        assert_eq!(status, CertificateStatus::Suspended);
+
+        ca.unsuspend_certificate(&cert.id).await.unwrap();
+        assert!(ca.get_certificate(&cert.id).await.unwrap().is_valid());
+        record(TransactionType::CertificateReinstatement).await;
+        assert_eq!(
+            blockchain.certificate_status(&cert.id).await,
+            Some(CertificateStatus::Active)
+        );
+        node.propagate_status(&cert.id, CertificateStatus::Active)
+            .await;
+        let (status, _) = node.authenticate_certificate(&cert.id).await.unwrap();
+        assert_eq!(status, CertificateStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_redacted_proof_hides_certificate_but_verifies() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let cert = ca
+            .issue_certificate("VEH-PRIVATE".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let blockchain = Arc::new(Blockchain::new(1));
+        blockchain
+            .add_transaction(BlockchainTransaction::for_certificate(
+                TransactionType::CertificateIssuance,
+                &cert,
+                DataEncoding::Json,
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain);
+        rsu.trust_ca("CA-TEST", ca.public_key().await).await;
+
+        let (_, full) = rsu.authenticate_with_proof(&cert.id, false).await.unwrap();
+        assert_eq!(
+            full.transaction.decode_certificate().unwrap().vehicle_id,
+            "VEH-PRIVATE"
+        );
+
+        let (status, redacted) = rsu.authenticate_with_proof(&cert.id, true).await.unwrap();
+        assert_eq!(status, CertificateStatus::Active);
+        assert!(redacted.verify());
+        assert!(redacted.transaction.data.is_empty());
+        assert!(redacted.transaction.decode_certificate().is_err());
+        assert_eq!(redacted.data_commitment, full.data_commitment);
+
+        let mut forged = redacted.clone();
+        forged.data_commitment = full.header.merkle_root.clone();
+        assert!(!forged.verify());
+    }
+
+    #[tokio::test]
+    async fn test_compact_keeps_latest_transaction_per_certificate() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let mut certs = HashMap::new();
+        for cert_id in ["CERT-1", "CERT-2", "CERT-3"] {
+            let cert = ca
+                .issue_certificate_with_id(
+                    cert_id.to_string(),
+                    "VEH-1".to_string(),
+                    test_public_key(),
+                )
+                .await
+                .unwrap();
+            certs.insert(cert_id, cert);
+        }
+
+        let blockchain = Arc::new(Blockchain::new(1));
+        let history = [
+            ("CERT-1", TransactionType::CertificateIssuance),
+            ("CERT-2", TransactionType::CertificateIssuance),
+            ("CERT-1", TransactionType::CertificateRevocation),
+            ("CERT-2", TransactionType::CertificateSuspension),
+            ("KEYROT-CA-1", TransactionType::KeyRotation),
+            ("CERT-3", TransactionType::CertificateIssuance),
+            ("CERT-2", TransactionType::CertificateReinstatement),
+        ];
+        for (cert_id, tx_type) in history {
+            let tx = if matches!(tx_type, TransactionType::CertificateIssuance) {
+                BlockchainTransaction::for_certificate(tx_type, &certs[cert_id], DataEncoding::Json)
+            } else {
+                BlockchainTransaction::new(cert_id.to_string(), tx_type, vec![0u8; 256])
+            };
+            blockchain.add_transaction(tx).await;
+            blockchain.mine_pending_transactions().await;
+        }
+        let old_tip = blockchain.chain.read().await.last().unwrap().hash.clone();
+
+        let report = blockchain.compact().await;
+        assert_eq!(report.blocks_removed, 7);
+        assert_eq!(report.transactions_before, 7);
+        // the suspension goes; each certificate's issuance stays so edge
+        // nodes can still verify it
+        assert_eq!(report.transactions_after, 6);
+        assert_eq!(report.compacted_tx_ids, vec!["CERT-2"]);
+        assert!(report.size_after_bytes < report.size_before_bytes);
+        assert_eq!(report.checkpoint_hash, old_tip);
+        assert_eq!(blockchain.get_chain_length().await, 2);
+
+        let expected = [
+            ("CERT-1", CertificateStatus::Revoked),
+            ("CERT-2", CertificateStatus::Active),
+            ("CERT-3", CertificateStatus::Active),
+        ];
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain.clone());
+        rsu.trust_ca("CA-TEST", ca.public_key().await).await;
+        for (cert_id, status) in expected {
+            assert_eq!(
+                blockchain.certificate_status(cert_id).await,
+                Some(status.clone())
+            );
+            assert_eq!(
+                rsu.authenticate_certificate(cert_id).await.unwrap().0,
+                status
+            );
+        }
+        assert!(blockchain.is_confirmed("KEYROT-CA-1").await);
+
+        // nothing left to drop
+        let again = blockchain.compact().await;
+        assert_eq!(again.blocks_removed, 0);
+        assert!(again.compacted_tx_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dyn_gateway_adapter_forwards_to_native_gateway() {
+        let mock = Arc::new(MockGateway::new());
+        // trait-object methods resolve without importing the adapter trait,
+        // which would make calls on concrete gateways ambiguous
+        let gateway: Arc<dyn bb_vpki::network::DynLedgerGateway> = mock.clone();
+        let tx = BlockchainTransaction::new(
+            "TX-DYN".to_string(),
+            TransactionType::CertificateIssuance,
+            vec![],
+        );
+
+        gateway.connect().await.unwrap();
+        gateway.submit_transaction(&tx).await.unwrap();
+        assert_eq!(mock.submitted()[0].tx_id, "TX-DYN");
+
+        mock.set_failing(true);
+        assert!(!gateway.is_connected().await);
+        assert!(gateway.submit_transaction(&tx).await.is_err());
+        assert_eq!(mock.submitted().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_task_registry_shuts_down_background_tasks() {
+        let tasks = Arc::new(TaskRegistry::new());
+        let blockchain = Arc::new(Blockchain::new(1).with_task_registry(tasks.clone()));
+        let node = Arc::new(
+            EdgeNode::new("RSU-1".to_string(), 16, blockchain.clone())
+                .with_task_registry(tasks.clone()),
+        );
+        let gateway = Arc::new(
+            HyperledgerFabricGateway::new("ch".to_string(), "cc".to_string())
+                .with_task_registry(tasks.clone()),
+        );
+
+        let miner = blockchain
+            .clone()
+            .spawn_miner(MineTrigger::Interval(std::time::Duration::from_secs(60)));
+        node.spawn_adaptive_sizing(AdaptiveCacheConfig::default());
+        gateway.spawn_health_monitor(std::time::Duration::from_secs(60));
+        let stubborn = tokio::spawn(std::future::pending::<()>());
+        tasks.register("stubborn", &stubborn);
+        assert_eq!(tasks.active_task_count(), 4);
+
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "TX-FLUSH".to_string(),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+        let aborted = tasks.shutdown(std::time::Duration::from_millis(200)).await;
+
+        // cooperative tasks stop at a clean point; the miner flushes on the way out
+        assert_eq!(aborted, vec!["stubborn"]);
+        assert!(tasks.is_cancelled());
+        assert_eq!(tasks.active_task_count(), 0);
+        assert!(!miner.is_running());
+        assert!(blockchain.is_confirmed("TX-FLUSH").await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_adaptive_cache_grows_and_shrinks_within_bounds() {
+        let config = AdaptiveCacheConfig {
+            target_hit_rate: 80.0,
+            shrink_margin: 10.0,
+            min_size: 2,
+            max_size: 4,
+            step: 1,
+            interval: std::time::Duration::from_secs(1),
+        };
+        let interval = config.interval;
+        let edge = Arc::new(EdgeNode::new(
+            "RSU-1".to_string(),
+            3,
+            Arc::new(Blockchain::new(1)),
+        ));
+        let sizing = edge.clone().spawn_adaptive_sizing(config);
+        tokio::task::yield_now().await;
+
+        // every lookup misses: the cache grows one step per interval, up to max_size
+        for round in 0..4 {
+            for i in 0..5 {
+                let _ = edge
+                    .authenticate_certificate(&format!("MISS-{}-{}", round, i))
+                    .await;
+            }
+            tokio::time::sleep(interval).await;
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(edge.get_cache_capacity().await, 4);
+
+        // every lookup hits: the cache shrinks back down to min_size
+        edge.import_cache(vec![("CERT-HOT".to_string(), CertificateStatus::Active)])
+            .await;
+        for _ in 0..4 {
+            for _ in 0..5 {
+                edge.authenticate_certificate("CERT-HOT").await.unwrap();
+            }
+            tokio::time::sleep(interval).await;
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(edge.get_cache_capacity().await, 2);
+
+        let events = edge.get_resize_events().await;
+        assert!(
+            events
+                .iter()
+                .any(|e| e.new_size > e.old_size && e.hit_rate < 80.0)
+        );
+        assert!(
+            events
+                .iter()
+                .any(|e| e.new_size < e.old_size && e.hit_rate > 90.0)
+        );
+        assert!(events.iter().all(|e| (2..=4).contains(&e.new_size)));
+        sizing.abort();
+    }
+
+    #[tokio::test]
+    async fn test_certificate_builder_defaults_and_required_fields() {
+        let hsm = HardwareSecurityModule::new();
+        let ca_key = hsm.generate_ca_keypair("CA-TEST").await;
+        let key = test_public_key();
+
+        let before = chrono::Utc::now();
+        let cert = CertificateBuilder::new()
+            .id("CERT-1")
+            .vehicle_id("VEH-1")
+            .public_key(key.clone())
+            .build(&hsm, "CA-TEST")
+            .await
+            .unwrap();
+        assert!(cert.issued_at >= before && cert.issued_at <= chrono::Utc::now());
+        assert_eq!(
+            cert.expires_at - cert.issued_at,
+            chrono::Duration::days(365)
+        );
+        assert_eq!(cert.issuer_ca, "CA-TEST");
+        assert_eq!(cert.status, CertificateStatus::Active);
+        assert_eq!(cert.version, CURRENT_CERT_VERSION);
+        assert_eq!(cert.certificate_hash, cert.compute_hash());
+        assert!(cert.verify(&ca_key));
+
+        let without_id = CertificateBuilder::new()
+            .vehicle_id("VEH-1")
+            .public_key(key.clone());
+        assert_eq!(
+            without_id.build(&hsm, "CA-TEST").await.err(),
+            Some("Certificate id not set".to_string())
+        );
+        let without_key = CertificateBuilder::new().id("CERT-1").vehicle_id("VEH-1");
+        assert_eq!(
+            without_key.build(&hsm, "CA-TEST").await.err(),
+            Some("Public key not set".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_certificate_json_validation_reports_the_bad_field() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let cert = ca
+            .issue_certificate("VEH-001".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let json = serde_json::to_value(&cert).unwrap();
+        let check = |edit: &dyn Fn(&mut serde_json::Value)| {
+            let mut value = json.clone();
+            edit(&mut value);
+            Certificate::from_json_validated(&serde_json::to_vec(&value).unwrap())
+        };
+
+        let parsed = check(&|_| {}).unwrap();
+        assert_eq!(parsed.id, cert.id);
+        assert!(parsed.verify(&hsm.get_public_key("CA-TEST").await.unwrap()));
+
+        assert!(matches!(
+            Certificate::from_json_validated(b"{not json"),
+            Err(ValidationError::Malformed(_))
+        ));
+        assert_eq!(
+            check(&|v| {
+                v.as_object_mut().unwrap().remove("expires_at");
+            })
+            .err(),
+            Some(ValidationError::MissingField("expires_at"))
+        );
+        assert_eq!(
+            check(&|v| v["vehicle_id"] = "".into()).err(),
+            Some(ValidationError::EmptyField("vehicle_id"))
+        );
+        assert_eq!(
+            check(&|v| v["public_key"] = serde_json::json!([1, 2, 3])).err(),
+            Some(ValidationError::PublicKeyLength(3))
+        );
+        assert!(matches!(
+            check(&|v| v["expires_at"] = v["issued_at"].clone()),
+            Err(ValidationError::ValidityPeriod { .. })
+        ));
+        assert_eq!(
+            check(&|v| v["status"] = "Frozen".into()).err(),
+            Some(ValidationError::UnknownStatus("\"Frozen\"".to_string()))
+        );
+        assert_eq!(
+            check(&|v| v["version"] = 99.into()).err(),
+            Some(ValidationError::UnsupportedVersion("99".to_string()))
+        );
+        assert_eq!(
+            check(&|v| v["id"] = "CA-TEST:CERT-FORGED".into()).err(),
+            Some(ValidationError::HashMismatch)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_transactions_expire_past_their_deadline() {
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let blockchain = Blockchain::new(1)
+            .with_clock(clock.clone())
+            .with_transaction_ttl(std::time::Duration::from_secs(10));
+        let tx = |id: &str| {
+            BlockchainTransaction::new(id.to_string(), TransactionType::CertificateIssuance, vec![])
+        };
+
+        blockchain.add_transaction(tx("TX-STALE")).await;
+        clock.advance(chrono::Duration::seconds(11));
+        blockchain.add_transaction(tx("TX-FRESH")).await;
+        blockchain.mine_pending_transactions().await;
+
+        assert!(!blockchain.is_confirmed("TX-STALE").await);
+        assert!(blockchain.is_confirmed("TX-FRESH").await);
+        assert_eq!(blockchain.expired_transaction_count().await, 1);
+        assert_eq!(
+            blockchain.expired_transactions().await,
+            vec!["TX-STALE".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ca_statistics_snapshot_aggregates_state() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm)
+            .await
+            .with_clock(clock.clone());
+
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let cert = ca
+                .issue_certificate("VEH-1".to_string(), test_public_key())
+                .await
+                .unwrap();
+            ids.push(cert.id);
+        }
+        ca.revoke_certificate(&ids[0], RevocationReason::Unspecified)
+            .await
+            .unwrap();
+        ca.suspend_certificate(&ids[1]).await.unwrap();
+
+        let stats = ca.statistics_snapshot().await;
+        assert_eq!(stats.total_issued, 3);
+        assert_eq!(stats.active, 1);
+        assert_eq!(stats.revoked, 1);
+        assert_eq!(stats.suspended, 1);
+        assert_eq!(stats.revocation_list_size, 1);
+        assert_eq!(stats.issuance_rate_per_sec, 3.0 / 60.0);
+        assert!(stats.hsm_operations >= 3);
+
+        clock.advance(chrono::Duration::seconds(61));
+        assert_eq!(ca.statistics_snapshot().await.issuance_rate_per_sec, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_exported_chain_round_trips_byte_identically() {
+        use bincode::Options;
+
+        let blockchain = Blockchain::new(1);
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "TX-1".to_string(),
+                TransactionType::CertificateIssuance,
+                vec![1, 2, 3],
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        let bytes = blockchain.export_chain().await;
+        let blocks: Vec<Block> = codec::decode(&bytes).unwrap();
+        assert_eq!(codec::encode(&blocks).unwrap(), bytes);
+        assert_eq!(blocks.len(), blockchain.get_chain_length().await);
+        assert!(blocks[1..].iter().all(|b| b.header().is_valid()));
+
+        // fixed little-endian layout, same as what bincode 1.x wrote before
+        assert_eq!(codec::encode(&1u64).unwrap(), 1u64.to_le_bytes());
+        assert_eq!(bincode::serialize(&blocks).unwrap(), bytes);
+        let big_endian = bincode::DefaultOptions::new()
+            .with_big_endian()
+            .with_fixint_encoding()
+            .serialize(&blocks)
+            .unwrap();
+        assert_ne!(big_endian, bytes);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(codec::decode::<Vec<Block>>(&trailing).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_issuance_rejects_weak_public_keys() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+
+        let mut identity = vec![0u8; 32];
+        identity[0] = 1;
+        for weak in [vec![0u8; 32], identity, vec![7u8; 16]] {
+            assert_eq!(
+                ca.issue_certificate("VEH-WEAK".to_string(), weak)
+                    .await
+                    .err(),
+                Some(IssuanceError::WeakKey("VEH-WEAK".to_string()))
+            );
+        }
+        assert_eq!(ca.get_total_issued().await, 0);
+
+        ca.issue_certificate("VEH-OK".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert_eq!(ca.get_total_issued().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_receive_message_enforces_replay_policy() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let mut sender = OnBoardUnit::new("VEH-SENDER".to_string()).await;
+        let cert = ca
+            .issue_certificate("VEH-SENDER".to_string(), sender.public_key.clone())
+            .await
+            .unwrap();
+        sender.set_certificate(cert);
+
+        let mut receiver = OnBoardUnit::new("VEH-RECEIVER".to_string()).await;
+        receiver.trust_ca("CA-TEST", hsm.get_public_key("CA-TEST").await.unwrap());
+        receiver.set_replay_policy(ReplayPolicy {
+            time_window: std::time::Duration::from_secs(2),
+            max_seq_gap: 3,
+        });
+
+        let first = sender.sign_envelope(b"brake!").await.unwrap();
+        assert_eq!(receiver.receive_message(&first), Ok(()));
+        assert_eq!(
+            receiver.receive_message(&first),
+            Err(MessageRejection::Replayed {
+                sequence: 0,
+                last_seen: 0
+            })
+        );
+
+        let mut restamped = sender.sign_envelope(b"brake!").await.unwrap();
+        restamped.sent_at += chrono::Duration::seconds(1);
+        assert_eq!(
+            receiver.receive_message(&restamped),
+            Err(MessageRejection::BadSignature)
+        );
+
+        let late = sender.sign_envelope(b"late").await.unwrap();
+        assert!(matches!(
+            receiver.receive_message_at(&late, late.sent_at + chrono::Duration::seconds(3)),
+            Err(MessageRejection::OutsideTimeWindow { .. })
+        ));
+
+        for _ in 0..2 {
+            sender.sign_envelope(b"lost").await.unwrap();
+        }
+        let after_gap = sender.sign_envelope(b"after gap").await.unwrap();
+        assert_eq!(
+            receiver.receive_message(&after_gap),
+            Err(MessageRejection::SequenceGap {
+                sequence: 5,
+                gap: 5,
+                max_gap: 3
+            })
+        );
+
+        let mut stranger = OnBoardUnit::new("VEH-STRANGER".to_string()).await;
+        assert_eq!(
+            stranger.receive_message(&first),
+            Err(MessageRejection::UntrustedCertificate)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_truncates_and_requeues_transactions() {
+        let blockchain = Blockchain::new(1);
+        for (tx_id, tx_type) in [
+            ("CERT-1", TransactionType::CertificateIssuance),
+            ("CERT-2", TransactionType::CertificateIssuance),
+            ("CERT-1", TransactionType::CertificateRevocation),
+        ] {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    tx_id.to_string(),
+                    tx_type,
+                    vec![],
+                ))
+                .await;
+            blockchain.mine_pending_transactions().await;
+        }
+        assert_eq!(
+            blockchain.certificate_status("CERT-1").await,
+            Some(CertificateStatus::Revoked)
+        );
+
+        assert_eq!(
+            blockchain.rollback_to(9).await.err(),
+            Some(RollbackError::BeyondTip { index: 9, tip: 3 })
+        );
+
+        let removed = blockchain.rollback_to(1).await.unwrap();
+        assert_eq!(
+            removed.iter().map(|b| b.index).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(blockchain.get_chain_length().await, 2);
+        assert_eq!(
+            blockchain.certificate_status("CERT-1").await,
+            Some(CertificateStatus::Active)
+        );
+        assert_eq!(blockchain.certificate_status("CERT-2").await, None);
+        assert_eq!(blockchain.get_pending_count().await, 2);
+
+        blockchain.mine_pending_transactions().await;
+        assert_eq!(
+            blockchain.certificate_status("CERT-1").await,
+            Some(CertificateStatus::Revoked)
+        );
+
+        blockchain.prune_old_blocks(1).await;
+        assert_eq!(
+            blockchain.rollback_to(0).await.err(),
+            Some(RollbackError::BelowRetained {
+                index: 0,
+                first_retained: 2
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_v2i_mutual_handshake_establishes_session() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let ca_key = hsm.get_public_key("CA-TEST").await.unwrap();
+        let blockchain = Arc::new(Blockchain::new(1));
+
+        let rsu_hsm = Arc::new(HardwareSecurityModule::new());
+        let rsu_key = rsu_hsm.generate_ca_keypair("RSU-1").await;
+        let rsu_cert = ca
+            .issue_certificate("RSU-1".to_string(), rsu_key)
+            .await
+            .unwrap();
+        let node = EdgeNode::new("RSU-1".to_string(), 16, blockchain.clone())
+            .with_identity(rsu_hsm, rsu_cert);
+        node.trust_ca("CA-TEST", ca_key.clone()).await;
+
+        let vehicle = BBVPKIClientSDK::new("VEH-1".to_string()).await;
+        let vehicle_key = vehicle.obu().lock().await.public_key.clone();
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), vehicle_key)
+            .await
+            .unwrap();
+        vehicle.set_certificate(cert.clone()).await;
+        assert!(matches!(
+            vehicle.connect_to_node(&node).await,
+            Err(HandshakeError::UntrustedCertificate(id)) if id == "RSU-1"
+        ));
+        vehicle.obu().lock().await.trust_ca("CA-TEST", ca_key);
+
+        blockchain
+            .add_transaction(BlockchainTransaction::for_certificate(
+                TransactionType::CertificateIssuance,
+                &cert,
+                DataEncoding::Json,
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        let session = vehicle.connect_to_node(&node).await.unwrap();
+        assert_eq!(node.session_peer(&session).await, Some("VEH-1".to_string()));
+        assert_eq!(
+            vehicle.send_to_node(&node, &session, &cert.id).await,
+            Ok(CertificateStatus::Active)
+        );
+        assert_eq!(
+            vehicle.send_to_node(&node, "forged", &cert.id).await,
+            Err(HandshakeError::InvalidSession("forged".to_string()))
+        );
+
+        // each challenge is good for one answer
+        let obu = vehicle.obu();
+        let obu = obu.lock().await;
+        let challenge = node.begin_handshake().await.unwrap();
+        let response = obu.respond_to_handshake(&challenge).await.unwrap();
+        node.complete_handshake(&response).await.unwrap();
+        assert!(matches!(
+            node.complete_handshake(&response).await,
+            Err(HandshakeError::UnknownChallenge)
+        ));
+
+        let anonymous = EdgeNode::new("RSU-2".to_string(), 16, blockchain);
+        assert!(matches!(
+            anonymous.begin_handshake().await,
+            Err(HandshakeError::NoIdentity(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_edge_node_trusts_cas_from_genesis_block() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let ca_key = hsm.get_public_key("CA-TEST").await.unwrap();
+        let config = GenesisConfig::new("test-net").with_trusted_ca("CA-TEST", ca_key.clone());
+
+        let genesis = config.build_block();
+        assert_eq!(genesis.hash, config.build_block().hash);
+        assert_ne!(genesis.hash, Block::genesis_for_network("test-net").hash);
+        let other_key = test_public_key();
+        assert_ne!(
+            genesis.hash,
+            GenesisConfig::new("test-net")
+                .with_trusted_ca("CA-TEST", other_key)
+                .build_block()
+                .hash
+        );
+
+        let blockchain = Arc::new(Blockchain::with_genesis(1, genesis.clone()));
+        let node = EdgeNode::new("RSU-TEST".to_string(), 16, blockchain);
+        node.verify_network(&genesis.hash).await.unwrap();
+        assert_eq!(node.load_genesis_trust_anchors().await, 1);
+        assert_eq!(node.get_ca_keys("CA-TEST").await, vec![ca_key]);
+
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert!(node.verify_certificate_signature(&cert).await);
+    }
+
+    #[test]
+    fn test_latency_histogram_buckets_and_exports() {
+        let mut metrics = PerformanceMetrics::new().with_histogram_buckets(vec![10.0, 1.0], vec![]);
+        for us in [0.5, 1.0, 3.0, 12.0, 40.0] {
+            metrics.authentication_histogram_us.observe(us);
+        }
+        let auth = &metrics.authentication_histogram_us;
+        assert_eq!(
+            auth.buckets(),
+            vec![(1.0, 2), (10.0, 1), (f64::INFINITY, 2)]
+        );
+        assert_eq!(auth.count(), 5);
+        assert_eq!(
+            auth.to_prometheus("auth_us"),
+            "# TYPE auth_us histogram\n\
+             auth_us_bucket{le=\"1\"} 2\n\
+             auth_us_bucket{le=\"10\"} 3\n\
+             auth_us_bucket{le=\"+Inf\"} 5\n\
+             auth_us_sum 56.5\n\
+             auth_us_count 5\n"
+        );
+
+        let json = serde_json::to_string(&metrics).unwrap();
+        let restored: PerformanceMetrics = serde_json::from_str(&json).unwrap();
+        assert!(restored.approx_eq(&metrics, 1e-9));
+        assert_eq!(restored.consensus_histogram_ms, Histogram::new(vec![]));
+    }
+
+    #[tokio::test]
+    async fn test_context_signatures_do_not_cross_protocols() {
+        let obu = OnBoardUnit::new("VEH-1".to_string()).await;
+        let key = obu.public_key.clone();
+        let message = b"brake!";
+
+        let signature = obu.sign_with_context(CONTEXT_V2V, message).await.unwrap();
+        assert!(obu.verify_with_context(CONTEXT_V2V, message, &signature, &key));
+        assert!(!obu.verify_with_context(CONTEXT_CSR, message, &signature, &key));
+        assert!(!obu.verify_message(message, &signature, &key));
+
+        // the length prefix keeps context and message from sliding into each other
+        let shifted = obu
+            .sign_with_context("bbvpki/v2", b"v-message")
+            .await
+            .unwrap();
+        assert!(!obu.verify_with_context("bbvpki/v2v-", b"message", &shifted, &key));
+    }
+
+    #[tokio::test]
+    async fn test_edge_authentication_checks_signature_and_expiry() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let blockchain = Arc::new(Blockchain::new(1));
+        let node = EdgeNode::new("RSU-TEST".to_string(), 16, blockchain.clone());
+        node.trust_ca("CA-TEST", hsm.get_public_key("CA-TEST").await.unwrap())
+            .await;
+
+        let past = chrono::Utc::now() - chrono::Duration::days(3 * 365);
+        let stale_ca = CertificateAuthority::new("CA-OLD".to_string(), hsm.clone())
+            .await
+            .with_clock(Arc::new(MockClock::new(past)));
+        node.trust_ca("CA-OLD", hsm.get_public_key("CA-OLD").await.unwrap())
+            .await;
+        let impostor = CertificateAuthority::new(
+            "CA-TEST".to_string(),
+            Arc::new(HardwareSecurityModule::new()),
+        )
+        .await;
+
+        let mut certs = Vec::new();
+        for (i, issuer) in [&ca, &stale_ca, &impostor, &ca].into_iter().enumerate() {
+            let cert = issuer
+                .issue_certificate(format!("VEH-{}", i), test_public_key())
+                .await
+                .unwrap();
+            blockchain
+                .add_transaction(BlockchainTransaction::for_certificate(
+                    TransactionType::CertificateIssuance,
+                    &cert,
+                    DataEncoding::Json,
+                ))
+                .await;
+            certs.push(cert.id);
+        }
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                certs[3].clone(),
+                TransactionType::CertificateRevocation,
+                vec![],
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        let status = |id: String| {
+            let node = &node;
+            async move { node.authenticate_certificate(&id).await.map(|(s, _)| s) }
+        };
+        assert_eq!(
+            status(certs[0].clone()).await,
+            Ok(CertificateStatus::Active)
+        );
+        assert_eq!(
+            status(certs[1].clone()).await,
+            Ok(CertificateStatus::Expired)
+        );
+        assert_eq!(
+            status(certs[2].clone()).await,
+            Err(EdgeError::InvalidSignature(certs[2].clone()))
+        );
+        assert_eq!(
+            status(certs[3].clone()).await,
+            Ok(CertificateStatus::Revoked)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_chain_reports_the_tampered_block() {
+        let blockchain = Blockchain::new(1);
+        for i in 0..4 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("TX-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![i],
+                ))
+                .await;
+            blockchain.mine_pending_transactions().await;
+        }
+        assert_eq!(blockchain.validate_chain().await, Ok(()));
+        blockchain.prune_old_blocks(2).await;
+        assert_eq!(blockchain.validate_chain().await, Ok(()));
+
+        let tamper = |edit: fn(&mut Block)| {
+            let blockchain = &blockchain;
+            async move {
+                let original = blockchain.chain.read().await.clone();
+                edit(blockchain.chain.write().await.last_mut().unwrap());
+                let result = blockchain.validate_chain().await;
+                *blockchain.chain.write().await = original;
+                result
+            }
+        };
+        assert_eq!(
+            tamper(|b| b.transactions[0].data = vec![9]).await,
+            Err(ChainError::MerkleRootMismatch { index: 4 })
+        );
+        assert_eq!(
+            tamper(|b| b.previous_hash = "forged".to_string()).await,
+            Err(ChainError::BrokenLink { index: 4 })
+        );
+        assert_eq!(
+            tamper(|b| b.nonce += 1).await,
+            Err(ChainError::HashMismatch { index: 4 })
+        );
+        // the seal time feeds consensus latency, so it is committed too
+        assert_eq!(
+            tamper(|b| b.timestamp += chrono::Duration::seconds(5)).await,
+            Err(ChainError::HashMismatch { index: 4 })
+        );
+        assert_eq!(
+            tamper(|b| b.difficulty = 0).await,
+            Err(ChainError::InsufficientDifficulty {
+                index: 4,
+                actual: 0,
+                required: 1
+            })
+        );
+        assert_eq!(blockchain.validate_chain().await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_ca_verifies_its_certificates_across_key_rotation() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let other = CertificateAuthority::new("CA-OTHER".to_string(), hsm).await;
+
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert!(!cert.signature.is_empty());
+        assert!(ca.verify_certificate(&cert).await);
+        assert!(!other.verify_certificate(&cert).await);
+
+        ca.rotate_key().await.unwrap();
+        assert!(ca.verify_certificate(&cert).await);
+
+        let mut tampered = cert.clone();
+        tampered.vehicle_id = "VEH-2".to_string();
+        assert!(!ca.verify_certificate(&tampered).await);
+    }
+
+    #[tokio::test]
+    async fn test_exported_ca_key_verifies_hsm_signatures() {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let public_key = ca.public_key().await;
+        assert_eq!(
+            Some(public_key.clone()),
+            hsm.get_public_key("CA-TEST").await
+        );
+        assert!(hsm.get_public_key("CA-MISSING").await.is_none());
+
+        let data = b"certificate bytes";
+        let signature = hsm.sign_certificate("CA-TEST", data).await.unwrap();
+        let key = VerifyingKey::from_bytes(&public_key.try_into().unwrap()).unwrap();
+        let signature = Signature::from_bytes(&signature.try_into().unwrap());
+        assert!(key.verify(data, &signature).is_ok());
+        assert!(key.verify(b"other bytes", &signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_renewal_supersedes_old_certificate() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let public_key = test_public_key();
+        let old = ca
+            .issue_certificate("VEH-1".to_string(), public_key.clone())
+            .await
+            .unwrap();
+
+        let renewed = ca.renew_certificate(&old.id, 30).await.unwrap();
+        assert_ne!(renewed.id, old.id);
+        assert_eq!(renewed.vehicle_id, "VEH-1");
+        assert_eq!(renewed.public_key, public_key);
+        assert_eq!(renewed.status, CertificateStatus::Active);
+        assert_eq!((renewed.expires_at - renewed.issued_at).num_days(), 30);
+        assert!(ca.verify_certificate(&renewed).await);
+        assert_eq!(
+            ca.get_certificate(&old.id).await.unwrap().status,
+            CertificateStatus::Deprecated
+        );
+        assert!(ca.renew_certificate(&old.id, 30).await.is_err());
+        assert!(ca.renew_certificate(&renewed.id, 0).await.is_err());
+
+        ca.revoke_certificate(&renewed.id, RevocationReason::Unspecified)
+            .await
+            .unwrap();
+        assert!(ca.renew_certificate(&renewed.id, 30).await.is_err());
+        assert!(ca.renew_certificate("CA-TEST:missing", 30).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hsm_signing_failure_issues_nothing() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert!(hsm.destroy_ca_keypair("CA-TEST").await);
+
+        assert!(matches!(
+            ca.issue_certificate("VEH-2".to_string(), test_public_key())
+                .await,
+            Err(IssuanceError::SigningFailed(_))
+        ));
+        assert!(matches!(
+            ca.renew_certificate(&cert.id, 30).await,
+            Err(IssuanceError::SigningFailed(_))
+        ));
+        assert_eq!(
+            ca.get_certificate(&cert.id).await.unwrap().status,
+            CertificateStatus::Active
+        );
+        assert!(matches!(
+            ca.issue_pseudonym_batch("VEH-1", vec![test_public_key()], 10)
+                .await,
+            Err(IssuanceError::SigningFailed(_))
+        ));
+        assert_eq!(ca.statistics_snapshot().await.total_issued, 1);
+        assert!(ca.resolve_pseudonym("anything").await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_edge_cache_entries_expire_after_ttl() {
+        let blockchain = Arc::new(Blockchain::new(1));
+        let default = EdgeNode::new("RSU-DEFAULT".to_string(), 10, blockchain.clone());
+        assert_eq!(default.cache_ttl(), DEFAULT_CACHE_TTL);
+
+        let node = EdgeNode::with_ttl(
+            "RSU-1".to_string(),
+            10,
+            std::time::Duration::from_secs(5),
+            blockchain,
+        );
+        node.propagate_revocation("CERT-X").await;
+        assert_eq!(
+            node.authenticate_certificate("CERT-X").await.unwrap().0,
+            CertificateStatus::Revoked
+        );
+
+        // past the TTL the entry is a miss and the chain (which never saw
+        // CERT-X) is consulted again
+        tokio::time::advance(std::time::Duration::from_secs(6)).await;
+        assert_eq!(
+            node.authenticate_certificate("CERT-X").await,
+            Err(EdgeError::NotFound)
+        );
+        assert_eq!(node.get_ttl_expired_count(), 1);
+        assert_eq!(node.get_cache_hit_rate().await, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_revocation_reaches_neighbor_caches() {
+        let blockchain = Arc::new(Blockchain::new(1));
+        let network = V2VNetwork::new();
+        let nodes: Vec<Arc<EdgeNode>> = (0..3)
+            .map(|i| Arc::new(EdgeNode::new(format!("RSU-{}", i), 10, blockchain.clone())))
+            .collect();
+        for node in &nodes {
+            network.register_edge_node(node.clone()).await;
+        }
+        nodes[0].add_neighboring_node("RSU-1".to_string()).await;
+        nodes[0].add_neighboring_node("RSU-1".to_string()).await;
+        nodes[0].add_neighboring_node("RSU-0".to_string()).await;
+        nodes[0].add_neighboring_node("RSU-GONE".to_string()).await;
+        assert_eq!(
+            nodes[0].neighbors().await,
+            vec!["RSU-1".to_string(), "RSU-GONE".to_string()]
+        );
+
+        nodes[0].propagate_revocation("CERT-1").await;
+        assert_eq!(
+            network
+                .propagate_revocation_to_neighbors("RSU-0", "CERT-1")
+                .await,
+            1
+        );
+        assert_eq!(
+            nodes[1].authenticate_certificate("CERT-1").await.unwrap().0,
+            CertificateStatus::Revoked
+        );
+        // RSU-2 is not a neighbor of RSU-0 and never heard of it
+        assert_eq!(
+            nodes[2].authenticate_certificate("CERT-1").await,
+            Err(EdgeError::NotFound)
+        );
+        assert_eq!(
+            network
+                .propagate_revocation_to_neighbors("RSU-MISSING", "CERT-1")
+                .await,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_reports_signature_verification() {
+        let network = V2VNetwork::new();
+        let mut signature = None;
+        for i in 0..4 {
+            let obu = OnBoardUnit::new(format!("VEH-{}", i)).await;
+            if i == 0 {
+                signature = Some(obu.sign_message(b"brake!").await.unwrap());
+            }
+            network
+                .register_vehicle(Arc::new(tokio::sync::Mutex::new(obu)))
+                .await;
+        }
+        let signature = signature.unwrap();
+
+        let unsigned = network
+            .broadcast_message("VEH-0", b"brake!".to_vec(), None)
+            .await;
+        assert_eq!(unsigned.delivered, 3);
+        assert_eq!((unsigned.verified_ok, unsigned.verify_failed), (0, 0));
+
+        let signed = network
+            .broadcast_message("VEH-0", b"brake!".to_vec(), Some(&signature))
+            .await;
+        assert_eq!((signed.delivered, signed.verified_ok), (3, 3));
+        assert_eq!(signed.verify_failed, 0);
+
+        let tampered = network
+            .broadcast_message("VEH-0", b"accelerate!".to_vec(), Some(&signature))
+            .await;
+        assert_eq!((tampered.verified_ok, tampered.verify_failed), (0, 3));
+
+        // an unregistered sender's key cannot be looked up
+        let spoofed = network
+            .broadcast_message("VEH-GHOST", b"brake!".to_vec(), Some(&signature))
+            .await;
+        assert_eq!((spoofed.delivered, spoofed.verify_failed), (4, 4));
+    }
+
+    #[tokio::test]
+    async fn test_revoking_vehicle_revokes_all_pseudonyms() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let mut obu = OnBoardUnit::new("VEH-SECRET".to_string()).await;
+        let keys = obu.generate_pseudonym_keys(5).await;
+
+        let batch = ca
+            .issue_pseudonym_batch("VEH-SECRET", keys.clone(), 10)
+            .await
+            .unwrap();
+        let other = ca
+            .issue_pseudonym_batch("VEH-OTHER", vec![test_public_key(), test_public_key()], 10)
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 5);
+        assert!(matches!(
+            ca.issue_pseudonym_batch("VEH-SECRET", keys.clone(), 0)
+                .await,
+            Err(IssuanceError::InvalidValidity(_))
+        ));
+        assert!(matches!(
+            ca.issue_pseudonym_batch("VEH-SECRET", vec![vec![0; 32]], 10)
+                .await,
+            Err(IssuanceError::WeakKey(_))
+        ));
+
+        // the vehicle signs under its pseudonyms; the CA's HSM never held those keys
+        for (cert, key) in batch.iter().zip(&keys) {
+            assert_eq!(&cert.public_key, key);
+            assert!(hsm.get_public_key(&cert.id).await.is_none());
+            let signature = obu.sign_with_pseudonym(cert, b"brake!").await.unwrap();
+            assert!(obu.verify_message(b"brake!", &signature, &cert.public_key));
+        }
+        assert!(obu.sign_with_pseudonym(&other[0], b"brake!").await.is_err());
+
+        let ids: HashSet<&str> = batch.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids.len(), 5);
+        for cert in &batch {
+            assert!(!cert.id.contains("VEH-SECRET"));
+            assert!(!cert.vehicle_id.contains("VEH-SECRET"));
+            assert_eq!((cert.expires_at - cert.issued_at).num_minutes(), 10);
+            assert!(ca.verify_certificate(cert).await);
+            assert_eq!(
+                ca.resolve_pseudonym(&cert.id).await.as_deref(),
+                Some("VEH-SECRET")
+            );
+        }
+
+        let mut revoked = ca
+            .revoke_pseudonyms("VEH-SECRET", "misbehavior")
+            .await
+            .unwrap();
+        revoked.sort();
+        let mut expected: Vec<String> = batch.iter().map(|c| c.id.clone()).collect();
+        expected.sort();
+        assert_eq!(revoked, expected);
+        for cert in &batch {
+            assert_eq!(
+                ca.get_certificate(&cert.id).await.unwrap().status,
+                CertificateStatus::Revoked
+            );
+        }
+        for cert in &other {
+            assert_eq!(
+                ca.get_certificate(&cert.id).await.unwrap().status,
+                CertificateStatus::Active
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crl_lists_revocations_and_warms_edge_cache() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm.clone()).await;
+        let compromised = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let retired = ca
+            .issue_certificate("VEH-2".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let revoked_at = ca
+            .revoke_certificate(&compromised.id, RevocationReason::KeyCompromise)
+            .await
+            .unwrap();
+        ca.revoke_with_reason(&retired.id, "superseded")
+            .await
+            .unwrap();
+
+        let crl = ca.generate_crl().await;
+        assert_eq!(crl.issuer, "CA-TEST");
+        assert!(crl.next_update > crl.this_update);
+        assert_eq!(crl.entries.len(), 2);
+        assert_eq!(crl.entries[0].cert_id, compromised.id);
+        assert_eq!(crl.entries[0].revocation_time, revoked_at);
+        assert_eq!(crl.entries[0].reason, RevocationReason::KeyCompromise);
+        assert_eq!(crl.entries[1].reason, RevocationReason::Superseded);
+
+        // survives the trip to a relying party
+        let fetched = Crl::from_json(&crl.to_json().unwrap()).unwrap();
+        assert_eq!(fetched, crl);
+
+        let node = EdgeNode::new("RSU-1".to_string(), 10, Arc::new(Blockchain::new(1)));
+        assert_eq!(
+            node.ingest_crl(&fetched).await,
+            Err(EdgeError::UntrustedCrl("CA-TEST".to_string()))
+        );
+        node.trust_ca("CA-TEST", ca.public_key().await).await;
+        let mut forged = fetched.clone();
+        forged.entries.pop();
+        assert!(node.ingest_crl(&forged).await.is_err());
+
+        assert_eq!(node.ingest_crl(&fetched).await, Ok(2));
+        assert_eq!(
+            node.authenticate_certificate(&compromised.id)
+                .await
+                .unwrap()
+                .0,
+            CertificateStatus::Revoked
+        );
+        assert_eq!(node.get_cache_hit_rate().await, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_crl_entries_for_another_ca_are_skipped() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca_a = CertificateAuthority::new("CA-A".to_string(), hsm.clone()).await;
+        let ca_b = CertificateAuthority::new("CA-B".to_string(), hsm.clone()).await;
+        let own = ca_a
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let foreign = ca_b
+            .issue_certificate("VEH-2".to_string(), test_public_key())
+            .await
+            .unwrap();
+        ca_a.revoke_certificate(&own.id, RevocationReason::Unspecified)
+            .await
+            .unwrap();
+
+        // CA-A signs a list that also claims to revoke CA-B's certificate
+        let mut crl = ca_a.generate_crl().await;
+        let mut cross = crl.entries[0].clone();
+        cross.cert_id = foreign.id.clone();
+        crl.entries.push(cross);
+        crl.signature = hsm
+            .sign_certificate("CA-A", &crl.signing_bytes())
+            .await
+            .unwrap();
+
+        let blockchain = Arc::new(Blockchain::new(1));
+        blockchain
+            .add_transaction(BlockchainTransaction::for_certificate(
+                TransactionType::CertificateIssuance,
+                &foreign,
+                DataEncoding::Json,
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+        let node = EdgeNode::new("RSU-1".to_string(), 10, blockchain);
+        node.trust_ca("CA-A", ca_a.public_key().await).await;
+        node.trust_ca("CA-B", ca_b.public_key().await).await;
+
+        assert_eq!(node.ingest_crl(&crl).await, Ok(1));
+        assert_eq!(
+            node.authenticate_certificate(&own.id).await.unwrap().0,
+            CertificateStatus::Revoked
+        );
+        assert_eq!(
+            node.authenticate_certificate(&foreign.id).await.unwrap().0,
+            CertificateStatus::Active
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edge_uses_ca_status_responder_on_cache_miss() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let ca = Arc::new(
+            CertificateAuthority::new("CA-TEST".to_string(), hsm)
+                .await
+                .with_clock(clock.clone()),
+        );
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        let revoked = ca
+            .issue_certificate("VEH-2".to_string(), test_public_key())
+            .await
+            .unwrap();
+        ca.revoke_certificate(&revoked.id, RevocationReason::KeyCompromise)
+            .await
+            .unwrap();
+
+        let response = ca.check_status(&cert.id).await;
+        assert_eq!(response.status, Some(CertificateStatus::Active));
+        assert_eq!(response.issuer, "CA-TEST");
+        assert_eq!(
+            ca.check_status(&revoked.id).await.status,
+            Some(CertificateStatus::Revoked)
+        );
+        assert_eq!(ca.check_status("CA-TEST:unknown").await.status, None);
+
+        // nothing is on the chain, so only the responder can answer
+        let node = EdgeNode::new("RSU-1".to_string(), 10, Arc::new(Blockchain::new(1)))
+            .with_status_responder(ca.clone());
+        assert_eq!(
+            node.authenticate_certificate(&revoked.id).await.unwrap().0,
+            CertificateStatus::Revoked
+        );
+        assert_eq!(
+            node.authenticate_certificate("CA-OTHER:CERT-1").await,
+            Err(EdgeError::NotFound)
+        );
+        assert_eq!(node.get_responder_queries(), 1);
+
+        clock.advance(chrono::Duration::days(366));
+        assert_eq!(
+            ca.check_status(&cert.id).await.status,
+            Some(CertificateStatus::Expired)
+        );
+        assert_eq!(
+            node.authenticate_certificate(&cert.id).await.unwrap().0,
+            CertificateStatus::Expired
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issuance_with_custom_validity() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm)
+            .await
+            .with_clock(clock.clone());
+
+        let default = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert_eq!((default.expires_at - default.issued_at).num_days(), 365);
+
+        let short = ca
+            .issue_certificate_with_validity(
+                "VEH-2".to_string(),
+                test_public_key(),
+                chrono::Duration::seconds(30),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            short.expires_at - short.issued_at,
+            chrono::Duration::seconds(30)
+        );
+        clock.advance(chrono::Duration::seconds(31));
+        assert_eq!(ca.deprecate_expired_certificates().await, vec![short.id]);
+
+        for validity in [chrono::Duration::zero(), chrono::Duration::seconds(-1)] {
+            let result = ca
+                .issue_certificate_with_validity("VEH-3".to_string(), test_public_key(), validity)
+                .await;
+            assert_eq!(result.err(), Some(IssuanceError::InvalidValidity(validity)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_difficulty_retargets_toward_block_time() {
+        async fn mine_blocks(chain: &Blockchain, count: usize) {
+            for i in 0..count {
+                chain
+                    .add_transaction(BlockchainTransaction::new(
+                        format!("TX-{}", i),
+                        TransactionType::CertificateIssuance,
+                        vec![],
+                    ))
+                    .await;
+                chain.mine_pending_transactions().await;
+            }
+        }
+
+        // blocks at difficulty 1 take far less than a minute
+        let fast = Blockchain::with_target_block_time(1, std::time::Duration::from_secs(60))
+            .with_retarget_interval(2);
+        mine_blocks(&fast, 1).await;
+        assert_eq!(fast.current_difficulty(), 1);
+        mine_blocks(&fast, 1).await;
+        assert_eq!(fast.current_difficulty(), 2);
+        mine_blocks(&fast, 2).await;
+        assert_eq!(fast.current_difficulty(), 3);
+        // earlier, easier blocks still validate after the raise
+        assert_eq!(fast.validate_chain().await, Ok(()));
+
+        // no block is faster than zero, so difficulty falls to the floor
+        let slow = Blockchain::with_target_block_time(2, std::time::Duration::ZERO)
+            .with_retarget_interval(1);
+        mine_blocks(&slow, 3).await;
+        assert_eq!(slow.current_difficulty(), 1);
+
+        let fixed = Blockchain::new(1);
+        mine_blocks(&fixed, 2 * bb_vpki::blockchain::DEFAULT_RETARGET_INTERVAL).await;
+        assert_eq!(fixed.current_difficulty(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_block_cap_carries_overflow_to_next_block() {
+        let blockchain = Blockchain::new(1).with_max_txs_per_block(3);
+        for i in 0..7 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("TX-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+        }
+
+        blockchain.mine_pending_transactions().await;
+        assert_eq!(blockchain.get_pending_count().await, 4);
+        {
+            let chain = blockchain.chain.read().await;
+            let ids: Vec<&str> = chain[1]
+                .transactions
+                .iter()
+                .map(|tx| tx.tx_id.as_str())
+                .collect();
+            assert_eq!(ids, ["TX-0", "TX-1", "TX-2"]);
+        }
+
+        assert_eq!(blockchain.mine_all_pending().await, 2);
+        assert_eq!(blockchain.get_pending_count().await, 0);
+        assert_eq!(blockchain.mine_all_pending().await, 0);
+
+        let stats = blockchain.get_mining_stats().await;
+        assert_eq!(stats.block_capacity, Some(3));
+        assert_eq!(stats.mine_calls, 2);
+        assert_eq!(stats.blocks_mined, 3);
+        assert_eq!(stats.blocks_per_mine(), 1.5);
+        assert_eq!(stats.avg_block_fill_ratio(), Some(7.0 / 9.0));
+    }
+
+    #[tokio::test]
+    async fn test_merkle_proof_for_transaction_in_block() {
+        let blockchain = Blockchain::new(1);
+        for i in 0..5 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("CERT-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![i as u8],
+                ))
+                .await;
+        }
+        blockchain.mine_pending_transactions().await;
+
+        let mut block = blockchain.chain.read().await[1].clone();
+        assert!(block.verify_merkle_root());
+
+        let proof = blockchain.generate_merkle_proof(1, "CERT-3").await.unwrap();
+        assert_eq!(proof.leaf_index, 3);
+        assert_eq!(proof.siblings.len(), 3);
+        assert_eq!(proof.header.merkle_root, block.merkle_root);
+        assert!(proof.verify());
+
+        assert!(
+            blockchain
+                .generate_merkle_proof(1, "CERT-9")
+                .await
+                .is_none()
+        );
+        assert!(
+            blockchain
+                .generate_merkle_proof(7, "CERT-3")
+                .await
+                .is_none()
+        );
+
+        block.transactions[2].data = vec![42];
+        assert!(!block.verify_merkle_root());
+    }
+
+    #[tokio::test]
+    async fn test_mine_reports_produced_block() {
+        let blockchain = Blockchain::new(1);
+        assert_eq!(blockchain.mine_pending_transactions().await, None);
+
+        for i in 0..2 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("TX-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+        }
+        let mined: MineResult = blockchain.mine_pending_transactions().await.unwrap();
+        assert_eq!(mined.block_index, 1);
+        assert_eq!(mined.tx_count, 2);
+        let block = blockchain.chain.read().await[1].clone();
+        assert_eq!(mined.nonce, block.nonce);
+        assert_eq!(blockchain.mine_pending_transactions().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_signature_policy_rejects_tampered_transactions() {
+        let key = ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng);
+        let stranger = ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng);
+        let blockchain =
+            Blockchain::new(1).with_transaction_policy(TransactionPolicy::RequireSignature {
+                trusted_keys: vec![key.verifying_key().to_bytes().to_vec()],
+            });
+        let tx = |id: &str| {
+            BlockchainTransaction::new(
+                id.to_string(),
+                TransactionType::CertificateRevocation,
+                b"payload".to_vec(),
+            )
+        };
+
+        let mut signed = tx("TX-SIGNED");
+        signed.sign(&key);
+        assert!(signed.verify(&key.verifying_key().to_bytes()));
+        assert_eq!(blockchain.try_add_transaction(signed.clone()).await, Ok(()));
+
+        let mut tampered = signed.clone();
+        tampered.tx_id = "TX-TAMPERED".to_string();
+        tampered.data = b"other payload".to_vec();
+        assert_eq!(
+            blockchain.try_add_transaction(tampered).await,
+            Err(TransactionRejection::InvalidSignature(
+                "TX-TAMPERED".to_string()
+            ))
+        );
+
+        let mut foreign = tx("TX-FOREIGN");
+        foreign.sign(&stranger);
+        assert!(blockchain.try_add_transaction(foreign).await.is_err());
+        assert_eq!(
+            blockchain.try_add_transaction(tx("TX-UNSIGNED")).await,
+            Err(TransactionRejection::Unsigned("TX-UNSIGNED".to_string()))
+        );
+
+        // the infallible entry point drops them too
+        blockchain.add_transaction(tx("TX-DROPPED")).await;
+        assert_eq!(blockchain.get_pending_count().await, 1);
+        assert_eq!(blockchain.get_rejected_transaction_count(), 4);
+
+        // without a policy anything goes
+        let open = Blockchain::new(1);
+        assert_eq!(open.try_add_transaction(tx("TX-UNSIGNED")).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_block_lookup_reports_pruned_blocks() {
+        let blockchain = Blockchain::new(1);
+        for (tx_id, tx_type) in [
+            ("CERT-1", TransactionType::CertificateIssuance),
+            ("CERT-2", TransactionType::CertificateIssuance),
+            ("CERT-1", TransactionType::CertificateRevocation),
+        ] {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    tx_id.to_string(),
+                    tx_type,
+                    vec![],
+                ))
+                .await;
+            blockchain.mine_pending_transactions().await;
+        }
+
+        let latest = blockchain.find_certificate_transaction("CERT-1").await;
+        assert!(matches!(
+            latest.map(|tx| tx.tx_type),
+            Some(TransactionType::CertificateRevocation)
+        ));
+        assert!(
+            blockchain
+                .find_certificate_transaction("CERT-9")
+                .await
+                .is_none()
+        );
+
+        let first_hash = match blockchain.get_block_by_index(1).await {
+            Some(BlockLookup::Found(block)) => block.hash,
+            other => panic!("expected block 1, got {other:?}"),
+        };
+        blockchain.prune_old_blocks(1).await;
+
+        match blockchain.get_block_by_index(1).await {
+            Some(BlockLookup::Pruned { index, hash }) => {
+                assert_eq!(index, 1);
+                assert_eq!(hash, first_hash);
+            }
+            other => panic!("expected pruned marker, got {other:?}"),
+        }
+        assert!(matches!(
+            blockchain.get_block_by_hash(&first_hash).await,
+            Some(BlockLookup::Pruned { index: 1, .. })
+        ));
+        assert!(matches!(
+            blockchain.get_block_by_index(3).await,
+            Some(BlockLookup::Found(block)) if block.index == 3
+        ));
+        assert!(blockchain.get_block_by_index(9).await.is_none());
+        assert!(blockchain.get_block_by_hash("deadbeef").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pbft_consensus_records_round_latency() {
+        let latency = Duration::from_millis(2);
+        let pbft = Arc::new(
+            PbftSimulated::new(4)
+                .with_quorum(3)
+                .with_message_latency(latency, latency),
+        );
+        assert_eq!(pbft.quorum(), 3);
+        let blockchain = Blockchain::new_with_consensus(pbft.clone());
+        assert_eq!(blockchain.current_difficulty(), 0);
+
+        for i in 0..2 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("CERT-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+            let mined = blockchain.mine_pending_transactions().await.unwrap();
+            assert_eq!(mined.nonce, i + 1);
+        }
+        assert_eq!(pbft.rounds(), 2);
+        assert_eq!(blockchain.validate_chain().await, Ok(()));
+
+        // pre-prepare, prepare and commit each wait one message latency
+        let latencies = blockchain.get_consensus_latencies_ms().await;
+        assert_eq!(latencies.len(), 2);
+        assert!(latencies.iter().all(|&ms| ms >= 6));
+
+        let mut block = Block::new(1, vec![], "prev".to_string(), 2, chrono::Utc::now());
+        let pow = ProofOfWork::new(2);
+        let proof = pow.seal_block(&mut block).await;
+        assert_eq!(
+            proof,
+            ConsensusProof::Work {
+                nonce: block.nonce,
+                difficulty: 2
+            }
+        );
+        assert!(block.header().pow_hash().starts_with("00"));
+        block.stamp_seal_time(chrono::Utc::now());
+        assert!(block.header().is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_archived_certificates_can_be_listed_and_restored() {
+        let blockchain = Blockchain::new(1);
+        for (cert_id, hash) in [("CERT-B", "hash-b"), ("CERT-A", "hash-a")] {
+            blockchain
+                .archive_deprecated_certificate(cert_id.to_string(), hash.to_string())
+                .await;
+        }
+
+        assert_eq!(
+            blockchain.list_archived_certificates().await,
+            vec![
+                ("CERT-A".to_string(), "hash-a".to_string()),
+                ("CERT-B".to_string(), "hash-b".to_string()),
+            ]
+        );
+        assert_eq!(
+            blockchain
+                .get_archived_certificate("CERT-A")
+                .await
+                .as_deref(),
+            Some("hash-a")
+        );
+        assert_eq!(blockchain.get_archived_certificate("CERT-C").await, None);
+
+        assert_eq!(
+            blockchain
+                .restore_archived_certificate("CERT-A")
+                .await
+                .as_deref(),
+            Some("hash-a")
+        );
+        assert_eq!(blockchain.get_archived_certificate("CERT-A").await, None);
+        assert_eq!(
+            blockchain.restore_archived_certificate("CERT-A").await,
+            None
+        );
+        assert_eq!(blockchain.list_archived_certificates().await.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache_stats_separate_lru_evictions_from_ttl_expirations() {
+        let blockchain = Arc::new(Blockchain::new(1));
+        let node = EdgeNode::with_ttl("RSU-1".to_string(), 2, Duration::from_secs(5), blockchain);
+        for cert_id in ["CERT-1", "CERT-2", "CERT-3"] {
+            node.propagate_status(cert_id, CertificateStatus::Active)
+                .await;
+        }
+        // refreshing a cached entry evicts nothing
+        node.propagate_status("CERT-3", CertificateStatus::Revoked)
+            .await;
+        assert!(node.authenticate_certificate("CERT-3").await.is_ok());
+
+        tokio::time::advance(Duration::from_secs(6)).await;
+        assert!(node.authenticate_certificate("CERT-2").await.is_err());
+
+        assert_eq!(
+            node.get_cache_stats().await,
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                lru_evictions: 1,
+                ttl_expirations: 1,
+                negative_hits: 0,
+                size: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hsm_signs_under_ed25519_and_secp256k1() {
+        let hsm = HardwareSecurityModule::new();
+        let message = b"certificate body";
+
+        for (ca_id, algorithm, key_len, sig_len) in [
+            ("CA-ED", KeyAlgorithm::Ed25519, 32, 64),
+            ("CA-K1", KeyAlgorithm::EcdsaSecp256k1, 33, 64),
+        ] {
+            let public_key = hsm
+                .generate_ca_keypair_with_algorithm(ca_id, algorithm)
+                .await;
+            assert_eq!(public_key.len(), key_len);
+            assert_eq!(hsm.key_algorithm(ca_id).await, Some(algorithm));
+
+            let signature = hsm.sign_certificate(ca_id, message).await.unwrap();
+            assert_eq!(signature.len(), sig_len);
+            assert!(algorithm.verify(&public_key, message, &signature));
+            assert!(!algorithm.verify(&public_key, b"tampered", &signature));
+        }
+
+        // the default stays ed25519, and keys never verify across algorithms
+        let ed_key = hsm.generate_ca_keypair("CA-DEFAULT").await;
+        assert_eq!(
+            hsm.key_algorithm("CA-DEFAULT").await,
+            Some(KeyAlgorithm::Ed25519)
+        );
+        let signature = hsm.sign_certificate("CA-DEFAULT", message).await.unwrap();
+        assert!(!KeyAlgorithm::EcdsaSecp256k1.verify(&ed_key, message, &signature));
+
+        // rotation keeps the algorithm
+        let (old_key, new_key, continuity) = hsm.rotate_ca_keypair("CA-K1").await.unwrap();
+        assert_eq!(hsm.get_public_key("CA-K1").await, Some(new_key.clone()));
+        assert_eq!(
+            hsm.key_algorithm("CA-K1").await,
+            Some(KeyAlgorithm::EcdsaSecp256k1)
+        );
+        assert!(KeyAlgorithm::EcdsaSecp256k1.verify(&old_key, &new_key, &continuity));
+    }
+
+    #[tokio::test]
+    async fn test_ecdsa_ca_verifies_end_to_end_at_edge() {
+        let path =
+            std::env::temp_dir().join(format!("revocations-k1-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::remove_file(path).ok();
+
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new_with_algorithm(
+            "CA-K1".to_string(),
+            hsm,
+            KeyAlgorithm::EcdsaSecp256k1,
+        )
+        .await
+        .with_revocation_log(path)
+        .unwrap();
+        let ca_key = ca.public_key().await;
+        assert_eq!(ca_key.len(), 33);
+
+        let blockchain = Arc::new(Blockchain::new(1));
+        let first = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert_eq!(first.signature_algorithm, KeyAlgorithm::EcdsaSecp256k1);
+        assert!(ca.verify_certificate(&first).await);
+
+        // a rotated key is learned through its continuity signature
+        let record = ca.rotate_key().await.unwrap();
+        assert!(record.verify());
+        let second = ca
+            .issue_certificate("VEH-2".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert!(ca.verify_certificate(&first).await);
+        assert!(ca.verify_certificate(&second).await);
+        for cert in [&first, &second] {
+            blockchain
+                .add_transaction(BlockchainTransaction::for_certificate(
+                    TransactionType::CertificateIssuance,
+                    cert,
+                    DataEncoding::Json,
+                ))
+                .await;
+        }
+        blockchain
+            .add_transaction(BlockchainTransaction::key_rotation(&record))
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        let edge = EdgeNode::new("RSU-1".to_string(), 10, blockchain);
+        edge.trust_ca("CA-K1", ca_key.clone()).await;
+        assert_eq!(edge.sync_ca_keyring().await, 1);
+        for cert in [&first, &second] {
+            assert_eq!(
+                edge.authenticate_certificate(&cert.id).await.unwrap().0,
+                CertificateStatus::Active
+            );
+        }
+
+        ca.revoke_certificate(&second.id, RevocationReason::Unspecified)
+            .await
+            .unwrap();
+        assert_eq!(verify_revocation_log(path, &record.new_public_key), Ok(1));
+        assert_eq!(edge.ingest_crl(&ca.generate_crl().await).await, Ok(1));
+        assert_eq!(
+            edge.authenticate_certificate(&second.id).await.unwrap().0,
+            CertificateStatus::Revoked
+        );
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_message_counts_per_sender_and_reset() {
+        let network = V2VNetwork::new();
+        for _ in 0..3 {
+            network
+                .broadcast_message("VEH-A", b"beacon".to_vec(), None)
+                .await;
+        }
+        network
+            .broadcast_message("VEH-B", b"beacon".to_vec(), None)
+            .await;
+
+        assert_eq!(network.get_message_count(), 4);
+        assert_eq!(
+            network.messages_by_sender().await,
+            HashMap::from([("VEH-A".to_string(), 3), ("VEH-B".to_string(), 1)])
+        );
+
+        network.reset_message_count();
+        assert_eq!(network.get_message_count(), 0);
+        assert!(network.messages_by_sender().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tpm_rate_limit_resets_with_the_window() {
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let tpm = TrustedPlatformModule::new().with_clock(clock.clone());
+        tpm.generate_key_pair_with_rate_limit("VEH-1", 3).await;
+
+        for _ in 0..3 {
+            assert!(tpm.sign_with_tpm("VEH-1", b"beacon").await.is_ok());
+        }
+        assert_eq!(
+            tpm.sign_with_tpm("VEH-1", b"beacon").await,
+            Err(TpmError::RateLimited {
+                key_id: "VEH-1".to_string(),
+                max_per_sec: 3,
+            })
+        );
+
+        clock.advance(chrono::Duration::milliseconds(999));
+        assert!(tpm.sign_with_tpm("VEH-1", b"beacon").await.is_err());
+        clock.advance(chrono::Duration::milliseconds(1));
+        assert!(tpm.sign_with_tpm("VEH-1", b"beacon").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_tpm_attestation_binds_key_to_genuine_tpm() {
+        let tpm = TrustedPlatformModule::new();
+        let public_key = tpm.generate_key_pair("VEH-1").await;
+        let attestation_key = tpm.get_attestation_key();
+
+        let attestation = tpm.attest("VEH-1").await.unwrap();
+        assert_eq!(attestation.public_key, public_key);
+        assert!(verify_attestation(&attestation, &attestation_key));
+
+        // fresh nonce every time
+        let again = tpm.attest("VEH-1").await.unwrap();
+        assert_ne!(again.nonce, attestation.nonce);
+
+        // another TPM's attestation key, or a swapped public key, fails
+        let other_tpm = TrustedPlatformModule::new();
+        assert!(!verify_attestation(
+            &attestation,
+            &other_tpm.get_attestation_key()
+        ));
+        let mut forged = attestation.clone();
+        forged.public_key = other_tpm.generate_key_pair("VEH-1").await;
+        assert!(!verify_attestation(&forged, &attestation_key));
+
+        assert!(tpm.attest("MISSING").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sdk_enrolls_through_csr_on_initialize() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = Arc::new(CertificateAuthority::new("CA-TEST".to_string(), hsm).await);
+
+        let mut sdk = BBVPKIClientSDK::new("VEH-1".to_string())
+            .await
+            .with_enrollment_ca(ca.clone());
+        sdk.initialize().await.unwrap();
+
+        let obu = sdk.obu();
+        let obu = obu.lock().await;
+        let cert = obu.get_certificate().cloned().unwrap();
+        assert_eq!(cert.vehicle_id, "VEH-1");
+        assert_eq!(cert.public_key, obu.public_key);
+        assert!(obu.verify_peer_certificate(&cert));
+        assert!(ca.get_certificate(&cert.id).await.is_some());
+
+        // a request for someone else's key fails proof of possession
+        let csr = obu.create_csr().await.unwrap();
+        assert!(csr.verify());
+        let stolen = CertificateSigningRequest {
+            vehicle_id: "VEH-2".to_string(),
+            ..csr
+        };
+        assert!(!stolen.verify());
+        assert_eq!(
+            ca.process_csr(stolen).await.err(),
+            Some(IssuanceError::InvalidProofOfPossession("VEH-2".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sdk_verifies_peer_messages_against_edge_status() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = Arc::new(CertificateAuthority::new("CA-TEST".to_string(), hsm).await);
+        let blockchain = Arc::new(Blockchain::new(1));
+        let edge = EdgeNode::new("RSU-1".to_string(), 16, blockchain.clone());
+
+        let mut sender = BBVPKIClientSDK::new("VEH-SENDER".to_string())
+            .await
+            .with_enrollment_ca(ca.clone());
+        let mut receiver = BBVPKIClientSDK::new("VEH-RECEIVER".to_string())
+            .await
+            .with_enrollment_ca(ca.clone());
+        sender.initialize().await.unwrap();
+        receiver.initialize().await.unwrap();
+        let cert = sender
+            .obu()
+            .lock()
+            .await
+            .get_certificate()
+            .cloned()
+            .unwrap();
+
+        let message = b"hazard ahead";
+        let signature = sender.sign_v2v_message(message).await.unwrap();
+
+        // not on chain yet, so the edge node cannot vouch for it
+        assert_eq!(
+            receiver
+                .verify_peer_message(message, &signature, &cert, &edge)
+                .await,
+            Err(PeerMessageError::Edge(EdgeError::NotFound))
+        );
+
+        blockchain
+            .add_transaction(BlockchainTransaction::for_certificate(
+                TransactionType::CertificateIssuance,
+                &cert,
+                DataEncoding::Json,
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+        let edge = EdgeNode::new("RSU-2".to_string(), 16, blockchain.clone());
+        edge.trust_ca("CA-TEST", ca.public_key().await).await;
+        assert_eq!(
+            receiver
+                .verify_peer_message(message, &signature, &cert, &edge)
+                .await,
+            Ok(())
+        );
+        assert_eq!(
+            receiver
+                .verify_peer_message(b"tampered", &signature, &cert, &edge)
+                .await,
+            Err(PeerMessageError::BadSignature)
+        );
+
+        let mut expired = cert.clone();
+        expired.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+        assert_eq!(
+            receiver
+                .verify_peer_message(message, &signature, &expired, &edge)
+                .await,
+            Err(PeerMessageError::Expired(cert.id.clone()))
+        );
+
+        edge.propagate_revocation(&cert.id).await;
+        assert_eq!(
+            receiver
+                .verify_peer_message(message, &signature, &cert, &edge)
+                .await,
+            Err(PeerMessageError::Revoked(cert.id.clone()))
+        );
+
+        // a stranger that trusts no CA rejects the certificate outright
+        let stranger = BBVPKIClientSDK::new("VEH-STRANGER".to_string()).await;
+        assert_eq!(
+            stranger
+                .verify_peer_message(message, &signature, &cert, &edge)
+                .await,
+            Err(PeerMessageError::UntrustedCertificate(cert.id.clone()))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_negative_cache_entry_re_resolves_after_ttl() {
+        let hsm = Arc::new(HardwareSecurityModule::new());
+        let ca = CertificateAuthority::new("CA-TEST".to_string(), hsm).await;
+        let blockchain = Arc::new(Blockchain::new(1));
+        let rsu = EdgeNode::new("RSU-1".to_string(), 16, blockchain.clone())
+            .with_negative_ttl(Duration::from_secs(2));
+        rsu.trust_ca("CA-TEST", ca.public_key().await).await;
+
+        let cert = ca
+            .issue_certificate("VEH-1".to_string(), test_public_key())
+            .await
+            .unwrap();
+        assert_eq!(
+            rsu.authenticate_certificate(&cert.id).await,
+            Err(EdgeError::NotFound)
+        );
+
+        // mined meanwhile, but the negative entry still answers until it expires
+        blockchain
+            .add_transaction(BlockchainTransaction::for_certificate(
+                TransactionType::CertificateIssuance,
+                &cert,
+                DataEncoding::Json,
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+        assert_eq!(
+            rsu.authenticate_certificate(&cert.id).await,
+            Err(EdgeError::NotFound)
+        );
+        let stats = rsu.get_cache_stats().await;
+        assert_eq!((stats.negative_hits, stats.misses), (1, 1));
+
+        tokio::time::advance(Duration::from_secs(3)).await;
+        assert_eq!(
+            rsu.authenticate_certificate(&cert.id).await.unwrap().0,
+            CertificateStatus::Active
+        );
+        let stats = rsu.get_cache_stats().await;
+        assert_eq!((stats.negative_hits, stats.misses, stats.size), (1, 2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_revocation_jumps_ahead_of_queued_issuances() {
+        let blockchain = Blockchain::new(1).with_max_txs_per_block(2);
+        for i in 0..3 {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("CERT-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+        }
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "CERT-9".to_string(),
+                TransactionType::CertificateRevocation,
+                vec![],
+            ))
+            .await;
+        blockchain
+            .add_transaction_with_priority(
+                BlockchainTransaction::new(
+                    "CERT-LOW".to_string(),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ),
+                TransactionPriority::Low,
+            )
+            .await;
+        // revoking a queued issuance pulls the issuance forward with it
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "CERT-2".to_string(),
+                TransactionType::CertificateRevocation,
+                vec![],
+            ))
+            .await;
+
+        blockchain.mine_all_pending().await;
+        let chain = blockchain.chain.read().await;
+        let blocks: Vec<Vec<String>> = chain[1..]
+            .iter()
+            .map(|block| {
+                block
+                    .transactions
+                    .iter()
+                    .map(|tx| format!("{}:{:?}", tx.tx_id, tx.tx_type))
+                    .collect()
+            })
+            .collect();
+        drop(chain);
+        assert_eq!(
+            blocks,
+            vec![
+                vec!["CERT-2:CertificateIssuance", "CERT-9:CertificateRevocation"],
+                vec!["CERT-2:CertificateRevocation", "CERT-0:CertificateIssuance"],
+                vec!["CERT-1:CertificateIssuance", "CERT-LOW:CertificateIssuance"],
+            ]
+        );
+        assert_eq!(
+            blockchain.certificate_status("CERT-2").await,
+            Some(CertificateStatus::Revoked)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_receive_block_events() {
+        let blockchain = Blockchain::new(1);
+        let mut events = blockchain.subscribe();
+        let mut lagging = blockchain.subscribe();
+
+        blockchain
+            .add_transaction(BlockchainTransaction::new(
+                "CERT-1".to_string(),
+                TransactionType::CertificateIssuance,
+                vec![],
+            ))
+            .await;
+        blockchain.mine_pending_transactions().await;
+
+        let tip = blockchain.chain.read().await.last().unwrap().clone();
+        assert_eq!(
+            events.recv().await.unwrap(),
+            BlockEvent {
+                index: 1,
+                hash: tip.hash,
+                tx_count: 1,
+                timestamp: tip.timestamp,
+            }
+        );
+
+        // a subscriber that falls behind skips ahead instead of stalling mining
+        for i in 0..BLOCK_EVENT_CAPACITY {
+            blockchain
+                .add_transaction(BlockchainTransaction::new(
+                    format!("CERT-LAG-{}", i),
+                    TransactionType::CertificateIssuance,
+                    vec![],
+                ))
+                .await;
+            blockchain.mine_pending_transactions().await;
+        }
+        assert!(matches!(
+            lagging.recv().await,
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(1))
+        ));
+        assert_eq!(lagging.recv().await.unwrap().index, 2);
+    }
+
+    #[test]
+    fn test_performance_metrics_json_round_trip() {
+        let mut metrics = PerformanceMetrics::new();
+        metrics.authentication_delay_us = 12.5;
+        metrics.revocation_latency_ms = Some(3.25);
+        metrics.cache_lru_evictions = 7;
+        metrics.authentication_histogram_us.observe(4.0);
+
+        let json = metrics.to_json_string();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], METRICS_SCHEMA_VERSION);
+        assert_eq!(value["authentication_delay_us"], 12.5);
+        assert_eq!(
+            PerformanceMetrics::from_json_str(&json),
+            Ok(metrics.clone())
+        );
+
+        let path = std::env::temp_dir().join(format!("bbvpki-metrics-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        metrics.save_to_json(path).unwrap();
+        let loaded = PerformanceMetrics::from_json_str(&std::fs::read_to_string(path).unwrap());
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded, Ok(metrics));
+    }
+
+    #[test]
+    fn test_performance_metrics_prometheus_exposition() {
+        let mut metrics = PerformanceMetrics::new();
+        metrics.timestamp = "2026-01-01T00:00:00+00:00".to_string();
+        metrics.authentication_delay_us = 12.5;
+        metrics.cache_hit_rate = 90.0;
+        metrics.revocation_latency_ms = None;
+        metrics.consensus_histogram_ms.observe(3.0);
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("# TYPE bbvpki_authentication_delay_us gauge\n"));
+        assert!(text.contains(
+            "bbvpki_authentication_delay_us{timestamp=\"2026-01-01T00:00:00+00:00\"} 12.5\n"
+        ));
+        assert!(text.contains(
+            "bbvpki_cache_hit_rate_percent{timestamp=\"2026-01-01T00:00:00+00:00\"} 90\n"
+        ));
+        assert!(!text.contains("bbvpki_revocation_latency_ms"));
+        assert!(text.contains("# TYPE bbvpki_consensus_latency_ms histogram\n"));
+        assert!(text.contains("bbvpki_consensus_latency_ms_count 1\n"));
+    }
+
+    #[test]
+    fn test_performance_metrics_load_from_csv_and_compare() {
+        let mut baseline = PerformanceMetrics::new();
+        baseline.authentication_delay_us = 100.0;
+        baseline.blockchain_tps = 200.0;
+        baseline.message_signing_time_us = 40.0;
+        baseline.blocks_mined = 10;
+        baseline.revocation_latency_ms = Some(5.0);
+
+        let mut current = baseline.clone();
+        current.authentication_delay_us = 150.0;
+        current.blockchain_tps = 190.0;
+        current.message_signing_time_us = 20.0;
+        current.blocks_mined = 30;
+        current.revocation_latency_ms = None;
+
+        let path = std::env::temp_dir().join(format!("bbvpki-baseline-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        baseline.save_to_csv(path).unwrap();
+        current.save_to_csv(path).unwrap();
+        let runs = PerformanceMetrics::load_from_csv(path);
+        std::fs::remove_file(path).unwrap();
+        let runs = runs.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].timestamp, baseline.timestamp);
+        assert_eq!(runs[0].revocation_latency_ms, Some(5.0));
+        assert_eq!(runs[1].revocation_latency_ms, None);
+        assert_eq!(runs[1].blocks_mined, 30);
+
+        let delta = runs[1].compare(&runs[0]);
+        assert_eq!(
+            delta.threshold_percent,
+            DEFAULT_REGRESSION_THRESHOLD_PERCENT
+        );
+        let change = |metric: &str| delta.changes.iter().find(|c| c.metric == metric).unwrap();
+        // 50% slower authentication regresses; a 5% TPS dip is within threshold
+        assert!(change("authentication_delay_us").regressed);
+        assert_eq!(change("authentication_delay_us").percent_change, Some(50.0));
+        assert!(!change("blockchain_tps").regressed);
+        assert!(!change("message_signing_time_us").regressed);
+        assert_eq!(change("blocks_mined").better, None);
+        assert!(
+            !delta
+                .changes
+                .iter()
+                .any(|c| c.metric == "revocation_latency_ms")
+        );
+        assert_eq!(delta.exit_code(), 1);
+        assert!(
+            delta
+                .summary()
+                .contains("REGRESSION authentication_delay_us")
+        );
+        assert!(delta.summary().starts_with("REGRESSION"));
+        assert!(delta.summary().contains("FAIL: 1 metric(s)"));
+
+        let strict = runs[1].compare_with_threshold(&runs[0], 1.0);
+        assert!(
+            strict
+                .changes
+                .iter()
+                .any(|c| c.metric == "blockchain_tps" && c.regressed)
+        );
+        assert_eq!(change("blockchain_tps").better, Some(Better::Higher));
+        assert_eq!(runs[0].compare(&runs[0]).exit_code(), 0);
+    }
+
+    #[test]
+    fn test_performance_metrics_load_from_csv_with_fewer_columns() {
+        let path = std::env::temp_dir().join(format!("bbvpki-old-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "timestamp,authentication_delay_us,blockchain_tps\n2025-01-01T00:00:00+00:00,12.5,100\n",
+        )
+        .unwrap();
+        let mut current = PerformanceMetrics::new();
+        current.authentication_delay_us = 13.0;
+        current.save_to_csv(path).unwrap();
+        let runs = PerformanceMetrics::load_from_csv(path);
+        std::fs::remove_file(path).unwrap();
+        let runs = runs.unwrap();
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].timestamp, "2025-01-01T00:00:00+00:00");
+        assert_eq!(runs[0].authentication_delay_us, 12.5);
+        assert_eq!(runs[0].blockchain_tps, 100.0);
+        assert_eq!(runs[0].consensus_p99_ms, 0.0);
+        // appended under the old header, read with the current layout
+        assert_eq!(runs[1].authentication_delay_us, 13.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_miners_never_fork_the_tip() {
+        let engine = PbftSimulated::new(4)
+            .with_message_latency(Duration::from_millis(2), Duration::from_millis(4));
+        let blockchain = Arc::new(Blockchain::new_with_consensus(Arc::new(engine)));
+        let miner = blockchain
+            .clone()
+            .spawn_miner(MineTrigger::Interval(Duration::from_millis(1)));
+
+        let mut handles = Vec::new();
+        for i in 0..8 {
+            let blockchain = blockchain.clone();
+            handles.push(tokio::spawn(async move {
+                blockchain
+                    .add_transaction(BlockchainTransaction::new(
+                        format!("TX-{}", i),
+                        TransactionType::CertificateIssuance,
+                        vec![],
+                    ))
+                    .await;
+                blockchain.mine_pending_transactions().await
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        miner.shutdown().await;
+
+        let chain = blockchain.chain.read().await;
+        for (expected, block) in chain.iter().enumerate() {
+            assert_eq!(block.index, expected as u64);
+        }
+        for pair in chain.windows(2) {
+            assert_eq!(pair[1].previous_hash, pair[0].hash);
+        }
+        let mined: usize = chain.iter().map(|b| b.transactions.len()).sum();
+        assert_eq!(mined, 8);
+        drop(chain);
+        assert!(blockchain.validate_chain().await.is_ok());
+    }
 }